@@ -5,6 +5,24 @@ pub(crate) struct ConnectionID {
     len: usize,
 }
 
+impl PartialEq for ConnectionID {
+    fn eq(&self, other: &Self) -> bool {
+        self.get_id() == other.get_id()
+    }
+}
+
+impl Eq for ConnectionID {}
+
+impl std::fmt::Debug for ConnectionID {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConnectionID(")?;
+        for b in self.get_id() {
+            write!(f, "{:02x}", b)?;
+        }
+        write!(f, ")")
+    }
+}
+
 impl ConnectionID {
     pub(crate) fn new() -> Self {
         Self {
@@ -19,10 +37,10 @@ impl ConnectionID {
     /// `connection_id` - ConnectionID
     pub(crate) fn set_id(&mut self, connection_id: &[u8]) {
         let len = connection_id.len();
-        assert!(1 <= len && len <= 20);
+        assert!((1..=20).contains(&len));
 
         self.len = len;
-        self.connection_id.copy_from_slice(connection_id);
+        self.connection_id[..len].copy_from_slice(connection_id);
     }
 
     /// 获取 Connection ID
@@ -30,6 +48,6 @@ impl ConnectionID {
     /// # Returns
     /// 返回 Connection ID
     pub(crate) fn get_id(&self) -> &[u8] {
-        return &self.connection_id[..self.len];
+        &self.connection_id[..self.len]
     }
 }
@@ -0,0 +1,49 @@
+/// IP 头部中的 ECN 码点
+///
+/// 位于 IPv4 TOS 字段或 IPv6 Traffic Class 字段的低 2 位.
+/// 套接字层通过 IP_TOS / IPV6_TCLASS 设置发送数据包的码点,
+/// 并通过 IP_RECVTOS / IPV6_RECVTCLASS 读取接收数据包的码点.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EcnCodepoint {
+    /// Not-ECT, 不支持 ECN
+    NotEct,
+
+    /// ECT(1)
+    Ect1,
+
+    /// ECT(0)
+    Ect0,
+
+    /// CE, 路径上发生了拥塞
+    Ce,
+}
+
+impl EcnCodepoint {
+    /// 由 TOS 字段解析 ECN 码点
+    ///
+    /// # Arguments
+    /// `tos` - IPv4 TOS 字段或 IPv6 Traffic Class 字段
+    /// # Returns
+    /// 返回 ECN 码点
+    pub const fn from_tos(tos: u8) -> Self {
+        match tos & 0b11 {
+            0b01 => EcnCodepoint::Ect1,
+            0b10 => EcnCodepoint::Ect0,
+            0b11 => EcnCodepoint::Ce,
+            _ => EcnCodepoint::NotEct,
+        }
+    }
+
+    /// 获取 ECN 码点在 TOS 字段中的取值
+    ///
+    /// # Returns
+    /// 返回 TOS 字段的低 2 位
+    pub const fn to_tos(&self) -> u8 {
+        match self {
+            EcnCodepoint::NotEct => 0b00,
+            EcnCodepoint::Ect1 => 0b01,
+            EcnCodepoint::Ect0 => 0b10,
+            EcnCodepoint::Ce => 0b11,
+        }
+    }
+}
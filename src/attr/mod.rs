@@ -1,11 +1,25 @@
 mod conn_id;
+mod ecn;
 mod packet_number;
 mod serialize;
 mod stream;
+mod transport_params;
 mod version;
 
 pub(crate) use conn_id::*;
 pub(crate) use packet_number::*;
 pub(crate) use serialize::*;
 pub(crate) use stream::*;
+pub(crate) use transport_params::{
+    DEFAULT_ACK_DELAY_EXPONENT, DEFAULT_ACTIVE_CONNECTION_ID_LIMIT, DEFAULT_MAX_ACK_DELAY,
+};
 pub(crate) use version::*;
+
+pub use ecn::EcnCodepoint;
+pub use stream::{Side, StreamDirection, StreamId};
+pub use transport_params::TransportParameters;
+
+#[cfg(test)]
+mod stream_test;
+#[cfg(test)]
+mod transport_params_test;
@@ -87,3 +87,91 @@ impl FixedDeserializer for PacketNumber {
         Ok(())
     }
 }
+
+/// 按 RFC 9000 附录 A.2 选择 Packet Number 的编码长度
+///
+/// 编码长度需要能表示两倍于尚未被确认的数据包编号范围, 使接收方能够还原完整的编号.
+///
+/// # Arguments
+/// `packet_number` - 待发送的数据包编号
+/// `largest_acked` - 该编号空间中被确认的最大数据包编号
+/// # Returns
+/// 返回 1 到 4 字节的编码长度
+pub(crate) fn encode_packet_number_len(
+    packet_number: PacketNumber,
+    largest_acked: Option<PacketNumber>,
+) -> usize {
+    let unacked = match largest_acked {
+        Some(largest_acked) => packet_number.saturating_sub(largest_acked),
+        None => packet_number + 1,
+    };
+
+    let bits = 64 - (unacked << 1).leading_zeros() as usize;
+    bits.div_ceil(8).clamp(1, 4)
+}
+
+/// 按 RFC 9000 附录 A.3 由截断的 Packet Number 还原完整的数据包编号
+///
+/// # Arguments
+/// `truncated` - 数据包头中截断的 Packet Number
+/// `packet_number_len` - Packet Number 的编码长度
+/// `largest` - 该编号空间中成功处理的最大数据包编号
+/// # Returns
+/// 返回与期望编号最接近的完整数据包编号
+pub(crate) fn decode_packet_number(
+    truncated: PacketNumber,
+    packet_number_len: usize,
+    largest: Option<PacketNumber>,
+) -> PacketNumber {
+    let expected = largest.map_or(0, |largest| largest + 1);
+    let window = 1u64 << (packet_number_len * 8);
+    let half_window = window / 2;
+    let mask = window - 1;
+
+    let candidate = (expected & !mask) | truncated;
+    if candidate + half_window <= expected && candidate < (1 << 62) - window {
+        candidate + window
+    } else if candidate > expected + half_window && candidate >= window {
+        candidate - window
+    } else {
+        candidate
+    }
+}
+
+/// 数据包编号空间
+///
+/// QUIC 中的数据包编号被划分为三个独立的空间, 每个空间内的数据包编号独立递增,
+/// 且 ACK 帧只确认与其所在数据包相同编号空间内的数据包.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum PacketNumberSpace {
+    /// 所有 Initial 数据包
+    Initial,
+
+    /// 所有 Handshake 数据包
+    Handshake,
+
+    /// 所有 0-RTT 与 1-RTT 数据包
+    ApplicationData,
+}
+
+impl PacketNumberSpace {
+    /// 按照握手推进顺序排列的全部数据包编号空间
+    pub(crate) const ALL: [PacketNumberSpace; 3] = [
+        PacketNumberSpace::Initial,
+        PacketNumberSpace::Handshake,
+        PacketNumberSpace::ApplicationData,
+    ];
+
+    /// 获取数据包编号空间对应的下标, 用于按空间存储状态
+    ///
+    /// # Returns
+    /// 返回编号空间的下标
+    #[inline(always)]
+    pub(crate) const fn index(&self) -> usize {
+        match self {
+            PacketNumberSpace::Initial => 0,
+            PacketNumberSpace::Handshake => 1,
+            PacketNumberSpace::ApplicationData => 2,
+        }
+    }
+}
@@ -1,11 +1,231 @@
-pub(crate) type StreamID = u64;
+use std::fmt;
+
+use crate::error::{TransportError, TransportErrorCode};
+
+/// 终端角色
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Side {
+    /// 客户端
+    Client,
+
+    /// 服务端
+    Server,
+}
+
+impl Side {
+    /// 获取对端的角色
+    ///
+    /// # Returns
+    /// 返回对端的角色
+    #[inline(always)]
+    pub const fn peer(&self) -> Self {
+        match self {
+            Self::Client => Self::Server,
+            Self::Server => Self::Client,
+        }
+    }
+}
+
+/// 流的方向
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StreamDirection {
+    /// 双向流
+    Bidi,
+
+    /// 单向流, 仅由发起方发送数据
+    Uni,
+}
+
+/// 流标识
+///
+/// 最低位标识流的发起方 (0: 客户端, 1: 服务端),
+/// 次低位标识流的方向 (0: 双向, 1: 单向),
+/// 其余位为同类流中的序号.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Default)]
+pub struct StreamId(u64);
+
+impl StreamId {
+    /// 构造一个流标识
+    ///
+    /// # Arguments
+    /// `id` - 流标识的数值
+    ///
+    /// # Returns
+    /// 返回流标识
+    #[inline(always)]
+    pub const fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// 构造指定发起方与方向的第 n 个流的流标识 (从 0 开始)
+    ///
+    /// # Arguments
+    /// `initiator` - 流的发起方
+    /// `direction` - 流的方向
+    /// `n` - 同类流中的序号
+    ///
+    /// # Returns
+    /// 返回流标识
+    pub const fn nth(initiator: Side, direction: StreamDirection, n: u64) -> Self {
+        let initiator = match initiator {
+            Side::Client => 0x00,
+            Side::Server => 0x01,
+        };
+        let direction = match direction {
+            StreamDirection::Bidi => 0x00,
+            StreamDirection::Uni => 0x02,
+        };
+
+        Self(n << 2 | direction | initiator)
+    }
+
+    /// 获取流标识的数值
+    ///
+    /// # Returns
+    /// 返回流标识的数值
+    #[inline(always)]
+    pub const fn get_value(&self) -> u64 {
+        self.0
+    }
+
+    /// 获取流的发起方
+    ///
+    /// # Returns
+    /// 返回流的发起方
+    #[inline(always)]
+    pub const fn get_initiator(&self) -> Side {
+        if self.0 & 0x01 == 0 {
+            Side::Client
+        } else {
+            Side::Server
+        }
+    }
+
+    /// 获取流的方向
+    ///
+    /// # Returns
+    /// 返回流的方向
+    #[inline(always)]
+    pub const fn get_direction(&self) -> StreamDirection {
+        if self.0 & 0x02 == 0 {
+            StreamDirection::Bidi
+        } else {
+            StreamDirection::Uni
+        }
+    }
+
+    /// 获取流在同类流中的序号
+    ///
+    /// # Returns
+    /// 返回流的序号
+    #[inline(always)]
+    pub const fn get_index(&self) -> u64 {
+        self.0 >> 2
+    }
+
+    /// 判断流是否由本端发起
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    ///
+    /// # Returns
+    /// 返回流是否由本端发起
+    #[inline(always)]
+    pub fn is_local(&self, local: Side) -> bool {
+        self.get_initiator() == local
+    }
+
+    /// 判断本端是否可以在该流上发送数据
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    ///
+    /// # Returns
+    /// 返回本端是否可以在该流上发送数据
+    pub fn is_sendable(&self, local: Side) -> bool {
+        self.get_direction() == StreamDirection::Bidi || self.is_local(local)
+    }
+
+    /// 判断本端是否可以在该流上接收数据
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    ///
+    /// # Returns
+    /// 返回本端是否可以在该流上接收数据
+    pub fn is_receivable(&self, local: Side) -> bool {
+        self.get_direction() == StreamDirection::Bidi || !self.is_local(local)
+    }
+
+    /// 检查承载流数据的帧 (STREAM, RESET_STREAM, STREAM_DATA_BLOCKED) 是否可以
+    /// 在该流上被本端接收, 本端仅发送的流上收到此类帧时返回 STREAM_STATE_ERROR.
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    /// `frame_type` - 收到的帧类型
+    pub(crate) fn check_receivable(
+        &self,
+        local: Side,
+        frame_type: u64,
+    ) -> Result<(), TransportError> {
+        if self.is_receivable(local) {
+            Ok(())
+        } else {
+            Err(TransportError::new(
+                TransportErrorCode::StreamStateError,
+                frame_type,
+                "frame received on a send-only stream",
+            ))
+        }
+    }
+
+    /// 检查作用于发送方向的帧 (STOP_SENDING, MAX_STREAM_DATA) 是否可以在该流上
+    /// 被本端接收, 本端仅接收的流上收到此类帧时返回 STREAM_STATE_ERROR.
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    /// `frame_type` - 收到的帧类型
+    pub(crate) fn check_sendable(
+        &self,
+        local: Side,
+        frame_type: u64,
+    ) -> Result<(), TransportError> {
+        if self.is_sendable(local) {
+            Ok(())
+        } else {
+            Err(TransportError::new(
+                TransportErrorCode::StreamStateError,
+                frame_type,
+                "frame received on a receive-only stream",
+            ))
+        }
+    }
+}
+
+impl From<u64> for StreamId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+impl From<StreamId> for u64 {
+    fn from(id: StreamId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
 
 pub(crate) trait StreamIDGetter {
     /// 获取设置帧的 Stream 标识
     ///
     /// # Returns
     /// 返回 Stream 标识
-    fn get_stream_id(&self) -> StreamID;
+    fn get_stream_id(&self) -> StreamId;
 }
 
 pub(crate) trait StreamIDSetter {
@@ -13,7 +233,7 @@ pub(crate) trait StreamIDSetter {
     ///
     /// # Arguments
     /// `stream_id` - Stream 标识
-    fn set_stream_id(&mut self, stream_id: StreamID);
+    fn set_stream_id(&mut self, stream_id: StreamId);
 }
 
 pub(crate) trait StreamDataGetter {
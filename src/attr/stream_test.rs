@@ -0,0 +1,58 @@
+use crate::{
+    attr::StreamIDSetter,
+    error::TransportErrorCode,
+    frame::{MaxStreamDataFrame, ResetStreamFrame, StopSendingFrame, StreamFrame},
+};
+
+use super::{Side, StreamDirection, StreamId};
+
+#[test]
+fn test_stream_id() {
+    let cases = [
+        (0, Side::Client, StreamDirection::Bidi),
+        (1, Side::Server, StreamDirection::Bidi),
+        (2, Side::Client, StreamDirection::Uni),
+        (3, Side::Server, StreamDirection::Uni),
+    ];
+
+    for (value, initiator, direction) in cases {
+        let id = StreamId::nth(initiator, direction, 5);
+        assert_eq!(id.get_value(), 20 + value);
+        assert_eq!(id.get_initiator(), initiator);
+        assert_eq!(id.get_direction(), direction);
+        assert_eq!(id.get_index(), 5);
+    }
+
+    let id = StreamId::nth(Side::Client, StreamDirection::Uni, 0);
+    assert!(id.is_local(Side::Client));
+    assert!(id.is_sendable(Side::Client));
+    assert!(!id.is_receivable(Side::Client));
+    assert!(!id.is_sendable(Side::Server));
+    assert!(id.is_receivable(Side::Server));
+}
+
+#[test]
+fn test_check_direction() {
+    let uni = StreamId::nth(Side::Client, StreamDirection::Uni, 0);
+
+    let mut stream = StreamFrame::new(false, false, false);
+    stream.set_stream_id(uni);
+    assert!(stream.check_direction(Side::Server).is_ok());
+    let err = stream.check_direction(Side::Client).unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::StreamStateError);
+    assert_eq!(err.get_frame_type(), 0x08);
+
+    let mut reset = ResetStreamFrame::new();
+    reset.set_stream_id(uni);
+    assert!(reset.check_direction(Side::Client).is_err());
+
+    let mut stop = StopSendingFrame::new();
+    stop.set_stream_id(uni);
+    assert!(stop.check_direction(Side::Client).is_ok());
+    assert!(stop.check_direction(Side::Server).is_err());
+
+    let mut max = MaxStreamDataFrame::new();
+    max.set_stream_id(StreamId::nth(Side::Server, StreamDirection::Bidi, 0));
+    assert!(max.check_direction(Side::Client).is_ok());
+    assert!(max.check_direction(Side::Server).is_ok());
+}
@@ -0,0 +1,538 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+use crate::{
+    error::{TransportError, TransportErrorCode},
+    frame::FrameType,
+    util,
+};
+
+use super::{Deserializer, Serializer};
+
+/// active_connection_id_limit 的默认值, 也是允许的最小值 (RFC 9000 §18.2)
+pub(crate) const DEFAULT_ACTIVE_CONNECTION_ID_LIMIT: usize = 2;
+
+/// ack_delay_exponent 的默认值
+pub(crate) const DEFAULT_ACK_DELAY_EXPONENT: u8 = 3;
+
+/// max_ack_delay 的默认值
+pub(crate) const DEFAULT_MAX_ACK_DELAY: Duration = Duration::from_millis(25);
+
+/// ack_delay_exponent 允许的最大值
+const MAX_ACK_DELAY_EXPONENT: u8 = 20;
+
+/// max_ack_delay 允许的上限 (不含), 以毫秒计
+const MAX_ACK_DELAY_LIMIT: u64 = 1 << 14;
+
+/// initial_max_streams_bidi 与 initial_max_streams_uni 允许的最大值
+const MAX_STREAMS_LIMIT: u64 = 1 << 60;
+
+/// max_idle_timeout 传输参数的标识
+const MAX_IDLE_TIMEOUT_PARAMETER_ID: u64 = 0x01;
+
+/// initial_max_data 传输参数的标识
+const INITIAL_MAX_DATA_PARAMETER_ID: u64 = 0x04;
+
+/// initial_max_stream_data_bidi_local 传输参数的标识
+const INITIAL_MAX_STREAM_DATA_BIDI_LOCAL_PARAMETER_ID: u64 = 0x05;
+
+/// initial_max_stream_data_bidi_remote 传输参数的标识
+const INITIAL_MAX_STREAM_DATA_BIDI_REMOTE_PARAMETER_ID: u64 = 0x06;
+
+/// initial_max_stream_data_uni 传输参数的标识
+const INITIAL_MAX_STREAM_DATA_UNI_PARAMETER_ID: u64 = 0x07;
+
+/// initial_max_streams_bidi 传输参数的标识
+const INITIAL_MAX_STREAMS_BIDI_PARAMETER_ID: u64 = 0x08;
+
+/// initial_max_streams_uni 传输参数的标识
+const INITIAL_MAX_STREAMS_UNI_PARAMETER_ID: u64 = 0x09;
+
+/// ack_delay_exponent 传输参数的标识
+const ACK_DELAY_EXPONENT_PARAMETER_ID: u64 = 0x0a;
+
+/// max_ack_delay 传输参数的标识
+const MAX_ACK_DELAY_PARAMETER_ID: u64 = 0x0b;
+
+/// active_connection_id_limit 传输参数的标识
+const ACTIVE_CONNECTION_ID_LIMIT_PARAMETER_ID: u64 = 0x0e;
+
+/// reset_stream_at 传输参数的标识
+pub(crate) const RESET_STREAM_AT_PARAMETER_ID: u64 = 0x17f7586d2cb571;
+
+/// 传输参数
+///
+/// 握手期间由双方各自通告, 用于约束对端的行为 (RFC 9000 §18.2).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TransportParameters {
+    /// 空闲超时时长, 为 0 时表示不启用空闲超时
+    max_idle_timeout: Duration,
+
+    /// 连接上可发送的初始最大数据量
+    initial_max_data: usize,
+
+    /// 本端发起的双向流上, 对端可发送的初始最大数据量
+    initial_max_stream_data_bidi_local: usize,
+
+    /// 对端发起的双向流上, 对端可发送的初始最大数据量
+    initial_max_stream_data_bidi_remote: usize,
+
+    /// 对端发起的单向流上, 对端可发送的初始最大数据量
+    initial_max_stream_data_uni: usize,
+
+    /// 对端可发起的初始最大双向流数量
+    initial_max_streams_bidi: usize,
+
+    /// 对端可发起的初始最大单向流数量
+    initial_max_streams_uni: usize,
+
+    /// 编码 ACK 帧中 ACK Delay 字段所用的指数
+    ack_delay_exponent: u8,
+
+    /// 延迟发送 ACK 的最大时长
+    max_ack_delay: Duration,
+
+    /// 愿意保存的对端 Connection ID 的最大数量, 不能小于 2
+    active_connection_id_limit: usize,
+
+    /// 是否支持 RESET_STREAM_AT 帧
+    reset_stream_at: bool,
+}
+
+impl Default for TransportParameters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransportParameters {
+    /// 构造一组传输参数, 未通告的参数均为 0; ack_delay_exponent、max_ack_delay 与
+    /// active_connection_id_limit 为 RFC 9000 规定的默认值
+    ///
+    /// # Returns
+    /// 返回传输参数
+    pub const fn new() -> Self {
+        Self {
+            max_idle_timeout: Duration::ZERO,
+            initial_max_data: 0,
+            initial_max_stream_data_bidi_local: 0,
+            initial_max_stream_data_bidi_remote: 0,
+            initial_max_stream_data_uni: 0,
+            initial_max_streams_bidi: 0,
+            initial_max_streams_uni: 0,
+            ack_delay_exponent: DEFAULT_ACK_DELAY_EXPONENT,
+            max_ack_delay: DEFAULT_MAX_ACK_DELAY,
+            active_connection_id_limit: DEFAULT_ACTIVE_CONNECTION_ID_LIMIT,
+            reset_stream_at: false,
+        }
+    }
+
+    /// 获取 max_idle_timeout
+    ///
+    /// # Returns
+    /// 返回 max_idle_timeout
+    #[inline(always)]
+    pub const fn get_max_idle_timeout(&self) -> Duration {
+        self.max_idle_timeout
+    }
+
+    /// 设置 max_idle_timeout
+    ///
+    /// # Arguments
+    /// `value` - max_idle_timeout, 为 0 时表示不启用空闲超时
+    pub fn set_max_idle_timeout(&mut self, value: Duration) {
+        self.max_idle_timeout = value
+    }
+
+    /// 获取 initial_max_data
+    ///
+    /// # Returns
+    /// 返回 initial_max_data
+    #[inline(always)]
+    pub const fn get_initial_max_data(&self) -> usize {
+        self.initial_max_data
+    }
+
+    /// 设置 initial_max_data
+    ///
+    /// # Arguments
+    /// `value` - initial_max_data
+    pub fn set_initial_max_data(&mut self, value: usize) {
+        self.initial_max_data = value
+    }
+
+    /// 获取 initial_max_stream_data_bidi_local
+    ///
+    /// # Returns
+    /// 返回 initial_max_stream_data_bidi_local
+    #[inline(always)]
+    pub const fn get_initial_max_stream_data_bidi_local(&self) -> usize {
+        self.initial_max_stream_data_bidi_local
+    }
+
+    /// 设置 initial_max_stream_data_bidi_local
+    ///
+    /// # Arguments
+    /// `value` - initial_max_stream_data_bidi_local
+    pub fn set_initial_max_stream_data_bidi_local(&mut self, value: usize) {
+        self.initial_max_stream_data_bidi_local = value
+    }
+
+    /// 获取 initial_max_stream_data_bidi_remote
+    ///
+    /// # Returns
+    /// 返回 initial_max_stream_data_bidi_remote
+    #[inline(always)]
+    pub const fn get_initial_max_stream_data_bidi_remote(&self) -> usize {
+        self.initial_max_stream_data_bidi_remote
+    }
+
+    /// 设置 initial_max_stream_data_bidi_remote
+    ///
+    /// # Arguments
+    /// `value` - initial_max_stream_data_bidi_remote
+    pub fn set_initial_max_stream_data_bidi_remote(&mut self, value: usize) {
+        self.initial_max_stream_data_bidi_remote = value
+    }
+
+    /// 获取 initial_max_stream_data_uni
+    ///
+    /// # Returns
+    /// 返回 initial_max_stream_data_uni
+    #[inline(always)]
+    pub const fn get_initial_max_stream_data_uni(&self) -> usize {
+        self.initial_max_stream_data_uni
+    }
+
+    /// 设置 initial_max_stream_data_uni
+    ///
+    /// # Arguments
+    /// `value` - initial_max_stream_data_uni
+    pub fn set_initial_max_stream_data_uni(&mut self, value: usize) {
+        self.initial_max_stream_data_uni = value
+    }
+
+    /// 获取 initial_max_streams_bidi
+    ///
+    /// # Returns
+    /// 返回 initial_max_streams_bidi
+    #[inline(always)]
+    pub const fn get_initial_max_streams_bidi(&self) -> usize {
+        self.initial_max_streams_bidi
+    }
+
+    /// 设置 initial_max_streams_bidi
+    ///
+    /// # Arguments
+    /// `value` - initial_max_streams_bidi
+    pub fn set_initial_max_streams_bidi(&mut self, value: usize) {
+        self.initial_max_streams_bidi = value
+    }
+
+    /// 获取 initial_max_streams_uni
+    ///
+    /// # Returns
+    /// 返回 initial_max_streams_uni
+    #[inline(always)]
+    pub const fn get_initial_max_streams_uni(&self) -> usize {
+        self.initial_max_streams_uni
+    }
+
+    /// 设置 initial_max_streams_uni
+    ///
+    /// # Arguments
+    /// `value` - initial_max_streams_uni
+    pub fn set_initial_max_streams_uni(&mut self, value: usize) {
+        self.initial_max_streams_uni = value
+    }
+
+    /// 获取 ack_delay_exponent
+    ///
+    /// # Returns
+    /// 返回 ack_delay_exponent
+    #[inline(always)]
+    pub const fn get_ack_delay_exponent(&self) -> u8 {
+        self.ack_delay_exponent
+    }
+
+    /// 设置 ack_delay_exponent
+    ///
+    /// # Arguments
+    /// `value` - ack_delay_exponent, 不能超过 20
+    pub fn set_ack_delay_exponent(&mut self, value: u8) {
+        self.ack_delay_exponent = value
+    }
+
+    /// 获取 max_ack_delay
+    ///
+    /// # Returns
+    /// 返回 max_ack_delay
+    #[inline(always)]
+    pub const fn get_max_ack_delay(&self) -> Duration {
+        self.max_ack_delay
+    }
+
+    /// 设置 max_ack_delay
+    ///
+    /// # Arguments
+    /// `value` - max_ack_delay, 必须小于 2^14 毫秒
+    pub fn set_max_ack_delay(&mut self, value: Duration) {
+        self.max_ack_delay = value
+    }
+
+    /// 获取 active_connection_id_limit
+    ///
+    /// # Returns
+    /// 返回 active_connection_id_limit
+    #[inline(always)]
+    pub const fn get_active_connection_id_limit(&self) -> usize {
+        self.active_connection_id_limit
+    }
+
+    /// 设置 active_connection_id_limit
+    ///
+    /// # Arguments
+    /// `value` - active_connection_id_limit, 不能小于 2
+    pub fn set_active_connection_id_limit(&mut self, value: usize) {
+        self.active_connection_id_limit = value
+    }
+
+    /// 获取是否支持 RESET_STREAM_AT 帧
+    ///
+    /// # Returns
+    /// 返回是否支持 RESET_STREAM_AT 帧
+    #[inline(always)]
+    pub const fn get_reset_stream_at(&self) -> bool {
+        self.reset_stream_at
+    }
+
+    /// 设置是否支持 RESET_STREAM_AT 帧
+    ///
+    /// # Arguments
+    /// `value` - 是否支持 RESET_STREAM_AT 帧
+    pub fn set_reset_stream_at(&mut self, value: bool) {
+        self.reset_stream_at = value
+    }
+
+    /// 将传输参数编码为 quic_transport_parameters 扩展的内容, 由会话放入握手消息中
+    ///
+    /// # Returns
+    /// 返回编码后的字节, 取默认值的参数不编码
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        // 写入 Vec 不会失败
+        let _ = self.write(&mut buf);
+        buf
+    }
+
+    /// 由握手消息中 quic_transport_parameters 扩展的内容解码对端的传输参数
+    ///
+    /// # Arguments
+    /// `buf` - 扩展的内容
+    /// # Returns
+    /// 返回传输参数; 格式错误、参数重复或取值无效时返回 TRANSPORT_PARAMETER_ERROR
+    pub fn decode(mut buf: &[u8]) -> Result<Self, TransportError> {
+        let mut params = Self::new();
+        params.read(&mut buf).map_err(|_| {
+            TransportError::new(
+                TransportErrorCode::TransportParameterError,
+                u8::from(FrameType::Crypto) as u64,
+                "invalid transport parameters",
+            )
+        })?;
+        Ok(params)
+    }
+}
+
+/// 写入一个取值为变长整数的传输参数
+///
+/// # Arguments
+/// `id` - 传输参数的标识
+/// `value` - 传输参数的取值
+/// `w` - 具备 io::Write 特征的一个实现
+/// # Returns
+/// 返回写入的数据长度
+fn write_varint_parameter(id: u64, value: u64, w: &mut dyn Write) -> Result<usize, io::Error> {
+    let mut size = util::write_varint(id, w)?;
+    size += util::write_varint(util::varint_len(value) as u64, w)?;
+    size += util::write_varint(value, w)?;
+    Ok(size)
+}
+
+/// 读取一个取值为变长整数的传输参数
+///
+/// # Arguments
+/// `value` - 传输参数的内容
+/// # Returns
+/// 返回传输参数的取值; 内容不是恰好一个变长整数时返回 io::Error
+fn read_varint_parameter(mut value: &[u8]) -> Result<u64, io::Error> {
+    let varint = util::read_varint(&mut value)?;
+    if !value.is_empty() {
+        return Err(invalid_parameter());
+    }
+    Ok(varint.value)
+}
+
+/// 构造传输参数取值无效的错误
+fn invalid_parameter() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid transport parameter")
+}
+
+impl Serializer for TransportParameters {
+    fn write(&self, w: &mut dyn Write) -> Result<usize, io::Error> {
+        let defaults = Self::new();
+        let varints = [
+            (
+                MAX_IDLE_TIMEOUT_PARAMETER_ID,
+                self.max_idle_timeout.as_millis() as u64,
+                defaults.max_idle_timeout.as_millis() as u64,
+            ),
+            (
+                INITIAL_MAX_DATA_PARAMETER_ID,
+                self.initial_max_data as u64,
+                defaults.initial_max_data as u64,
+            ),
+            (
+                INITIAL_MAX_STREAM_DATA_BIDI_LOCAL_PARAMETER_ID,
+                self.initial_max_stream_data_bidi_local as u64,
+                defaults.initial_max_stream_data_bidi_local as u64,
+            ),
+            (
+                INITIAL_MAX_STREAM_DATA_BIDI_REMOTE_PARAMETER_ID,
+                self.initial_max_stream_data_bidi_remote as u64,
+                defaults.initial_max_stream_data_bidi_remote as u64,
+            ),
+            (
+                INITIAL_MAX_STREAM_DATA_UNI_PARAMETER_ID,
+                self.initial_max_stream_data_uni as u64,
+                defaults.initial_max_stream_data_uni as u64,
+            ),
+            (
+                INITIAL_MAX_STREAMS_BIDI_PARAMETER_ID,
+                self.initial_max_streams_bidi as u64,
+                defaults.initial_max_streams_bidi as u64,
+            ),
+            (
+                INITIAL_MAX_STREAMS_UNI_PARAMETER_ID,
+                self.initial_max_streams_uni as u64,
+                defaults.initial_max_streams_uni as u64,
+            ),
+            (
+                ACK_DELAY_EXPONENT_PARAMETER_ID,
+                self.ack_delay_exponent as u64,
+                defaults.ack_delay_exponent as u64,
+            ),
+            (
+                MAX_ACK_DELAY_PARAMETER_ID,
+                self.max_ack_delay.as_millis() as u64,
+                defaults.max_ack_delay.as_millis() as u64,
+            ),
+            (
+                ACTIVE_CONNECTION_ID_LIMIT_PARAMETER_ID,
+                self.active_connection_id_limit as u64,
+                defaults.active_connection_id_limit as u64,
+            ),
+        ];
+
+        let mut payload_size = 0;
+        for (id, value, default) in varints {
+            if value != default {
+                payload_size += write_varint_parameter(id, value, w)?;
+            }
+        }
+
+        if self.reset_stream_at {
+            payload_size += util::write_varint(RESET_STREAM_AT_PARAMETER_ID, w)?;
+            payload_size += util::write_varint(0, w)?;
+        }
+
+        Ok(payload_size)
+    }
+}
+
+impl Deserializer for TransportParameters {
+    fn read(&mut self, r: &mut dyn Read) -> Result<usize, io::Error> {
+        let mut buf = Vec::new();
+        let payload_size = r.read_to_end(&mut buf)?;
+
+        let mut seen = BTreeSet::new();
+        let mut r = buf.as_slice();
+        while !r.is_empty() {
+            let id = util::read_varint(&mut r)?.value;
+            let len = util::read_varint(&mut r)?.value as usize;
+            // 同一个传输参数不能出现多次
+            if len > r.len() || !seen.insert(id) {
+                return Err(invalid_parameter());
+            }
+            let (value, rest) = r.split_at(len);
+            r = rest;
+
+            match id {
+                MAX_IDLE_TIMEOUT_PARAMETER_ID => {
+                    self.max_idle_timeout = Duration::from_millis(read_varint_parameter(value)?)
+                }
+                INITIAL_MAX_DATA_PARAMETER_ID => {
+                    self.initial_max_data = read_varint_parameter(value)? as usize
+                }
+                INITIAL_MAX_STREAM_DATA_BIDI_LOCAL_PARAMETER_ID => {
+                    self.initial_max_stream_data_bidi_local = read_varint_parameter(value)? as usize
+                }
+                INITIAL_MAX_STREAM_DATA_BIDI_REMOTE_PARAMETER_ID => {
+                    self.initial_max_stream_data_bidi_remote =
+                        read_varint_parameter(value)? as usize
+                }
+                INITIAL_MAX_STREAM_DATA_UNI_PARAMETER_ID => {
+                    self.initial_max_stream_data_uni = read_varint_parameter(value)? as usize
+                }
+                INITIAL_MAX_STREAMS_BIDI_PARAMETER_ID => {
+                    let max = read_varint_parameter(value)?;
+                    if max > MAX_STREAMS_LIMIT {
+                        return Err(invalid_parameter());
+                    }
+                    self.initial_max_streams_bidi = max as usize;
+                }
+                INITIAL_MAX_STREAMS_UNI_PARAMETER_ID => {
+                    let max = read_varint_parameter(value)?;
+                    if max > MAX_STREAMS_LIMIT {
+                        return Err(invalid_parameter());
+                    }
+                    self.initial_max_streams_uni = max as usize;
+                }
+                ACK_DELAY_EXPONENT_PARAMETER_ID => {
+                    let exponent = read_varint_parameter(value)?;
+                    if exponent > MAX_ACK_DELAY_EXPONENT as u64 {
+                        return Err(invalid_parameter());
+                    }
+                    self.ack_delay_exponent = exponent as u8;
+                }
+                MAX_ACK_DELAY_PARAMETER_ID => {
+                    let delay = read_varint_parameter(value)?;
+                    if delay >= MAX_ACK_DELAY_LIMIT {
+                        return Err(invalid_parameter());
+                    }
+                    self.max_ack_delay = Duration::from_millis(delay);
+                }
+                ACTIVE_CONNECTION_ID_LIMIT_PARAMETER_ID => {
+                    let limit = read_varint_parameter(value)?;
+                    if limit < DEFAULT_ACTIVE_CONNECTION_ID_LIMIT as u64 {
+                        return Err(invalid_parameter());
+                    }
+                    self.active_connection_id_limit = limit as usize;
+                }
+                RESET_STREAM_AT_PARAMETER_ID => {
+                    if !value.is_empty() {
+                        return Err(invalid_parameter());
+                    }
+                    self.reset_stream_at = true;
+                }
+                // 忽略未实现或未知的传输参数
+                _ => {}
+            }
+        }
+
+        Ok(payload_size)
+    }
+}
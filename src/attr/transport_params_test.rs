@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use crate::error::TransportErrorCode;
+
+use super::TransportParameters;
+
+#[test]
+fn test_transport_params_round_trip() {
+    // 全部取默认值时不编码任何参数
+    assert!(TransportParameters::new().encode().is_empty());
+
+    let mut params = TransportParameters::new();
+    params.set_max_idle_timeout(Duration::from_secs(30));
+    params.set_initial_max_data(1 << 20);
+    params.set_initial_max_stream_data_bidi_local(1 << 16);
+    params.set_initial_max_stream_data_bidi_remote(1 << 17);
+    params.set_initial_max_stream_data_uni(1 << 18);
+    params.set_initial_max_streams_bidi(100);
+    params.set_initial_max_streams_uni(3);
+    params.set_ack_delay_exponent(10);
+    params.set_max_ack_delay(Duration::from_millis(100));
+    params.set_active_connection_id_limit(4);
+    params.set_reset_stream_at(true);
+
+    let decoded = TransportParameters::decode(&params.encode()).unwrap();
+    assert_eq!(decoded, params);
+
+    // 未知的传输参数被忽略
+    let mut buf = vec![0x21, 0x02, 0xab, 0xcd];
+    buf.extend_from_slice(&params.encode());
+    assert_eq!(TransportParameters::decode(&buf).unwrap(), params);
+}
+
+#[test]
+fn test_transport_params_invalid() {
+    let invalid: [&[u8]; 7] = [
+        // initial_max_streams_bidi 超过 2^60
+        &[0x08, 0x08, 0xd0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01],
+        // initial_max_streams_uni 超过 2^60
+        &[0x09, 0x08, 0xd0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01],
+        // ack_delay_exponent 超过 20
+        &[0x0a, 0x01, 21],
+        // max_ack_delay 达到 2^14 毫秒
+        &[0x0b, 0x04, 0x80, 0x00, 0x40, 0x00],
+        // active_connection_id_limit 小于 2
+        &[0x0e, 0x01, 0x01],
+        // 同一个参数出现两次
+        &[0x04, 0x01, 0x01, 0x04, 0x01, 0x02],
+        // 长度与内容不符
+        &[0x04, 0x02, 0x01],
+    ];
+    for buf in invalid {
+        let error = TransportParameters::decode(buf).unwrap_err();
+        assert_eq!(
+            error.get_code(),
+            TransportErrorCode::TransportParameterError
+        );
+    }
+}
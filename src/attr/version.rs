@@ -1 +1,4 @@
 pub(crate) type Version = u32;
+
+/// QUIC 版本 1 (RFC 9000)
+pub(crate) const QUIC_VERSION_1: Version = 0x00000001;
@@ -0,0 +1,101 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+use crate::{crypto::Random, error::ConnectionIdError};
+
+/// 无状态重置令牌的长度
+pub(crate) const RESET_TOKEN_LEN: usize = 16;
+
+/// Connection ID 生成器特征
+///
+/// 连接通过生成器产生通过 NEW_CONNECTION_ID 帧提供给对端的 Connection ID,
+/// 并为每个 Connection ID 计算无状态重置令牌. 实现该特征即可在 Connection ID 中
+/// 嵌入负载均衡器所需的路由信息.
+///
+/// 短包头中没有 Connection ID 的长度字段, 因此同一生成器产生的 Connection ID 长度必须固定,
+/// 且与建立连接时本端的 Connection ID 长度一致.
+pub trait ConnectionIdGenerator {
+    /// 生成一个新的 Connection ID
+    ///
+    /// # Returns
+    /// 返回长度为 `cid_len` 的 Connection ID
+    fn generate(&mut self) -> Vec<u8>;
+
+    /// 获取生成的 Connection ID 的长度
+    ///
+    /// # Returns
+    /// 返回 Connection ID 的长度, 取值范围为 [1, 20]
+    fn cid_len(&self) -> usize;
+
+    /// 计算 Connection ID 对应的无状态重置令牌
+    ///
+    /// 令牌应当由 Connection ID 与只有本端知道的密钥确定性地派生,
+    /// 以便在丢失连接状态后仍能为收到的数据包生成无状态重置 (RFC 9000 §10.3.2).
+    ///
+    /// # Arguments
+    /// `cid` - Connection ID
+    /// # Returns
+    /// 返回无状态重置令牌
+    fn reset_token(&self, cid: &[u8]) -> [u8; RESET_TOKEN_LEN];
+}
+
+/// 随机 Connection ID 生成器
+///
+/// 以加密库的随机数生成器生成 Connection ID, 并以随机密钥的 SipHash 从 Connection ID
+/// 派生无状态重置令牌.
+pub struct RandomConnectionIdGenerator {
+    /// 生成的 Connection ID 的长度
+    cid_len: usize,
+
+    /// 密码学安全的随机数生成器
+    random: Box<dyn Random>,
+
+    /// 派生无状态重置令牌的密钥
+    key: RandomState,
+}
+
+impl RandomConnectionIdGenerator {
+    /// 构造一个随机 Connection ID 生成器
+    ///
+    /// # Arguments
+    /// `cid_len` - 生成的 Connection ID 的长度, 取值范围为 [1, 20]
+    /// `random` - 密码学安全的随机数生成器, 通常由 `Session::random` 提供
+    /// # Returns
+    /// 返回随机 Connection ID 生成器, 长度超出范围时返回错误
+    pub fn new(cid_len: usize, random: Box<dyn Random>) -> Result<Self, ConnectionIdError> {
+        if !(1..=20).contains(&cid_len) {
+            return Err(ConnectionIdError::InvalidLength(cid_len));
+        }
+
+        Ok(Self {
+            cid_len,
+            random,
+            key: RandomState::new(),
+        })
+    }
+}
+
+impl ConnectionIdGenerator for RandomConnectionIdGenerator {
+    fn generate(&mut self) -> Vec<u8> {
+        let mut cid = vec![0; self.cid_len];
+        self.random.fill(&mut cid);
+        cid
+    }
+
+    fn cid_len(&self) -> usize {
+        self.cid_len
+    }
+
+    fn reset_token(&self, cid: &[u8]) -> [u8; RESET_TOKEN_LEN] {
+        let mut token = [0; RESET_TOKEN_LEN];
+        for (i, chunk) in token.chunks_mut(8).enumerate() {
+            let mut hasher = self.key.build_hasher();
+            hasher.write_usize(i);
+            hasher.write(cid);
+            chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+        }
+        token
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    attr::ConnectionID,
+    error::{TransportError, TransportErrorCode},
+    frame::{FrameType, NewConnectionIDFrame, RetireConnectionIDFrame},
+    recovery::ControlFrameTracker,
+};
+
+use super::generator::{ConnectionIdGenerator, RESET_TOKEN_LEN};
+
+/// 本端最多同时提供给对端的 Connection ID 数量, 即使对端允许更多
+const MAX_LOCAL_CONNECTION_IDS: usize = 8;
+
+/// 对端提供的 Connection ID
+struct RemoteConnectionId {
+    /// Connection ID
+    cid: ConnectionID,
+
+    /// 无状态重置令牌, 握手期间得知的 Connection ID 没有令牌
+    reset_token: Option<[u8; RESET_TOKEN_LEN]>,
+}
+
+/// Connection ID 管理器
+///
+/// 管理连接两个方向上的 Connection ID (RFC 9000 §5.1):
+/// 本端通过 NEW_CONNECTION_ID 帧提供的 Connection ID 不超过对端的
+/// active_connection_id_limit, 被对端废弃后补充新的 Connection ID;
+/// 对端提供的 Connection ID 不能超过本端的 active_connection_id_limit,
+/// 按 Retire Prior To 废弃旧的 Connection ID, 轮换时废弃正在使用的 Connection ID.
+///
+/// 两端握手期间使用的 Connection ID 的序列号均为 0.
+pub(crate) struct ConnectionIdManager {
+    /// Connection ID 生成器, 本端使用零长度 Connection ID 时为 None, 不提供新的 Connection ID
+    generator: Option<Box<dyn ConnectionIdGenerator>>,
+
+    /// 本端提供且尚未被对端废弃的 Connection ID, 按序列号索引
+    local: BTreeMap<u64, ConnectionID>,
+
+    /// 下一个本端 Connection ID 的序列号
+    next_local_seq: u64,
+
+    /// 对端的 active_connection_id_limit, 握手完成前为 0, 不提供新的 Connection ID
+    peer_limit: usize,
+
+    /// 对端提供且尚未被本端废弃的 Connection ID, 按序列号索引
+    remote: BTreeMap<u64, RemoteConnectionId>,
+
+    /// 正在使用的对端 Connection ID 的序列号
+    remote_seq: u64,
+
+    /// 对端通告的最大 Retire Prior To
+    remote_retire_prior_to: u64,
+
+    /// 本端的 active_connection_id_limit
+    local_limit: usize,
+}
+
+impl ConnectionIdManager {
+    /// 构造一个 Connection ID 管理器
+    ///
+    /// # Arguments
+    /// `local_cid` - 握手期间本端的 Connection ID
+    /// `remote_cid` - 握手期间对端的 Connection ID
+    /// `local_limit` - 本端的 active_connection_id_limit
+    /// `generator` - Connection ID 生成器, 本端使用零长度 Connection ID 时为 None
+    /// # Returns
+    /// 返回 Connection ID 管理器
+    pub(crate) fn new(
+        local_cid: ConnectionID,
+        remote_cid: ConnectionID,
+        local_limit: usize,
+        generator: Option<Box<dyn ConnectionIdGenerator>>,
+    ) -> Self {
+        let remote = RemoteConnectionId {
+            cid: remote_cid,
+            reset_token: None,
+        };
+        Self {
+            generator,
+            local: BTreeMap::from([(0, local_cid)]),
+            next_local_seq: 1,
+            peer_limit: 0,
+            remote: BTreeMap::from([(0, remote)]),
+            remote_seq: 0,
+            remote_retire_prior_to: 0,
+            local_limit,
+        }
+    }
+
+    /// 替换 Connection ID 生成器
+    ///
+    /// # Arguments
+    /// `generator` - Connection ID 生成器
+    pub(crate) fn set_generator(&mut self, generator: Box<dyn ConnectionIdGenerator>) {
+        self.generator = Some(generator)
+    }
+
+    /// 获取正在使用的对端 Connection ID
+    ///
+    /// # Returns
+    /// 返回对端 Connection ID
+    pub(crate) fn get_remote(&self) -> ConnectionID {
+        self.remote[&self.remote_seq].cid
+    }
+
+    /// 设置握手期间对端的 Connection ID, 即对端首个数据包的 Source Connection ID
+    ///
+    /// # Arguments
+    /// `cid` - 对端 Connection ID
+    pub(crate) fn set_initial_remote(&mut self, cid: ConnectionID) {
+        if let Some(remote) = self.remote.get_mut(&0) {
+            remote.cid = cid;
+        }
+    }
+
+    /// 获取本端提供且尚未被对端废弃的 Connection ID
+    ///
+    /// # Returns
+    /// 返回本端 Connection ID, 按序列号排列
+    pub(crate) fn get_local(&self) -> impl Iterator<Item = &ConnectionID> {
+        self.local.values()
+    }
+
+    /// 设置对端的 active_connection_id_limit, 之后由 `issue` 提供新的 Connection ID
+    ///
+    /// # Arguments
+    /// `limit` - 对端的 active_connection_id_limit
+    pub(crate) fn set_peer_limit(&mut self, limit: usize) {
+        self.peer_limit = limit
+    }
+
+    /// 提供新的 Connection ID, 直到达到对端允许的数量; 本端使用零长度 Connection ID 时不提供
+    ///
+    /// # Arguments
+    /// `tracker` - 控制帧重传跟踪器
+    pub(crate) fn issue(&mut self, tracker: &mut ControlFrameTracker) {
+        let Some(generator) = self.generator.as_mut() else {
+            return;
+        };
+
+        let limit = self.peer_limit.min(MAX_LOCAL_CONNECTION_IDS);
+        while self.local.len() < limit {
+            let mut cid = ConnectionID::new();
+            cid.set_id(&generator.generate());
+
+            let seq = self.next_local_seq;
+            self.next_local_seq += 1;
+            self.local.insert(seq, cid);
+
+            let mut frame = NewConnectionIDFrame::new();
+            frame.set_seq(seq);
+            frame.set_connection_id(cid);
+            frame.set_reset_token(&generator.reset_token(cid.get_id()));
+            tracker.push_new_connection_id(frame);
+        }
+    }
+
+    /// 处理 RETIRE_CONNECTION_ID 帧, 废弃本端的 Connection ID 并补充新的 Connection ID
+    ///
+    /// # Arguments
+    /// `frame` - RETIRE_CONNECTION_ID 帧
+    /// `tracker` - 控制帧重传跟踪器
+    /// # Returns
+    /// 废弃了尚未提供的 Connection ID 时返回 PROTOCOL_VIOLATION
+    pub(crate) fn on_retire_connection_id_frame(
+        &mut self,
+        frame: &RetireConnectionIDFrame,
+        tracker: &mut ControlFrameTracker,
+    ) -> Result<(), TransportError> {
+        let seq = frame.get_seq();
+        if seq >= self.next_local_seq {
+            return Err(TransportError::new(
+                TransportErrorCode::ProtocolViolation,
+                u8::from(FrameType::RetireConnectionID) as u64,
+                "retired connection id that was never issued",
+            ));
+        }
+
+        if self.local.remove(&seq).is_some() {
+            tracker.on_connection_id_retired(seq);
+            self.issue(tracker);
+        }
+        Ok(())
+    }
+
+    /// 处理 NEW_CONNECTION_ID 帧, 保存对端的 Connection ID 并按 Retire Prior To 废弃旧的 Connection ID
+    ///
+    /// # Arguments
+    /// `frame` - NEW_CONNECTION_ID 帧
+    /// `tracker` - 控制帧重传跟踪器
+    /// # Returns
+    /// Retire Prior To 大于序列号时返回 FRAME_ENCODING_ERROR;
+    /// 同一序列号对应不同的 Connection ID 时返回 PROTOCOL_VIOLATION;
+    /// 保存的 Connection ID 超过本端的 active_connection_id_limit 时返回 CONNECTION_ID_LIMIT_ERROR
+    pub(crate) fn on_new_connection_id_frame(
+        &mut self,
+        frame: &NewConnectionIDFrame,
+        tracker: &mut ControlFrameTracker,
+    ) -> Result<(), TransportError> {
+        let frame_type = u8::from(FrameType::NewConnectionID) as u64;
+        let seq = frame.get_seq();
+        let retire_prior_to = frame.get_retire_prior_to();
+        if retire_prior_to > seq {
+            return Err(TransportError::new(
+                TransportErrorCode::FrameEncodingError,
+                frame_type,
+                "retire prior to exceeds sequence number",
+            ));
+        }
+
+        let cid = frame.get_connection_id();
+        let mut reset_token = [0; RESET_TOKEN_LEN];
+        reset_token.copy_from_slice(frame.get_reset_token());
+        if let Some(remote) = self.remote.get(&seq) {
+            // 重传的帧必须与之前的内容一致
+            if remote.cid != cid || remote.reset_token != Some(reset_token) {
+                return Err(TransportError::new(
+                    TransportErrorCode::ProtocolViolation,
+                    frame_type,
+                    "sequence number reused for different connection id",
+                ));
+            }
+            return Ok(());
+        }
+        if self.remote.values().any(|remote| remote.cid == cid) {
+            return Err(TransportError::new(
+                TransportErrorCode::ProtocolViolation,
+                frame_type,
+                "connection id reused with different sequence number",
+            ));
+        }
+
+        if seq < self.remote_retire_prior_to {
+            // 迟到的帧所提供的 Connection ID 已经被要求废弃
+            tracker.push_retire_connection_id(seq);
+        } else {
+            let reset_token = Some(reset_token);
+            self.remote
+                .insert(seq, RemoteConnectionId { cid, reset_token });
+        }
+
+        if retire_prior_to > self.remote_retire_prior_to {
+            self.remote_retire_prior_to = retire_prior_to;
+            let retired = self
+                .remote
+                .range(..retire_prior_to)
+                .map(|(&seq, _)| seq)
+                .collect::<Vec<_>>();
+            for seq in retired {
+                self.remote.remove(&seq);
+                tracker.push_retire_connection_id(seq);
+            }
+            if self.remote_seq < retire_prior_to {
+                // 序列号不小于 Retire Prior To 的新 Connection ID 刚被保存, 集合不会为空
+                self.remote_seq = *self.remote.keys().next().unwrap();
+            }
+        }
+
+        if self.remote.len() > self.local_limit {
+            return Err(TransportError::new(
+                TransportErrorCode::ConnectionIdLimitError,
+                frame_type,
+                "too many connection ids",
+            ));
+        }
+        Ok(())
+    }
+
+    /// 换用对端提供的下一个 Connection ID, 并废弃正在使用的 Connection ID
+    ///
+    /// # Arguments
+    /// `tracker` - 控制帧重传跟踪器
+    /// # Returns
+    /// 返回是否换用了新的 Connection ID, 对端没有提供未使用的 Connection ID 时返回 false
+    pub(crate) fn rotate_remote(&mut self, tracker: &mut ControlFrameTracker) -> bool {
+        let Some(&next) = self
+            .remote
+            .range(self.remote_seq + 1..)
+            .next()
+            .map(|(seq, _)| seq)
+        else {
+            return false;
+        };
+
+        self.remote.remove(&self.remote_seq);
+        tracker.push_retire_connection_id(self.remote_seq);
+        self.remote_seq = next;
+        true
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    attr::ConnectionID,
+    crypto::Random,
+    error::{ConnectionIdError, TransportErrorCode},
+    frame::{NewConnectionIDFrame, RetireConnectionIDFrame},
+    packet::{PacketBuilder, PacketHeader, ShortHeader},
+    recovery::{ControlFrameTracker, SentFrame},
+};
+
+use super::{ConnectionIdGenerator, ConnectionIdManager, RandomConnectionIdGenerator};
+
+/// 按序生成 Connection ID 的生成器
+struct CountingGenerator(u8);
+
+impl ConnectionIdGenerator for CountingGenerator {
+    fn generate(&mut self) -> Vec<u8> {
+        self.0 += 1;
+        vec![self.0; 4]
+    }
+
+    fn cid_len(&self) -> usize {
+        4
+    }
+
+    fn reset_token(&self, cid: &[u8]) -> [u8; 16] {
+        [cid[0]; 16]
+    }
+}
+
+/// 以固定字节填充的随机数生成器
+struct FixedRandom(u8);
+
+impl Random for FixedRandom {
+    fn fill(&mut self, buf: &mut [u8]) {
+        buf.fill(self.0);
+    }
+}
+
+fn cid(id: &[u8]) -> ConnectionID {
+    let mut cid = ConnectionID::new();
+    cid.set_id(id);
+    cid
+}
+
+fn manager() -> ConnectionIdManager {
+    ConnectionIdManager::new(
+        cid(&[0; 4]),
+        cid(&[0xff; 4]),
+        2,
+        Some(Box::new(CountingGenerator(0))),
+    )
+}
+
+fn new_connection_id(seq: u64, retire_prior_to: u64, id: u8) -> NewConnectionIDFrame {
+    let mut frame = NewConnectionIDFrame::new();
+    frame.set_seq(seq);
+    frame.set_retire_prior_to(retire_prior_to);
+    frame.set_connection_id(cid(&[id; 4]));
+    frame.set_reset_token(&[id; 16]);
+    frame
+}
+
+fn retire_connection_id(seq: u64) -> RetireConnectionIDFrame {
+    let mut frame = RetireConnectionIDFrame::new();
+    frame.set_seq(seq);
+    frame
+}
+
+/// 将跟踪器中待发送的控制帧写入数据包, 返回写入的帧
+fn flush(tracker: &mut ControlFrameTracker) -> Vec<SentFrame> {
+    let mut header = ShortHeader::new(4, 1);
+    header.set_dst(cid(&[0xff; 4]));
+    let mut builder = PacketBuilder::new(PacketHeader::Short(header), 1200, 16).unwrap();
+    tracker.write_frames(&mut builder);
+    let packet = builder.finish().unwrap();
+    packet
+        .get_frames()
+        .iter()
+        .copied()
+        .filter(|frame| *frame != SentFrame::Padding)
+        .collect()
+}
+
+#[test]
+fn test_cid_issue_and_retire_local() {
+    let mut cids = manager();
+    let mut tracker = ControlFrameTracker::new();
+
+    // 握手完成前不提供新的 Connection ID
+    cids.issue(&mut tracker);
+    assert!(flush(&mut tracker).is_empty());
+
+    // 对端允许 3 个 Connection ID, 补充序列号 1 与 2
+    cids.set_peer_limit(3);
+    cids.issue(&mut tracker);
+    assert_eq!(
+        flush(&mut tracker),
+        vec![
+            SentFrame::NewConnectionId { seq: 1 },
+            SentFrame::NewConnectionId { seq: 2 },
+        ]
+    );
+    assert_eq!(
+        cids.get_local()
+            .map(ConnectionID::get_id)
+            .collect::<Vec<_>>(),
+        vec![&[0; 4], &[1; 4], &[2; 4]]
+    );
+
+    // 对端废弃一个 Connection ID 后补充新的 Connection ID
+    cids.on_retire_connection_id_frame(&retire_connection_id(0), &mut tracker)
+        .unwrap();
+    assert_eq!(
+        flush(&mut tracker),
+        vec![SentFrame::NewConnectionId { seq: 3 }]
+    );
+
+    let error = cids
+        .on_retire_connection_id_frame(&retire_connection_id(4), &mut tracker)
+        .unwrap_err();
+    assert_eq!(error.get_code(), TransportErrorCode::ProtocolViolation);
+}
+
+#[test]
+fn test_cid_retire_prior_to() {
+    let mut cids = manager();
+    let mut tracker = ControlFrameTracker::new();
+
+    cids.on_new_connection_id_frame(&new_connection_id(1, 0, 1), &mut tracker)
+        .unwrap();
+    assert_eq!(cids.get_remote(), cid(&[0xff; 4]));
+    assert!(flush(&mut tracker).is_empty());
+
+    // Retire Prior To 废弃正在使用的 Connection ID, 换用剩余的 Connection ID
+    cids.on_new_connection_id_frame(&new_connection_id(2, 1, 2), &mut tracker)
+        .unwrap();
+    assert_eq!(cids.get_remote(), cid(&[1; 4]));
+    assert_eq!(
+        flush(&mut tracker),
+        vec![SentFrame::RetireConnectionId { seq: 0 }]
+    );
+
+    // 迟到的帧提供的 Connection ID 已被要求废弃, 直接废弃
+    cids.on_new_connection_id_frame(&new_connection_id(3, 3, 3), &mut tracker)
+        .unwrap();
+    cids.on_new_connection_id_frame(&new_connection_id(0, 0, 9), &mut tracker)
+        .unwrap();
+    assert_eq!(cids.get_remote(), cid(&[3; 4]));
+    assert_eq!(
+        flush(&mut tracker),
+        vec![
+            SentFrame::RetireConnectionId { seq: 1 },
+            SentFrame::RetireConnectionId { seq: 2 },
+            SentFrame::RetireConnectionId { seq: 0 },
+        ]
+    );
+}
+
+#[test]
+fn test_cid_remote_errors() {
+    let mut cids = manager();
+    let mut tracker = ControlFrameTracker::new();
+
+    let error = cids
+        .on_new_connection_id_frame(&new_connection_id(1, 2, 1), &mut tracker)
+        .unwrap_err();
+    assert_eq!(error.get_code(), TransportErrorCode::FrameEncodingError);
+
+    // 重传的帧被忽略, 内容不一致时为协议违规
+    cids.on_new_connection_id_frame(&new_connection_id(1, 0, 1), &mut tracker)
+        .unwrap();
+    cids.on_new_connection_id_frame(&new_connection_id(1, 0, 1), &mut tracker)
+        .unwrap();
+    let error = cids
+        .on_new_connection_id_frame(&new_connection_id(1, 0, 2), &mut tracker)
+        .unwrap_err();
+    assert_eq!(error.get_code(), TransportErrorCode::ProtocolViolation);
+
+    // 超过本端的 active_connection_id_limit
+    let error = cids
+        .on_new_connection_id_frame(&new_connection_id(2, 0, 2), &mut tracker)
+        .unwrap_err();
+    assert_eq!(error.get_code(), TransportErrorCode::ConnectionIdLimitError);
+}
+
+#[test]
+fn test_cid_rotate_remote() {
+    let mut cids = manager();
+    let mut tracker = ControlFrameTracker::new();
+    assert!(!cids.rotate_remote(&mut tracker));
+
+    cids.on_new_connection_id_frame(&new_connection_id(1, 0, 1), &mut tracker)
+        .unwrap();
+    assert!(cids.rotate_remote(&mut tracker));
+    assert_eq!(cids.get_remote(), cid(&[1; 4]));
+    assert_eq!(
+        flush(&mut tracker),
+        vec![SentFrame::RetireConnectionId { seq: 0 }]
+    );
+    assert!(!cids.rotate_remote(&mut tracker));
+
+    // 随机生成器的无状态重置令牌由 Connection ID 确定
+    assert_eq!(
+        RandomConnectionIdGenerator::new(0, Box::new(FixedRandom(1))).err(),
+        Some(ConnectionIdError::InvalidLength(0))
+    );
+    assert_eq!(
+        RandomConnectionIdGenerator::new(21, Box::new(FixedRandom(1))).err(),
+        Some(ConnectionIdError::InvalidLength(21))
+    );
+    let mut generator = RandomConnectionIdGenerator::new(8, Box::new(FixedRandom(1))).unwrap();
+    let id = generator.generate();
+    assert_eq!(id, vec![1; 8]);
+    assert_eq!(generator.reset_token(&id), generator.reset_token(&id));
+    assert_ne!(generator.reset_token(&id), generator.reset_token(&[0; 8]));
+}
@@ -0,0 +1,10 @@
+mod generator;
+mod manager;
+
+pub(crate) use generator::*;
+pub(crate) use manager::*;
+
+pub use generator::{ConnectionIdGenerator, RandomConnectionIdGenerator};
+
+#[cfg(test)]
+mod manager_test;
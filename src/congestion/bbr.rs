@@ -0,0 +1,529 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::recovery::{RateSample, RttEstimator, SentPacket, INITIAL_RTT};
+
+use super::{controller::CongestionController, new_reno::initial_window};
+
+/// Startup 阶段的发送速率增益与拥塞窗口增益, 即 2/ln(2)
+const STARTUP_GAIN: f64 = 2.885;
+
+/// Drain 阶段的发送速率增益
+const DRAIN_GAIN: f64 = 1.0 / STARTUP_GAIN;
+
+/// ProbeBW 阶段的拥塞窗口增益
+const PROBE_BW_CWND_GAIN: f64 = 2.0;
+
+/// ProbeBW 阶段按轮次循环使用的发送速率增益
+const PACING_GAIN_CYCLE: [f64; 8] = [1.25, 0.75, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+
+/// 瓶颈带宽最大值过滤器的窗口长度, 以轮为单位
+const BTL_BW_FILTER_LEN: u64 = 10;
+
+/// 最小 RTT 的有效期, 过期后进入 ProbeRTT 阶段重新测量
+const MIN_RTT_FILTER_LEN: Duration = Duration::from_secs(10);
+
+/// ProbeRTT 阶段至少维持的时长
+const PROBE_RTT_DURATION: Duration = Duration::from_millis(200);
+
+/// 判断带宽仍在增长的阈值
+const FULL_BW_THRESH: f64 = 1.25;
+
+/// 带宽连续若干轮未增长后, 认为瓶颈链路已被填满
+const FULL_BW_COUNT: usize = 3;
+
+/// 一轮中可容忍的最大丢包率
+const LOSS_THRESH: f64 = 0.02;
+
+/// 丢包率过高时在途数据上限的缩减比例
+const BETA: f64 = 0.7;
+
+/// 在途数据上限在无过量丢包的探测轮中的增长比例
+const INFLIGHT_HI_GROWTH: f64 = 1.25;
+
+/// BBR 所处的阶段
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BbrState {
+    /// 指数增长发送速率, 探测瓶颈带宽
+    Startup,
+
+    /// 排空 Startup 阶段在瓶颈链路中积压的队列
+    Drain,
+
+    /// 以循环的发送速率增益周期性探测更多带宽
+    ProbeBw,
+
+    /// 减少在途数据, 重新测量最小 RTT
+    ProbeRtt,
+}
+
+/// 以轮为窗口的最大值过滤器
+struct MaxFilter {
+    /// 各轮的最大值, 按轮次递增排列且数值递减
+    samples: VecDeque<(u64, u64)>,
+}
+
+impl MaxFilter {
+    fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// 在指定轮次加入一个样本, 并移除窗口外的样本
+    fn update(&mut self, round: u64, value: u64) {
+        while self.samples.back().is_some_and(|(_, v)| *v <= value) {
+            self.samples.pop_back();
+        }
+        self.samples.push_back((round, value));
+
+        while self
+            .samples
+            .front()
+            .is_some_and(|(r, _)| *r + BTL_BW_FILTER_LEN <= round)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// 获取窗口内的最大值
+    fn get(&self) -> u64 {
+        self.samples.front().map_or(0, |(_, v)| *v)
+    }
+}
+
+/// BBR 拥塞控制
+///
+/// 基于模型的拥塞控制算法. 由交付速率样本估算瓶颈带宽 (BtlBw),
+/// 由 RTT 样本估算最小 RTT (RTprop), 以二者之积 (BDP) 决定拥塞窗口,
+/// 以增益后的瓶颈带宽决定发送速率, 而不以丢包作为主要的拥塞信号.
+///
+/// 参照 BBRv2, 当一轮中的丢包率超过阈值时, 会限制在途数据的上限
+/// 并提前结束 Startup 或带宽探测.
+pub struct Bbr {
+    /// 最大数据报大小
+    max_datagram_size: usize,
+
+    /// 当前阶段
+    state: BbrState,
+
+    /// 拥塞窗口
+    congestion_window: usize,
+
+    /// 发送速率, 以 字节/秒 为单位
+    pacing_rate: u64,
+
+    /// 发送速率增益
+    pacing_gain: f64,
+
+    /// 拥塞窗口增益
+    cwnd_gain: f64,
+
+    /// 瓶颈带宽估算
+    btl_bw: MaxFilter,
+
+    /// 最小 RTT 估算
+    min_rtt: Option<Duration>,
+
+    /// 最小 RTT 的更新时刻
+    min_rtt_stamp: Option<Instant>,
+
+    /// 已经经过的轮数
+    round_count: u64,
+
+    /// 交付字节数达到该值时开始新的一轮
+    next_round_delivered: usize,
+
+    /// 是否已经填满瓶颈链路
+    filled_pipe: bool,
+
+    /// 填满瓶颈链路判定中记录的带宽
+    full_bw: u64,
+
+    /// 带宽未增长的连续轮数
+    full_bw_count: usize,
+
+    /// ProbeBW 阶段当前的增益下标
+    cycle_index: usize,
+
+    /// ProbeBW 阶段当前增益的开始时刻
+    cycle_stamp: Option<Instant>,
+
+    /// ProbeRTT 阶段的结束时刻
+    probe_rtt_done_stamp: Option<Instant>,
+
+    /// ProbeRTT 阶段是否已经经过一轮
+    probe_rtt_round_done: bool,
+
+    /// 当前轮新确认的字节数
+    round_delivered: usize,
+
+    /// 当前轮丢失的字节数
+    round_lost: usize,
+
+    /// 在途数据上限
+    inflight_hi: usize,
+
+    /// 最近一次的在途字节数
+    bytes_in_flight: usize,
+}
+
+impl Bbr {
+    /// 构造一个 BBR 拥塞控制
+    ///
+    /// # Arguments
+    /// `max_datagram_size` - 最大数据报大小
+    /// # Returns
+    /// 返回一个 BBR 拥塞控制
+    pub fn new(max_datagram_size: usize) -> Self {
+        let congestion_window = initial_window(max_datagram_size);
+        let pacing_rate =
+            (congestion_window as f64 * STARTUP_GAIN / INITIAL_RTT.as_secs_f64()) as u64;
+
+        Self {
+            max_datagram_size,
+            state: BbrState::Startup,
+            congestion_window,
+            pacing_rate,
+            pacing_gain: STARTUP_GAIN,
+            cwnd_gain: STARTUP_GAIN,
+            btl_bw: MaxFilter::new(),
+            min_rtt: None,
+            min_rtt_stamp: None,
+            round_count: 0,
+            next_round_delivered: 0,
+            filled_pipe: false,
+            full_bw: 0,
+            full_bw_count: 0,
+            cycle_index: 0,
+            cycle_stamp: None,
+            probe_rtt_done_stamp: None,
+            probe_rtt_round_done: false,
+            round_delivered: 0,
+            round_lost: 0,
+            inflight_hi: usize::MAX,
+            bytes_in_flight: 0,
+        }
+    }
+
+    /// 获取当前阶段
+    ///
+    /// # Returns
+    /// 返回当前阶段
+    #[inline(always)]
+    pub const fn get_state(&self) -> BbrState {
+        self.state
+    }
+
+    /// 获取瓶颈带宽估算
+    ///
+    /// # Returns
+    /// 返回以 字节/秒 为单位的瓶颈带宽
+    #[inline(always)]
+    pub fn get_btl_bw(&self) -> u64 {
+        self.btl_bw.get()
+    }
+
+    /// 获取在途数据上限
+    ///
+    /// # Returns
+    /// 返回在途数据上限, 尚未因过量丢包缩减时返回 usize::MAX
+    #[inline(always)]
+    pub const fn get_inflight_hi(&self) -> usize {
+        self.inflight_hi
+    }
+
+    /// 获取最小 RTT 估算
+    ///
+    /// # Returns
+    /// 返回最小 RTT; 尚未获得 RTT 样本时返回 None
+    #[inline(always)]
+    pub const fn get_min_rtt(&self) -> Option<Duration> {
+        self.min_rtt
+    }
+
+    /// 获取带宽时延积与增益之积
+    fn get_inflight(&self, gain: f64) -> Option<usize> {
+        let min_rtt = self.min_rtt?;
+        let bw = self.btl_bw.get();
+        if bw == 0 {
+            return None;
+        }
+
+        Some((bw as f64 * min_rtt.as_secs_f64() * gain) as usize)
+    }
+
+    /// 获取最小拥塞窗口
+    fn min_pipe_cwnd(&self) -> usize {
+        self.max_datagram_size * 4
+    }
+
+    /// 获取拥塞窗口的目标值
+    fn get_target_cwnd(&self) -> usize {
+        let target = match self.get_inflight(self.cwnd_gain) {
+            // 为 ACK 聚合额外留出 3 个数据报
+            Some(bdp) => bdp + self.max_datagram_size * 3,
+            None => initial_window(self.max_datagram_size),
+        };
+
+        target.min(self.inflight_hi).max(self.min_pipe_cwnd())
+    }
+
+    /// 更新轮次, 返回本次 ACK 是否开始了新的一轮
+    fn update_round(&mut self, sample: &RateSample) -> bool {
+        if sample.get_prior_delivered() < self.next_round_delivered {
+            return false;
+        }
+        self.next_round_delivered = sample.get_total_delivered();
+        self.round_count += 1;
+        true
+    }
+
+    /// 结束一轮的丢包统计, 返回该轮丢包率是否过高
+    fn end_round_loss(&mut self) -> bool {
+        let total = self.round_delivered + self.round_lost;
+        let excessive = total != 0 && self.round_lost as f64 / total as f64 > LOSS_THRESH;
+
+        self.round_delivered = 0;
+        self.round_lost = 0;
+        excessive
+    }
+
+    /// 更新瓶颈带宽与最小 RTT, 本次 ACK 没有产生 RTT 样本时不更新最小 RTT
+    fn update_model(&mut self, now: Instant, sample: &RateSample, rtt_sample: Option<Duration>) {
+        let rate = sample.get_delivery_rate();
+        if !sample.is_app_limited() || rate >= self.btl_bw.get() {
+            self.btl_bw.update(self.round_count, rate);
+        }
+
+        let expired = self
+            .min_rtt_stamp
+            .is_some_and(|stamp| now > stamp + MIN_RTT_FILTER_LEN);
+        if let Some(rtt) = rtt_sample {
+            if self.min_rtt.is_none_or(|min_rtt| rtt <= min_rtt) || expired {
+                self.min_rtt = Some(rtt);
+                self.min_rtt_stamp = Some(now);
+            }
+        }
+
+        if expired && self.state != BbrState::ProbeRtt {
+            self.enter_probe_rtt();
+        }
+    }
+
+    /// 在新的一轮开始时判断瓶颈链路是否已被填满
+    fn check_full_pipe(&mut self, sample: &RateSample, excessive_loss: bool) {
+        if self.filled_pipe || sample.is_app_limited() {
+            return;
+        }
+
+        if excessive_loss {
+            self.filled_pipe = true;
+            return;
+        }
+
+        let bw = self.btl_bw.get();
+        if bw as f64 >= self.full_bw as f64 * FULL_BW_THRESH {
+            self.full_bw = bw;
+            self.full_bw_count = 0;
+            return;
+        }
+
+        self.full_bw_count += 1;
+        if self.full_bw_count >= FULL_BW_COUNT {
+            self.filled_pipe = true;
+        }
+    }
+
+    fn enter_drain(&mut self) {
+        self.state = BbrState::Drain;
+        self.pacing_gain = DRAIN_GAIN;
+        self.cwnd_gain = STARTUP_GAIN;
+    }
+
+    fn enter_probe_bw(&mut self, now: Instant) {
+        self.state = BbrState::ProbeBw;
+        self.cwnd_gain = PROBE_BW_CWND_GAIN;
+        // 跳过降速的增益, 从匀速阶段中的某一个开始循环.
+        self.cycle_index = 2 + (self.round_count as usize % (PACING_GAIN_CYCLE.len() - 2));
+        self.pacing_gain = PACING_GAIN_CYCLE[self.cycle_index];
+        self.cycle_stamp = Some(now);
+    }
+
+    fn enter_probe_rtt(&mut self) {
+        self.state = BbrState::ProbeRtt;
+        self.pacing_gain = 1.0;
+        self.cwnd_gain = 1.0;
+        self.probe_rtt_done_stamp = None;
+        self.probe_rtt_round_done = false;
+    }
+
+    /// 推进 ProbeBW 阶段的增益循环
+    fn advance_cycle_phase(&mut self, now: Instant, excessive_loss: bool) {
+        let min_rtt = self.min_rtt.unwrap_or(INITIAL_RTT);
+        let elapsed = self
+            .cycle_stamp
+            .is_none_or(|stamp| now.saturating_duration_since(stamp) > min_rtt);
+
+        let advance = if self.pacing_gain > 1.0 {
+            // 探测阶段遇到过量丢包时立即结束探测.
+            excessive_loss
+                || (elapsed
+                    && self
+                        .get_inflight(self.pacing_gain)
+                        .is_none_or(|target| self.bytes_in_flight >= target))
+        } else if self.pacing_gain < 1.0 {
+            elapsed
+                || self
+                    .get_inflight(1.0)
+                    .is_some_and(|bdp| self.bytes_in_flight <= bdp)
+        } else {
+            elapsed
+        };
+
+        if advance {
+            self.cycle_index = (self.cycle_index + 1) % PACING_GAIN_CYCLE.len();
+            self.pacing_gain = PACING_GAIN_CYCLE[self.cycle_index];
+            self.cycle_stamp = Some(now);
+        }
+    }
+
+    /// 处理 ProbeRTT 阶段
+    fn handle_probe_rtt(&mut self, now: Instant, sample: &RateSample, round_start: bool) {
+        match self.probe_rtt_done_stamp {
+            None => {
+                if self.bytes_in_flight <= self.min_pipe_cwnd() {
+                    self.probe_rtt_done_stamp = Some(now + PROBE_RTT_DURATION);
+                    self.probe_rtt_round_done = false;
+                    self.next_round_delivered = sample.get_total_delivered();
+                }
+            }
+            Some(done_stamp) => {
+                if round_start {
+                    self.probe_rtt_round_done = true;
+                }
+                if self.probe_rtt_round_done && now > done_stamp {
+                    self.min_rtt_stamp = Some(now);
+                    if self.filled_pipe {
+                        self.enter_probe_bw(now);
+                    } else {
+                        self.state = BbrState::Startup;
+                        self.pacing_gain = STARTUP_GAIN;
+                        self.cwnd_gain = STARTUP_GAIN;
+                    }
+                }
+            }
+        }
+    }
+
+    /// 根据模型更新发送速率
+    fn set_pacing_rate(&mut self) {
+        let bw = self.btl_bw.get();
+        if bw == 0 {
+            return;
+        }
+
+        let rate = (bw as f64 * self.pacing_gain) as u64;
+        if self.filled_pipe || rate > self.pacing_rate {
+            self.pacing_rate = rate;
+        }
+    }
+}
+
+impl CongestionController for Bbr {
+    fn on_packet_sent(&mut self, _packet: &SentPacket, bytes_in_flight: usize) {
+        self.bytes_in_flight = bytes_in_flight;
+    }
+
+    fn on_ack(&mut self, _now: Instant, packet: &SentPacket, _rtt: &RttEstimator) {
+        self.bytes_in_flight = self.bytes_in_flight.saturating_sub(packet.get_size());
+        self.round_delivered += packet.get_size();
+
+        let target = self.get_target_cwnd();
+        if self.filled_pipe {
+            self.congestion_window = (self.congestion_window + packet.get_size()).min(target);
+        } else if self.congestion_window < target {
+            self.congestion_window += packet.get_size();
+        }
+        self.congestion_window = self.congestion_window.max(self.min_pipe_cwnd());
+
+        if self.state == BbrState::ProbeRtt {
+            self.congestion_window = self.congestion_window.min(self.min_pipe_cwnd());
+        }
+    }
+
+    fn on_rate_sample(
+        &mut self,
+        now: Instant,
+        sample: &RateSample,
+        rtt_sample: Option<Duration>,
+        _rtt: &RttEstimator,
+    ) {
+        self.bytes_in_flight = sample.get_bytes_in_flight();
+
+        let round_start = self.update_round(sample);
+        let excessive_loss = round_start && self.end_round_loss();
+
+        self.update_model(now, sample, rtt_sample);
+
+        if round_start {
+            self.check_full_pipe(sample, excessive_loss);
+
+            if excessive_loss {
+                let limit =
+                    (self.bytes_in_flight.max(self.congestion_window) as f64 * BETA) as usize;
+                self.inflight_hi = limit.max(self.min_pipe_cwnd());
+            } else if self.state == BbrState::ProbeBw
+                && self.pacing_gain > 1.0
+                && self.inflight_hi != usize::MAX
+            {
+                self.inflight_hi = (self.inflight_hi as f64 * INFLIGHT_HI_GROWTH) as usize;
+            }
+        }
+
+        match self.state {
+            BbrState::Startup => {
+                if self.filled_pipe {
+                    self.enter_drain();
+                }
+            }
+            BbrState::Drain => {
+                if self
+                    .get_inflight(1.0)
+                    .is_none_or(|bdp| self.bytes_in_flight <= bdp)
+                {
+                    self.enter_probe_bw(now);
+                }
+            }
+            BbrState::ProbeBw => self.advance_cycle_phase(now, excessive_loss),
+            BbrState::ProbeRtt => self.handle_probe_rtt(now, sample, round_start),
+        }
+
+        self.set_pacing_rate();
+    }
+
+    fn on_packet_lost(&mut self, _now: Instant, packet: &SentPacket) {
+        self.bytes_in_flight = self.bytes_in_flight.saturating_sub(packet.get_size());
+        self.round_lost += packet.get_size();
+    }
+
+    fn on_congestion_event(&mut self, _now: Instant, _time_sent: Instant) {}
+
+    fn on_persistent_congestion(&mut self, _now: Instant) {
+        self.congestion_window = self.min_pipe_cwnd();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.max_datagram_size);
+    }
+
+    fn get_window(&self) -> usize {
+        self.congestion_window
+    }
+
+    fn get_pacing_rate(&self) -> Option<u64> {
+        Some(self.pacing_rate)
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    attr::PacketNumberSpace,
+    frame::ACKFrame,
+    recovery::{DeliveryRateSampler, LossDetector, RttEstimator, SentFrame, SentPacket},
+};
+
+use super::{Bbr, BbrState, CongestionController};
+
+#[test]
+fn test_bbr_model() {
+    // 瓶颈带宽 1200 字节/毫秒, 往返时延 50ms 的链路.
+    let start = Instant::now();
+    let rtt = Duration::from_millis(50);
+    let service = Duration::from_millis(1);
+
+    let mut detector = LossDetector::new(true, Duration::ZERO, Box::new(Bbr::new(1200)));
+    let space = PacketNumberSpace::ApplicationData;
+
+    let mut pn = 0;
+    let mut next_send = start;
+    let mut link_free = start;
+    let mut acks: VecDeque<(Instant, u64)> = VecDeque::new();
+
+    let mut now = start;
+    while now < start + Duration::from_secs(3) {
+        while acks.front().is_some_and(|(t, _)| *t <= now) {
+            let (_, acked) = acks.pop_front().unwrap();
+            let mut ack = ACKFrame::new(false);
+            ack.set_largest(acked);
+            detector.on_ack_received(now, space, &ack).unwrap();
+        }
+
+        // 按照 BBR 给出的发送速率发送数据包.
+        while next_send <= now
+            && detector.get_bytes_in_flight() + 1200 <= detector.get_congestion_window()
+        {
+            let pacing_rate = detector.get_congestion().get_pacing_rate().unwrap();
+            next_send = now + Duration::from_secs_f64(1200.0 / pacing_rate as f64);
+
+            let packet = SentPacket::new(pn, now, 1200, vec![SentFrame::Ping]);
+            detector.on_packet_sent(space, packet);
+
+            link_free = link_free.max(now) + service;
+            acks.push_back((link_free + rtt, pn));
+            pn += 1;
+        }
+
+        now += Duration::from_micros(500);
+    }
+
+    // 发送速率在瓶颈带宽的 0.75 至 1.25 倍之间循环.
+    let pacing_rate = detector.get_congestion().get_pacing_rate().unwrap();
+    assert!((900_000..=1_500_000).contains(&pacing_rate));
+
+    // 拥塞窗口约为 2 倍 BDP 加 3 个数据报, Startup 阶段积压的队列已被排空.
+    let bdp = 1200 * rtt.as_millis() as usize;
+    let expected = 2 * bdp + 3 * 1200;
+    let cwnd = detector.get_congestion_window();
+    assert!((expected * 95 / 100..=expected * 105 / 100).contains(&cwnd));
+    assert!(detector.get_rtt().get_smoothed_rtt() < Duration::from_millis(55));
+}
+
+#[test]
+fn test_bbr_initial_state() {
+    let bbr = Bbr::new(1200);
+    assert_eq!(bbr.get_state(), BbrState::Startup);
+    assert_eq!(bbr.get_min_rtt(), None);
+    assert_eq!(bbr.get_pacing_rate(), Some(103963));
+}
+
+#[test]
+fn test_bbr_min_rtt_requires_rtt_sample() {
+    let start = Instant::now();
+    let mut sampler = DeliveryRateSampler::new();
+    let mut packet = SentPacket::new(0, start, 1200, vec![SentFrame::Ping]);
+    sampler.on_packet_sent(&mut packet, 0);
+
+    // 不足 1 微秒的采样区间也能得到交付速率
+    let now = start + Duration::from_nanos(500);
+    sampler.on_packet_acked(now, &packet);
+    let sample = sampler.take_sample(now, Duration::ZERO, 0).unwrap();
+    assert_eq!(sample.get_delivery_rate(), 2_400_000_000);
+
+    // 没有 RTT 样本的 ACK 不会更新最小 RTT
+    let mut bbr = Bbr::new(1200);
+    let rtt = RttEstimator::new();
+    bbr.on_rate_sample(now, &sample, None, &rtt);
+    assert_eq!(bbr.get_min_rtt(), None);
+
+    bbr.on_rate_sample(now, &sample, Some(Duration::from_millis(50)), &rtt);
+    assert_eq!(bbr.get_min_rtt(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn test_bbr_excessive_loss_reduces_inflight_hi() {
+    let start = Instant::now();
+    let rtt = RttEstimator::new();
+    let mut sampler = DeliveryRateSampler::new();
+    let mut bbr = Bbr::new(1200);
+
+    let mut packets = Vec::new();
+    for pn in 0..20 {
+        let mut packet = SentPacket::new(pn, start, 1200, vec![SentFrame::Ping]);
+        sampler.on_packet_sent(&mut packet, pn as usize * 1200);
+        bbr.on_packet_sent(&packet, (pn as usize + 1) * 1200);
+        packets.push(packet);
+    }
+
+    // 每个 ACK 确认一个数据包, 20 个数据包中丢失 1 个, 即 5% 的丢包率
+    let mut now = start + Duration::from_millis(50);
+    for (i, packet) in packets.iter().enumerate() {
+        if i == 1 {
+            bbr.on_packet_lost(now, packet);
+            continue;
+        }
+        sampler.on_packet_acked(now, packet);
+        bbr.on_ack(now, packet, &rtt);
+        let sample = sampler.take_sample(now, Duration::ZERO, 0).unwrap();
+        bbr.on_rate_sample(now, &sample, Some(Duration::from_millis(50)), &rtt);
+        now += Duration::from_millis(1);
+    }
+    assert_eq!(bbr.get_inflight_hi(), usize::MAX);
+
+    // 下一轮的首个 ACK 结束上一轮, 丢包率超过 2% 时缩减在途数据上限
+    let mut packet = SentPacket::new(20, now, 1200, vec![SentFrame::Ping]);
+    sampler.on_packet_sent(&mut packet, 0);
+    bbr.on_packet_sent(&packet, 1200);
+    now += Duration::from_millis(50);
+    sampler.on_packet_acked(now, &packet);
+    bbr.on_ack(now, &packet, &rtt);
+    let sample = sampler.take_sample(now, Duration::ZERO, 0).unwrap();
+    bbr.on_rate_sample(now, &sample, Some(Duration::from_millis(50)), &rtt);
+    assert!(bbr.get_inflight_hi() < usize::MAX);
+    assert_eq!(bbr.get_state(), BbrState::Drain);
+}
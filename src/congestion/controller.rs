@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+use crate::recovery::{RateSample, RttEstimator, SentPacket};
+
+/// 拥塞控制特征
+///
+/// 丢包检测在数据包发送、被确认、被判定丢失以及发生拥塞时回调拥塞控制算法,
+/// 并通过拥塞窗口限制在途字节数. 实现该特征即可替换连接所使用的拥塞控制算法.
+pub trait CongestionController {
+    /// 计入在途字节数的数据包被发送
+    ///
+    /// # Arguments
+    /// `packet` - 已发送的数据包
+    /// `bytes_in_flight` - 发送该数据包后的在途字节数
+    fn on_packet_sent(&mut self, packet: &SentPacket, bytes_in_flight: usize);
+
+    /// 计入在途字节数的数据包被确认
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `packet` - 被确认的数据包
+    /// `rtt` - 已使用本次 ACK 更新后的 RTT 估算
+    fn on_ack(&mut self, now: Instant, packet: &SentPacket, rtt: &RttEstimator);
+
+    /// 处理一次 ACK 后得到的交付速率样本
+    ///
+    /// 在本次 ACK 确认的所有数据包都回调 `on_ack` 之后调用.
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `sample` - 交付速率样本
+    /// `rtt_sample` - 本次 ACK 产生的 RTT 样本; 本次 ACK 没有产生 RTT 样本时为 None
+    /// `rtt` - 已使用本次 ACK 更新后的 RTT 估算
+    fn on_rate_sample(
+        &mut self,
+        _now: Instant,
+        _sample: &RateSample,
+        _rtt_sample: Option<Duration>,
+        _rtt: &RttEstimator,
+    ) {
+    }
+
+    /// 计入在途字节数的数据包被判定丢失
+    ///
+    /// 在 `on_congestion_event` 之前针对每个丢失的数据包回调.
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `packet` - 丢失的数据包
+    fn on_packet_lost(&mut self, _now: Instant, _packet: &SentPacket) {}
+
+    /// 发生拥塞事件, 即检测到丢包或收到 ECN-CE 标记
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `time_sent` - 触发拥塞事件的数据包中最晚的发送时刻
+    fn on_congestion_event(&mut self, now: Instant, time_sent: Instant);
+
+    /// 检测到持续拥塞
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    fn on_persistent_congestion(&mut self, now: Instant);
+
+    /// 恢复到初始状态, 连接迁移到新的网络路径时调用 (RFC 9000 §9.4)
+    fn reset(&mut self);
+
+    /// 获取拥塞窗口
+    ///
+    /// # Returns
+    /// 返回以字节为单位的拥塞窗口
+    fn get_window(&self) -> usize;
+
+    /// 获取拥塞控制算法给出的发送速率
+    ///
+    /// # Returns
+    /// 返回以 字节/秒 为单位的发送速率; 若算法不提供发送速率, 则返回 None
+    fn get_pacing_rate(&self) -> Option<u64> {
+        None
+    }
+}
@@ -0,0 +1,216 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    attr::PacketNumberSpace,
+    recovery::{RateSample, RttEstimator, SentPacket},
+};
+
+use super::{
+    controller::CongestionController,
+    hystart::HyStart,
+    new_reno::{initial_window, minimum_window},
+};
+
+/// CUBIC 函数的缩放常数, 单位为 数据报/秒^3
+const C: f64 = 0.4;
+
+/// 拥塞事件发生后拥塞窗口的缩减比例
+const BETA_CUBIC: f64 = 0.7;
+
+/// CUBIC 拥塞控制
+///
+/// RFC 9438 中描述的拥塞控制算法. 拥塞避免阶段的拥塞窗口按照以距上次拥塞事件的
+/// 时间为自变量的三次函数增长, 使增长速度与 RTT 无关, 适合高带宽时延积的链路.
+///
+/// - Reno 友好区间: 当三次函数的增长慢于以 AIMD 估算的 Reno 窗口时, 使用 Reno 窗口;
+/// - 快速收敛: 若拥塞事件发生时拥塞窗口小于上一次的 W_max, 则进一步降低 W_max,
+///   为新加入的流让出带宽;
+/// - 慢启动阶段使用 HyStart++ 在发生丢包之前退出慢启动.
+pub struct Cubic {
+    /// 最大数据报大小
+    max_datagram_size: usize,
+
+    /// 拥塞窗口
+    congestion_window: usize,
+
+    /// 慢启动阈值
+    ssthresh: usize,
+
+    /// 上一次拥塞事件发生前的拥塞窗口, 以字节为单位
+    w_max: f64,
+
+    /// 以 Reno 方式估算的拥塞窗口, 以字节为单位
+    w_est: f64,
+
+    /// 三次函数从当前窗口增长到 W_max 所需的时长, 以秒为单位
+    k: f64,
+
+    /// 当前拥塞避免阶段的开始时刻
+    epoch_start: Option<Instant>,
+
+    /// 当前恢复期的开始时刻
+    congestion_recovery_start_time: Option<Instant>,
+
+    /// HyStart++ 慢启动退出算法
+    hystart: HyStart,
+}
+
+impl Cubic {
+    /// 构造一个 CUBIC 拥塞控制
+    ///
+    /// # Arguments
+    /// `max_datagram_size` - 最大数据报大小
+    /// # Returns
+    /// 返回一个 CUBIC 拥塞控制
+    pub fn new(max_datagram_size: usize) -> Self {
+        Self {
+            max_datagram_size,
+            congestion_window: initial_window(max_datagram_size),
+            ssthresh: usize::MAX,
+            w_max: 0.0,
+            w_est: 0.0,
+            k: 0.0,
+            epoch_start: None,
+            congestion_recovery_start_time: None,
+            hystart: HyStart::new(),
+        }
+    }
+
+    /// 获取慢启动阈值
+    ///
+    /// # Returns
+    /// 返回慢启动阈值
+    #[inline(always)]
+    pub const fn get_ssthresh(&self) -> usize {
+        self.ssthresh
+    }
+
+    /// 判断数据包是否在当前恢复期内发送
+    fn in_congestion_recovery(&self, time_sent: Instant) -> bool {
+        self.congestion_recovery_start_time
+            .is_some_and(|start| time_sent <= start)
+    }
+
+    /// 计算三次函数 W_cubic(t), 以字节为单位
+    fn w_cubic(&self, t: Duration) -> f64 {
+        let offset = t.as_secs_f64() - self.k;
+        C * offset * offset * offset * self.max_datagram_size as f64 + self.w_max
+    }
+
+    /// 拥塞避免阶段处理被确认的数据包
+    fn congestion_avoidance(&mut self, now: Instant, acked: usize, rtt: &RttEstimator) {
+        let cwnd = self.congestion_window as f64;
+        let mds = self.max_datagram_size as f64;
+
+        let epoch_start = match self.epoch_start {
+            Some(epoch_start) => epoch_start,
+            None => {
+                // 通过 HyStart++ 退出慢启动时尚未发生过拥塞事件.
+                if self.w_max < cwnd {
+                    self.w_max = cwnd;
+                }
+                self.k = ((self.w_max - cwnd) / (C * mds)).cbrt();
+                self.w_est = cwnd;
+                self.epoch_start = Some(now);
+                now
+            }
+        };
+        let t = now.saturating_duration_since(epoch_start);
+
+        // W_est 达到 W_max 后以标准 Reno 的速度增长.
+        let alpha = if self.w_est < self.w_max {
+            3.0 * (1.0 - BETA_CUBIC) / (1.0 + BETA_CUBIC)
+        } else {
+            1.0
+        };
+        self.w_est += alpha * acked as f64 * mds / cwnd;
+
+        if self.w_cubic(t) < self.w_est {
+            self.congestion_window = self.w_est as usize;
+            return;
+        }
+
+        let target = self
+            .w_cubic(t + rtt.get_smoothed_rtt())
+            .clamp(cwnd, cwnd * 1.5);
+        let increment = (target - cwnd) * acked as f64 / cwnd;
+        self.congestion_window = (cwnd + increment) as usize;
+    }
+}
+
+impl CongestionController for Cubic {
+    fn on_packet_sent(&mut self, packet: &SentPacket, _bytes_in_flight: usize) {
+        // HyStart++ 只以应用数据空间的数据包划分轮次
+        if packet.get_space() == PacketNumberSpace::ApplicationData {
+            self.hystart.on_packet_sent(packet.get_packet_number());
+        }
+    }
+
+    fn on_ack(&mut self, now: Instant, packet: &SentPacket, rtt: &RttEstimator) {
+        if self.in_congestion_recovery(packet.get_time_sent()) {
+            return;
+        }
+
+        if self.congestion_window < self.ssthresh {
+            if packet.get_space() == PacketNumberSpace::ApplicationData
+                && self.hystart.on_packet_acked(packet.get_packet_number())
+            {
+                self.ssthresh = self.congestion_window;
+            } else {
+                self.congestion_window += packet.get_size() / self.hystart.get_growth_divisor();
+            }
+            return;
+        }
+
+        self.congestion_avoidance(now, packet.get_size(), rtt);
+    }
+
+    fn on_rate_sample(
+        &mut self,
+        _now: Instant,
+        _sample: &RateSample,
+        rtt_sample: Option<Duration>,
+        _rtt: &RttEstimator,
+    ) {
+        // 每个 ACK 帧至多提供一个 RTT 样本
+        if let Some(rtt_sample) = rtt_sample {
+            if self.congestion_window < self.ssthresh {
+                self.hystart.on_rtt_sample(rtt_sample);
+            }
+        }
+    }
+
+    fn on_congestion_event(&mut self, now: Instant, time_sent: Instant) {
+        if self.in_congestion_recovery(time_sent) {
+            return;
+        }
+        self.congestion_recovery_start_time = Some(now);
+
+        let cwnd = self.congestion_window as f64;
+        self.w_max = if cwnd < self.w_max {
+            // 快速收敛
+            cwnd * (1.0 + BETA_CUBIC) / 2.0
+        } else {
+            cwnd
+        };
+
+        self.ssthresh = ((cwnd * BETA_CUBIC) as usize).max(minimum_window(self.max_datagram_size));
+        self.congestion_window = self.ssthresh;
+        self.epoch_start = None;
+    }
+
+    fn on_persistent_congestion(&mut self, _now: Instant) {
+        self.congestion_window = minimum_window(self.max_datagram_size);
+        self.congestion_recovery_start_time = None;
+        self.epoch_start = None;
+        self.hystart.reset();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.max_datagram_size);
+    }
+
+    fn get_window(&self) -> usize {
+        self.congestion_window
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    attr::PacketNumberSpace,
+    frame::ACKFrame,
+    recovery::{LossDetector, RttEstimator, SentFrame, SentPacket},
+};
+
+use super::{CongestionController, Cubic};
+
+#[test]
+fn test_cubic_reduction_and_fast_convergence() {
+    let start = Instant::now();
+    let mut cc = Cubic::new(1200);
+    assert_eq!(cc.get_window(), 12000);
+
+    cc.on_congestion_event(start, start);
+    assert_eq!(cc.get_window(), 8400);
+    assert_eq!(cc.get_ssthresh(), 8400);
+
+    // 恢复期内的丢包不再缩减窗口.
+    cc.on_congestion_event(start + Duration::from_millis(1), start);
+    assert_eq!(cc.get_window(), 8400);
+
+    // 窗口未恢复到 W_max 时再次拥塞, 快速收敛降低 W_max.
+    let now = start + Duration::from_millis(100);
+    cc.on_congestion_event(now, now);
+    assert_eq!(cc.get_window(), 5880);
+}
+
+#[test]
+fn test_cubic_growth() {
+    let start = Instant::now();
+    let mut rtt = RttEstimator::new();
+    rtt.update(Duration::from_millis(50), Duration::ZERO, None);
+
+    let mut cc = Cubic::new(1200);
+    cc.on_congestion_event(start, start);
+
+    // 拥塞避免阶段窗口单调增长, 并在 K 秒附近回到 W_max.
+    let mut pn = 0;
+    let mut now = start;
+    let mut last = cc.get_window();
+    while now < start + Duration::from_secs(3) {
+        now += Duration::from_millis(50);
+        for _ in 0..(cc.get_window() / 1200) {
+            pn += 1;
+            let packet = SentPacket::new(pn, now, 1200, vec![SentFrame::Ping]);
+            cc.on_ack(now, &packet, &rtt);
+        }
+        assert!(cc.get_window() >= last);
+        last = cc.get_window();
+    }
+    assert!(cc.get_window() > 12000);
+}
+
+#[test]
+fn test_cubic_hystart_one_rtt_sample_per_ack() {
+    let start = Instant::now();
+    let mut detector = LossDetector::new(true, Duration::ZERO, Box::new(Cubic::new(1200)));
+    let space = PacketNumberSpace::ApplicationData;
+
+    // 每轮发送 8 个数据包, `batch` 为 true 时以一个 ACK 帧确认全部数据包
+    let mut pn = 0;
+    let mut now = start;
+    let mut round = |detector: &mut LossDetector, rtt: u64, batch: bool| {
+        let first = pn;
+        for _ in 0..8 {
+            detector.on_packet_sent(space, SentPacket::new(pn, now, 1200, vec![SentFrame::Ping]));
+            pn += 1;
+        }
+        now += Duration::from_millis(rtt);
+        let largest = if batch {
+            vec![pn - 1]
+        } else {
+            (first..pn).collect()
+        };
+        for largest in largest {
+            let mut ack = ACKFrame::new(false);
+            ack.set_largest(largest);
+            ack.set_first_range((largest - first) as usize);
+            detector.on_ack_received(now, space, &ack).unwrap();
+        }
+    };
+
+    // 第二轮的一个 ACK 帧只提供一个 RTT 样本, 不足以进入保守慢启动, 窗口按慢启动增长
+    round(&mut detector, 100, false);
+    round(&mut detector, 150, true);
+    round(&mut detector, 150, false);
+    assert_eq!(detector.get_congestion_window(), 12000 + 24 * 1200);
+}
@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use crate::attr::PacketNumber;
+
+/// 每轮中判断 RTT 是否增大所需的最少 RTT 样本数
+const N_RTT_SAMPLE: usize = 8;
+
+/// RTT 增大判定阈值的下限
+const MIN_RTT_THRESH: Duration = Duration::from_millis(4);
+
+/// RTT 增大判定阈值的上限
+const MAX_RTT_THRESH: Duration = Duration::from_millis(16);
+
+/// RTT 增大判定阈值为上一轮最小 RTT 的 1/8
+const MIN_RTT_DIVISOR: u32 = 8;
+
+/// 保守慢启动阶段拥塞窗口增长速度为慢启动的 1/4
+const CSS_GROWTH_DIVISOR: usize = 4;
+
+/// 保守慢启动持续的轮数
+const CSS_ROUNDS: usize = 5;
+
+/// HyStart++ 慢启动退出算法
+///
+/// 以"轮"为单位统计最小 RTT, 一轮从发送某个数据包开始, 到该数据包被确认时结束.
+/// 不同编号空间的数据包编号不可比较, 因此只以应用数据空间的数据包划分轮次,
+/// 每个 ACK 帧至多提供一个 RTT 样本.
+/// 当本轮最小 RTT 相比上一轮明显增大时进入保守慢启动 (CSS),
+/// 若 CSS 持续若干轮后 RTT 仍未回落, 则退出慢启动进入拥塞避免.
+pub(crate) struct HyStart {
+    /// 应用数据空间中已发送的最大数据包编号
+    largest_sent: PacketNumber,
+
+    /// 当前轮开始后发送的首个数据包编号, 该数据包被确认时当前轮结束
+    window_end: Option<PacketNumber>,
+
+    /// 上一轮的最小 RTT
+    last_round_min_rtt: Option<Duration>,
+
+    /// 当前轮的最小 RTT
+    current_round_min_rtt: Option<Duration>,
+
+    /// 当前轮获得的 RTT 样本数
+    rtt_sample_count: usize,
+
+    /// 进入 CSS 时的最小 RTT; 不处于 CSS 时为 None
+    css_baseline_min_rtt: Option<Duration>,
+
+    /// 处于 CSS 的轮数
+    css_rounds: usize,
+}
+
+impl HyStart {
+    /// 构造一个 HyStart++
+    ///
+    /// # Returns
+    /// 返回一个 HyStart++
+    pub(crate) fn new() -> Self {
+        Self {
+            largest_sent: 0,
+            window_end: None,
+            last_round_min_rtt: None,
+            current_round_min_rtt: None,
+            rtt_sample_count: 0,
+            css_baseline_min_rtt: None,
+            css_rounds: 0,
+        }
+    }
+
+    /// 判断是否处于保守慢启动阶段
+    ///
+    /// # Returns
+    /// 返回是否处于保守慢启动阶段
+    #[inline(always)]
+    pub(crate) const fn in_css(&self) -> bool {
+        self.css_baseline_min_rtt.is_some()
+    }
+
+    /// 获取慢启动阶段拥塞窗口增长速度的除数
+    ///
+    /// # Returns
+    /// CSS 阶段返回 CSS_GROWTH_DIVISOR, 否则返回 1
+    #[inline(always)]
+    pub(crate) const fn get_growth_divisor(&self) -> usize {
+        if self.in_css() {
+            CSS_GROWTH_DIVISOR
+        } else {
+            1
+        }
+    }
+
+    /// 记录应用数据空间中已发送的数据包
+    ///
+    /// # Arguments
+    /// `packet_number` - 数据包编号
+    pub(crate) fn on_packet_sent(&mut self, packet_number: PacketNumber) {
+        self.largest_sent = self.largest_sent.max(packet_number);
+    }
+
+    /// 处理慢启动阶段应用数据空间中被确认的数据包, 确认了当前轮的结束数据包时开始新的一轮
+    ///
+    /// # Arguments
+    /// `packet_number` - 被确认的数据包编号
+    /// # Returns
+    /// 返回是否应退出慢启动
+    pub(crate) fn on_packet_acked(&mut self, packet_number: PacketNumber) -> bool {
+        if self
+            .window_end
+            .is_some_and(|window_end| packet_number < window_end)
+        {
+            return false;
+        }
+
+        if self.in_css() {
+            self.css_rounds += 1;
+            if self.css_rounds >= CSS_ROUNDS {
+                return true;
+            }
+        }
+
+        self.last_round_min_rtt = self.current_round_min_rtt;
+        self.current_round_min_rtt = None;
+        self.rtt_sample_count = 0;
+        self.window_end = Some(self.largest_sent + 1);
+        false
+    }
+
+    /// 处理慢启动阶段一个 ACK 帧产生的 RTT 样本
+    ///
+    /// # Arguments
+    /// `latest_rtt` - RTT 样本
+    pub(crate) fn on_rtt_sample(&mut self, latest_rtt: Duration) {
+        // 第一轮开始之前的样本不属于任何一轮
+        if self.window_end.is_none() {
+            return;
+        }
+
+        let current_round_min_rtt = self
+            .current_round_min_rtt
+            .map_or(latest_rtt, |rtt| rtt.min(latest_rtt));
+        self.current_round_min_rtt = Some(current_round_min_rtt);
+        self.rtt_sample_count += 1;
+
+        if self.rtt_sample_count < N_RTT_SAMPLE {
+            return;
+        }
+
+        match self.css_baseline_min_rtt {
+            None => {
+                if let Some(last_round_min_rtt) = self.last_round_min_rtt {
+                    let rtt_thresh = (last_round_min_rtt / MIN_RTT_DIVISOR)
+                        .clamp(MIN_RTT_THRESH, MAX_RTT_THRESH);
+                    if current_round_min_rtt >= last_round_min_rtt + rtt_thresh {
+                        self.css_baseline_min_rtt = Some(current_round_min_rtt);
+                        self.css_rounds = 0;
+                    }
+                }
+            }
+            Some(css_baseline_min_rtt) => {
+                // RTT 回落, 说明此前的增大是误判, 恢复慢启动.
+                if current_round_min_rtt < css_baseline_min_rtt {
+                    self.css_baseline_min_rtt = None;
+                }
+            }
+        }
+    }
+
+    /// 重置 HyStart++ 的状态, 用于重新进入慢启动
+    pub(crate) fn reset(&mut self) {
+        *self = Self {
+            largest_sent: self.largest_sent,
+            ..Self::new()
+        };
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use super::HyStart;
+
+/// 发送 8 个数据包并逐个确认, 每个 ACK 提供一个 RTT 样本, 返回是否应退出慢启动
+fn round(hystart: &mut HyStart, pn: &mut u64, rtt: u64) -> bool {
+    for i in 0..8 {
+        hystart.on_packet_sent(*pn + i);
+    }
+    let mut exit = false;
+    for _ in 0..8 {
+        exit |= hystart.on_packet_acked(*pn);
+        hystart.on_rtt_sample(Duration::from_millis(rtt));
+        *pn += 1;
+    }
+    exit
+}
+
+#[test]
+fn test_hystart_exit() {
+    let mut hystart = HyStart::new();
+    let mut pn = 0;
+
+    assert!(!round(&mut hystart, &mut pn, 100));
+    assert!(!hystart.in_css());
+
+    // RTT 增大超过阈值, 进入保守慢启动.
+    assert!(!round(&mut hystart, &mut pn, 120));
+    assert!(hystart.in_css());
+    assert_eq!(hystart.get_growth_divisor(), 4);
+
+    // 保守慢启动持续 5 轮后退出慢启动.
+    for _ in 0..4 {
+        assert!(!round(&mut hystart, &mut pn, 120));
+    }
+    assert!(round(&mut hystart, &mut pn, 120));
+}
+
+#[test]
+fn test_hystart_round_tracking() {
+    let mut hystart = HyStart::new();
+
+    // 第一轮开始之前的样本被忽略
+    for _ in 0..8 {
+        hystart.on_rtt_sample(Duration::from_millis(300));
+    }
+
+    // 确认数据包 0 开始第一轮, 该轮在确认此时已发送的数据包之后的数据包时结束
+    for pn in 0..=16 {
+        hystart.on_packet_sent(pn);
+    }
+    assert!(!hystart.on_packet_acked(0));
+    for pn in 1..=8 {
+        assert!(!hystart.on_packet_acked(pn));
+        hystart.on_rtt_sample(Duration::from_millis(100));
+    }
+    // 同一轮中 RTT 增大不会进入保守慢启动
+    for pn in 9..=16 {
+        assert!(!hystart.on_packet_acked(pn));
+        hystart.on_rtt_sample(Duration::from_millis(150));
+    }
+    assert!(!hystart.in_css());
+
+    // 确认数据包 17 开始第二轮, 本轮最小 RTT 相比上一轮增大
+    for pn in 17..=24 {
+        hystart.on_packet_sent(pn);
+    }
+    for pn in 17..=24 {
+        assert!(!hystart.on_packet_acked(pn));
+        hystart.on_rtt_sample(Duration::from_millis(150));
+    }
+    assert!(hystart.in_css());
+}
+
+#[test]
+fn test_hystart_css_back_to_slow_start() {
+    let mut hystart = HyStart::new();
+    let mut pn = 0;
+
+    assert!(!round(&mut hystart, &mut pn, 100));
+    assert!(!round(&mut hystart, &mut pn, 120));
+    assert!(hystart.in_css());
+
+    // RTT 回落到进入 CSS 时的基准之下, 恢复慢启动
+    assert!(!round(&mut hystart, &mut pn, 100));
+    assert!(!hystart.in_css());
+    assert_eq!(hystart.get_growth_divisor(), 1);
+
+    // 恢复慢启动后不再按 CSS 的轮数退出慢启动
+    for _ in 0..6 {
+        assert!(!round(&mut hystart, &mut pn, 100));
+    }
+}
@@ -0,0 +1,29 @@
+mod bbr;
+mod controller;
+mod cubic;
+mod hystart;
+mod new_reno;
+mod pacer;
+
+pub(crate) use bbr::*;
+pub(crate) use controller::*;
+pub(crate) use cubic::*;
+pub(crate) use hystart::*;
+pub(crate) use new_reno::*;
+pub(crate) use pacer::*;
+
+pub use bbr::{Bbr, BbrState};
+pub use controller::CongestionController;
+pub use cubic::Cubic;
+pub use new_reno::NewReno;
+
+#[cfg(test)]
+mod bbr_test;
+#[cfg(test)]
+mod cubic_test;
+#[cfg(test)]
+mod hystart_test;
+#[cfg(test)]
+mod new_reno_test;
+#[cfg(test)]
+mod pacer_test;
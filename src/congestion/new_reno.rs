@@ -0,0 +1,135 @@
+use std::time::Instant;
+
+use crate::recovery::{RttEstimator, SentPacket};
+
+use super::controller::CongestionController;
+
+/// 拥塞事件发生后拥塞窗口的缩减比例
+const LOSS_REDUCTION_FACTOR_NUMERATOR: usize = 1;
+const LOSS_REDUCTION_FACTOR_DENOMINATOR: usize = 2;
+
+/// 获取初始拥塞窗口, 即 min(10 * max_datagram_size, max(14720, 2 * max_datagram_size))
+///
+/// # Arguments
+/// `max_datagram_size` - 最大数据报大小
+/// # Returns
+/// 返回初始拥塞窗口
+pub(crate) fn initial_window(max_datagram_size: usize) -> usize {
+    (max_datagram_size * 10).min(14720.max(max_datagram_size * 2))
+}
+
+/// 获取最小拥塞窗口, 即 2 * max_datagram_size
+///
+/// # Arguments
+/// `max_datagram_size` - 最大数据报大小
+/// # Returns
+/// 返回最小拥塞窗口
+pub(crate) fn minimum_window(max_datagram_size: usize) -> usize {
+    max_datagram_size * 2
+}
+
+/// NewReno 拥塞控制
+///
+/// RFC 9002 中描述的默认拥塞控制算法.
+/// 慢启动阶段每确认一个字节, 拥塞窗口增加一个字节;
+/// 拥塞避免阶段每确认一个拥塞窗口的数据, 拥塞窗口增加一个最大数据报大小.
+///
+/// 发生拥塞事件后进入恢复期, 恢复期内发送的数据包被确认时才退出恢复期,
+/// 恢复期内不会重复缩减拥塞窗口.
+pub struct NewReno {
+    /// 最大数据报大小
+    max_datagram_size: usize,
+
+    /// 拥塞窗口
+    congestion_window: usize,
+
+    /// 慢启动阈值
+    ssthresh: usize,
+
+    /// 拥塞避免阶段已确认但尚未折算进拥塞窗口的字节数
+    bytes_acked: usize,
+
+    /// 当前恢复期的开始时刻
+    congestion_recovery_start_time: Option<Instant>,
+}
+
+impl NewReno {
+    /// 构造一个 NewReno 拥塞控制
+    ///
+    /// # Arguments
+    /// `max_datagram_size` - 最大数据报大小
+    /// # Returns
+    /// 返回一个 NewReno 拥塞控制
+    pub fn new(max_datagram_size: usize) -> Self {
+        Self {
+            max_datagram_size,
+            congestion_window: initial_window(max_datagram_size),
+            ssthresh: usize::MAX,
+            bytes_acked: 0,
+            congestion_recovery_start_time: None,
+        }
+    }
+
+    /// 获取慢启动阈值
+    ///
+    /// # Returns
+    /// 返回慢启动阈值
+    #[inline(always)]
+    pub const fn get_ssthresh(&self) -> usize {
+        self.ssthresh
+    }
+
+    /// 判断数据包是否在当前恢复期内发送
+    fn in_congestion_recovery(&self, time_sent: Instant) -> bool {
+        self.congestion_recovery_start_time
+            .is_some_and(|start| time_sent <= start)
+    }
+}
+
+impl CongestionController for NewReno {
+    fn on_packet_sent(&mut self, _packet: &SentPacket, _bytes_in_flight: usize) {}
+
+    fn on_ack(&mut self, _now: Instant, packet: &SentPacket, _rtt: &RttEstimator) {
+        // 恢复期内发送的数据包被确认时不增加拥塞窗口.
+        if self.in_congestion_recovery(packet.get_time_sent()) {
+            return;
+        }
+
+        if self.congestion_window < self.ssthresh {
+            self.congestion_window += packet.get_size();
+            return;
+        }
+
+        self.bytes_acked += packet.get_size();
+        if self.bytes_acked >= self.congestion_window {
+            self.bytes_acked -= self.congestion_window;
+            self.congestion_window += self.max_datagram_size;
+        }
+    }
+
+    fn on_congestion_event(&mut self, now: Instant, time_sent: Instant) {
+        if self.in_congestion_recovery(time_sent) {
+            return;
+        }
+
+        self.congestion_recovery_start_time = Some(now);
+        self.ssthresh = self.congestion_window * LOSS_REDUCTION_FACTOR_NUMERATOR
+            / LOSS_REDUCTION_FACTOR_DENOMINATOR;
+        self.congestion_window = self.ssthresh.max(minimum_window(self.max_datagram_size));
+        self.bytes_acked = 0;
+    }
+
+    fn on_persistent_congestion(&mut self, _now: Instant) {
+        self.congestion_window = minimum_window(self.max_datagram_size);
+        self.congestion_recovery_start_time = None;
+        self.bytes_acked = 0;
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.max_datagram_size);
+    }
+
+    fn get_window(&self) -> usize {
+        self.congestion_window
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+use crate::recovery::{RttEstimator, SentFrame, SentPacket};
+
+use super::{CongestionController, NewReno};
+
+#[test]
+fn test_new_reno_window() {
+    let start = Instant::now();
+    let rtt = RttEstimator::new();
+    let mut cc = NewReno::new(1200);
+    assert_eq!(cc.get_window(), 12000);
+
+    // 慢启动: 每确认一个字节拥塞窗口增加一个字节.
+    let packet = SentPacket::new(0, start, 1200, vec![SentFrame::Ping]);
+    cc.on_ack(start + Duration::from_millis(10), &packet, &rtt);
+    assert_eq!(cc.get_window(), 13200);
+
+    // 拥塞事件后窗口减半, 并进入恢复期.
+    let now = start + Duration::from_millis(20);
+    cc.on_congestion_event(now, start);
+    assert_eq!(cc.get_window(), 6600);
+    assert_eq!(cc.get_ssthresh(), 6600);
+
+    // 恢复期内发送的数据包丢失或被确认均不影响窗口.
+    cc.on_congestion_event(now, start + Duration::from_millis(5));
+    cc.on_ack(now, &packet, &rtt);
+    assert_eq!(cc.get_window(), 6600);
+
+    // 拥塞避免: 每确认一个拥塞窗口的数据, 窗口增加一个最大数据报大小.
+    for pn in 1..=6 {
+        let packet = SentPacket::new(pn, now + Duration::from_millis(1), 1100, vec![]);
+        cc.on_ack(now + Duration::from_millis(30), &packet, &rtt);
+    }
+    assert_eq!(cc.get_window(), 7800);
+
+    cc.on_persistent_congestion(now);
+    assert_eq!(cc.get_window(), 2400);
+
+    // 迁移到新路径后恢复初始状态
+    cc.reset();
+    assert_eq!(cc.get_window(), 12000);
+    assert_eq!(cc.get_ssthresh(), usize::MAX);
+}
@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+/// 未使用拥塞控制给出的发送速率时, 发送速率相对 cwnd / smoothed_rtt 的增益
+///
+/// 略大于 1 的增益可以避免因计时器精度不足而无法用满拥塞窗口.
+const PACING_GAIN_NUMERATOR: u64 = 5;
+const PACING_GAIN_DENOMINATOR: u64 = 4;
+
+/// 令牌桶容量对应的发送时长
+const BURST_INTERVAL: Duration = Duration::from_millis(2);
+
+/// 令牌桶容量的下限, 以数据报为单位
+const MIN_BURST_PACKETS: usize = 10;
+
+/// 令牌桶容量的上限, 以数据报为单位
+const MAX_BURST_PACKETS: usize = 256;
+
+/// 发送节奏控制
+///
+/// 以令牌桶的形式将一个拥塞窗口的数据均匀地分散到一个 smoothed_rtt 内发送,
+/// 避免一次性发出整个拥塞窗口的数据而导致路由器队列溢出.
+/// 令牌以发送速率持续补充, 桶的容量允许小规模的突发发送.
+pub(crate) struct Pacer {
+    /// 令牌桶容量, 以字节为单位
+    capacity: usize,
+
+    /// 剩余令牌, 以字节为单位
+    tokens: usize,
+
+    /// 上一次补充令牌的时刻
+    last_refill: Option<Instant>,
+}
+
+impl Pacer {
+    /// 构造一个发送节奏控制
+    ///
+    /// # Returns
+    /// 返回一个发送节奏控制
+    pub(crate) fn new() -> Self {
+        Self {
+            capacity: 0,
+            tokens: 0,
+            last_refill: None,
+        }
+    }
+
+    /// 获取下一个数据包的最早发送时刻
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `bytes` - 下一个数据包的大小
+    /// `max_datagram_size` - 最大数据报大小
+    /// `window` - 拥塞窗口
+    /// `smoothed_rtt` - 平滑 RTT
+    /// `pacing_rate` - 拥塞控制给出的发送速率 (字节/秒); 为 None 时由拥塞窗口与 RTT 计算
+    /// # Returns
+    /// 返回下一个数据包的最早发送时刻; 若当前即可发送, 则返回 `now`
+    pub(crate) fn get_send_time(
+        &mut self,
+        now: Instant,
+        bytes: usize,
+        max_datagram_size: usize,
+        window: usize,
+        smoothed_rtt: Duration,
+        pacing_rate: Option<u64>,
+    ) -> Instant {
+        let rate = match pacing_rate {
+            Some(rate) => rate,
+            None => {
+                let rtt = smoothed_rtt.as_micros().max(1) as u64;
+                window as u64 * 1_000_000 * PACING_GAIN_NUMERATOR / PACING_GAIN_DENOMINATOR / rtt
+            }
+        };
+        if rate == 0 {
+            return now;
+        }
+
+        let capacity = ((rate as u128 * BURST_INTERVAL.as_micros() / 1_000_000) as usize).clamp(
+            max_datagram_size * MIN_BURST_PACKETS,
+            max_datagram_size * MAX_BURST_PACKETS,
+        );
+        if capacity != self.capacity {
+            self.tokens = match self.last_refill {
+                // 首次发送时允许发出一个完整的突发.
+                None => capacity,
+                Some(_) => self.tokens.min(capacity),
+            };
+            self.capacity = capacity;
+        }
+
+        if let Some(last_refill) = self.last_refill {
+            let elapsed = now.saturating_duration_since(last_refill);
+            let refill = (rate as u128 * elapsed.as_micros() / 1_000_000) as usize;
+            if refill > 0 {
+                self.tokens = (self.tokens + refill).min(self.capacity);
+                self.last_refill = Some(now);
+            }
+        } else {
+            self.last_refill = Some(now);
+        }
+
+        if self.tokens >= bytes {
+            return now;
+        }
+
+        let deficit = (bytes - self.tokens) as u128;
+        let wait = Duration::from_micros((deficit * 1_000_000).div_ceil(rate as u128) as u64);
+        self.last_refill.unwrap_or(now) + wait
+    }
+
+    /// 记录一个数据包被发送, 消耗对应的令牌
+    ///
+    /// # Arguments
+    /// `bytes` - 数据包的大小
+    pub(crate) fn on_packet_sent(&mut self, bytes: usize) {
+        self.tokens = self.tokens.saturating_sub(bytes);
+    }
+}
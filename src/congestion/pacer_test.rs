@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+use super::Pacer;
+
+#[test]
+fn test_pacer_spreads_window_over_rtt() {
+    let start = Instant::now();
+    let rtt = Duration::from_millis(100);
+    let window = 1200 * 100;
+    let mut pacer = Pacer::new();
+
+    // 速率为 1.25 * 120000 / 100ms = 1.5MB/s, 首次允许 10 个数据报的突发.
+    for _ in 0..10 {
+        assert_eq!(
+            pacer.get_send_time(start, 1200, 1200, window, rtt, None),
+            start
+        );
+        pacer.on_packet_sent(1200);
+    }
+
+    // 令牌耗尽后需等待 1200 / 1.5MB/s = 800us.
+    let next = pacer.get_send_time(start, 1200, 1200, window, rtt, None);
+    assert_eq!(next, start + Duration::from_micros(800));
+    assert_eq!(
+        pacer.get_send_time(next, 1200, 1200, window, rtt, None),
+        next
+    );
+}
+
+#[test]
+fn test_pacer_uses_controller_rate() {
+    let start = Instant::now();
+    let rtt = Duration::from_millis(100);
+    let mut pacer = Pacer::new();
+
+    for _ in 0..10 {
+        pacer.get_send_time(start, 1200, 1200, 12000, rtt, Some(120_000));
+        pacer.on_packet_sent(1200);
+    }
+
+    let next = pacer.get_send_time(start, 1200, 1200, 12000, rtt, Some(120_000));
+    assert_eq!(next, start + Duration::from_millis(10));
+}
@@ -0,0 +1,61 @@
+use crate::{
+    attr::TransportParameters,
+    congestion::{CongestionController, NewReno},
+};
+
+use super::conn::MAX_DATAGRAM_SIZE;
+
+/// 连接配置
+///
+/// 由本端的传输参数与拥塞控制算法组成, 在构造连接时传入.
+/// 默认使用 NewReno 拥塞控制算法.
+pub struct Config {
+    /// 本端的传输参数
+    params: TransportParameters,
+
+    /// 拥塞控制算法
+    congestion_controller: Box<dyn CongestionController>,
+}
+
+impl Config {
+    /// 构造连接配置
+    ///
+    /// # Arguments
+    /// `params` - 本端的传输参数
+    /// # Returns
+    /// 返回使用 NewReno 拥塞控制算法的连接配置
+    pub fn new(params: TransportParameters) -> Self {
+        Self {
+            params,
+            congestion_controller: Box::new(NewReno::new(MAX_DATAGRAM_SIZE)),
+        }
+    }
+
+    /// 获取本端的传输参数
+    ///
+    /// # Returns
+    /// 返回本端的传输参数
+    #[inline(always)]
+    pub const fn get_params(&self) -> &TransportParameters {
+        &self.params
+    }
+
+    /// 设置拥塞控制算法, 例如 `Cubic` 或 `Bbr`
+    ///
+    /// # Arguments
+    /// `congestion_controller` - 拥塞控制算法
+    pub fn set_congestion_controller(
+        &mut self,
+        congestion_controller: Box<dyn CongestionController>,
+    ) {
+        self.congestion_controller = congestion_controller
+    }
+
+    /// 拆分为传输参数与拥塞控制算法, 用于构造连接
+    ///
+    /// # Returns
+    /// 返回传输参数与拥塞控制算法
+    pub(crate) fn into_parts(self) -> (TransportParameters, Box<dyn CongestionController>) {
+        (self.params, self.congestion_controller)
+    }
+}
@@ -0,0 +1,2302 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    attr::{
+        encode_packet_number_len, ConnectionID, EcnCodepoint, PacketNumber, PacketNumberSpace,
+        Serializer, Side, StreamDataGetter, StreamDirection, StreamIDGetter, StreamId,
+        TransportParameters, DEFAULT_ACTIVE_CONNECTION_ID_LIMIT, DEFAULT_MAX_ACK_DELAY,
+        QUIC_VERSION_1,
+    },
+    cid::{ConnectionIdGenerator, ConnectionIdManager, RandomConnectionIdGenerator},
+    congestion::Pacer,
+    crypto::{EncryptionLevel, Random, Session},
+    error::{ConnectionError, ConnectionIdError, StreamError, TransportError, TransportErrorCode},
+    flow_control::{ConnectionFlowControl, StreamFlowControl},
+    frame::{
+        ACKFrame, ConnectionCloseFrame, Frame, FrameParser, FrameType, PathChallengeFrame,
+        PathResponseFrame, PingFrame,
+    },
+    packet::{
+        protect_header, HandshakeHeader, InitialHeader, PacketBuilder, PacketHeader, PacketType,
+        PartialPacket, ShortHeader, MIN_INITIAL_SIZE,
+    },
+    recovery::{
+        ControlFrameTracker, LossDetector, ReceivedEcnCounts, RttEstimator, SentFrame, SentPacket,
+    },
+    stream::{Priority, RecvState, SendState, StreamCountManager, StreamMap, StreamScheduler},
+};
+
+use super::{
+    config::Config,
+    event::Event,
+    path::{PathValidator, PendingPathResponse},
+    space::PacketSpace,
+    transmit::Transmit,
+};
+
+/// 发送的 UDP 数据报的最大字节数, 未进行路径 MTU 探测时使用 QUIC 允许的最小值
+pub(crate) const MAX_DATAGRAM_SIZE: usize = 1200;
+
+/// Connection ID 的最大字节数 (RFC 9000 §17.2)
+const MAX_CID_LEN: usize = 20;
+
+/// 接收窗口自动调整的上限
+const MAX_RECV_WINDOW: usize = 16 * 1024 * 1024;
+
+/// 地址验证之前, 服务端最多发送收到数据量的倍数 (RFC 9000 §8.1)
+const AMPLIFICATION_FACTOR: usize = 3;
+
+/// 每个加密级别最多缓存的尚未交给会话的握手数据
+const MAX_CRYPTO_BUFFER: usize = 64 * 1024;
+
+/// 关闭后保留连接状态的时长, 以 PTO 计 (RFC 9000 §10.2)
+const CLOSE_PTO_COUNT: u32 = 3;
+
+/// 最多同时等待发送的 PATH_RESPONSE 帧数量, 超出后不再应答新路径上的 PATH_CHALLENGE 帧
+const MAX_PATH_RESPONSES: usize = 4;
+
+/// 空闲超时时长的下限, 以 PTO 计, 避免在数据包重传期间超时 (RFC 9000 §10.1)
+const MIN_IDLE_PTO_COUNT: u32 = 3;
+
+/// 连接状态
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+    /// 正在握手
+    Handshake,
+
+    /// 握手完成
+    Established,
+
+    /// 本端已关闭连接, 以 CONNECTION_CLOSE 帧应答收到的数据包 (RFC 9000 §10.2.1)
+    Closing,
+
+    /// 对端已关闭连接, 不再发送任何数据包 (RFC 9000 §10.2.2)
+    Draining,
+
+    /// 连接状态已释放
+    Closed,
+}
+
+impl State {
+    /// 判断连接是否已经开始关闭
+    ///
+    /// # Returns
+    /// 处于 closing、draining 或已释放时返回 true
+    const fn is_closed(&self) -> bool {
+        matches!(self, State::Closing | State::Draining | State::Closed)
+    }
+}
+
+/// QUIC 连接
+///
+/// 不持有套接字与时钟的连接状态机: 调用者将收到的数据报交给 `handle_datagram`,
+/// 通过 `poll_transmit` 取出待发送的数据报, 在 `poll_timeout` 给出的时刻调用
+/// `handle_timeout`, 并通过 `poll_event` 获取连接与流的事件.
+///
+/// 连接将包头、帧、数据包编号空间、加密握手、丢包恢复与流组合在一起.
+pub struct Connection {
+    /// 本端角色
+    side: Side,
+
+    /// 连接状态
+    state: State,
+
+    /// 加密握手会话
+    session: Box<dyn Session>,
+
+    /// 加密库的随机数生成器, 用于生成 PATH_CHALLENGE 数据
+    random: Box<dyn Random>,
+
+    /// 本端的 Connection ID
+    local_cid: ConnectionID,
+
+    /// 两个方向上的 Connection ID
+    cids: ConnectionIdManager,
+
+    /// 是否已从对端的首个数据包中得知其 Connection ID
+    remote_cid_confirmed: bool,
+
+    /// 对端地址
+    remote: SocketAddr,
+
+    /// 对端迁移之前已验证的地址, 新地址的路径验证失败后恢复使用
+    previous_remote: Option<SocketAddr>,
+
+    /// 正在进行的路径验证
+    path_validation: Option<PathValidator>,
+
+    /// 待发送的 PATH_RESPONSE 帧
+    path_responses: VecDeque<PendingPathResponse>,
+
+    /// Initial、Handshake 与应用数据编号空间
+    spaces: [PacketSpace; 3],
+
+    /// 本端通告的传输参数
+    local_params: TransportParameters,
+
+    /// 对端通告的传输参数, 握手完成前为默认值
+    peer_params: TransportParameters,
+
+    /// 丢包检测与拥塞控制
+    loss: LossDetector,
+
+    /// 收到的数据包所携带的 ECN 码点计数, 在 ACK 帧中通告给对端
+    ecn_counts: ReceivedEcnCounts,
+
+    /// 发送节奏控制
+    pacer: Pacer,
+
+    /// 发送节奏限制解除的时刻
+    pacing_deadline: Option<Instant>,
+
+    /// 连接级流量控制
+    flow_control: ConnectionFlowControl,
+
+    /// 各个流的流量控制
+    stream_flow_control: BTreeMap<StreamId, StreamFlowControl>,
+
+    /// 流集合
+    streams: StreamMap,
+
+    /// 流数量管理
+    stream_counts: StreamCountManager,
+
+    /// 流调度
+    scheduler: StreamScheduler,
+
+    /// 控制帧重传跟踪
+    control_frames: ControlFrameTracker,
+
+    /// 待取出的事件
+    events: VecDeque<Event>,
+
+    /// 对端地址是否已验证
+    address_validated: bool,
+
+    /// 收到的字节数, 用于地址验证前的放大限制
+    bytes_received: usize,
+
+    /// 发送的字节数, 用于地址验证前的放大限制
+    bytes_sent: usize,
+
+    /// 握手是否已确认
+    handshake_confirmed: bool,
+
+    /// 空闲超时的时刻
+    idle_deadline: Option<Instant>,
+
+    /// 自上次收到数据包以来尚未发送 ack-eliciting 数据包,
+    /// 下一个 ack-eliciting 数据包发送时重新开始空闲计时
+    idle_restart_on_send: bool,
+
+    /// 保活间隔, 空闲达到该时长后发送 PING 帧
+    keep_alive_interval: Option<Duration>,
+
+    /// 发送保活 PING 帧的时刻
+    keep_alive_deadline: Option<Instant>,
+
+    /// 是否需要发送 PING 帧
+    ping_pending: bool,
+
+    /// 连接关闭的原因
+    close_error: Option<ConnectionError>,
+
+    /// 是否需要发送 CONNECTION_CLOSE 帧
+    close_pending: bool,
+
+    /// closing 或 draining 状态结束、释放连接状态的时刻
+    close_deadline: Option<Instant>,
+
+    /// closing 状态中收到的数据报数量
+    close_datagrams: u64,
+
+    /// closing 状态中收到的数据报数量达到该值时再次发送 CONNECTION_CLOSE 帧,
+    /// 每次发送后翻倍, 以限制应答的速率
+    close_next_response: u64,
+}
+
+impl Connection {
+    /// 构造客户端连接
+    ///
+    /// # Arguments
+    /// `remote` - 服务端地址
+    /// `local_cid` - 本端的 Connection ID
+    /// `remote_cid` - 客户端选择的目标 Connection ID, 用于派生 Initial 密钥
+    /// `config` - 连接配置
+    /// `session` - 加密握手会话
+    /// # Returns
+    /// 返回客户端连接, 首个 Initial 数据包由 `poll_transmit` 取出;
+    /// Connection ID 超过 20 字节时返回错误
+    pub fn connect(
+        remote: SocketAddr,
+        local_cid: &[u8],
+        remote_cid: &[u8],
+        config: Config,
+        session: Box<dyn Session>,
+    ) -> Result<Self, ConnectionIdError> {
+        if remote_cid.len() > MAX_CID_LEN {
+            return Err(ConnectionIdError::InvalidLength(remote_cid.len()));
+        }
+
+        let mut conn = Self::new(Side::Client, remote, local_cid, config, session)?;
+        conn.cids.set_initial_remote(connection_id(remote_cid));
+
+        let keys = conn.session.initial_keys(remote_cid, Side::Client);
+        conn.spaces[PacketNumberSpace::Initial.index()].set_keys(keys);
+        conn.poll_session();
+        Ok(conn)
+    }
+
+    /// 构造服务端连接
+    ///
+    /// # Arguments
+    /// `remote` - 客户端地址
+    /// `local_cid` - 本端的 Connection ID
+    /// `original_dst_cid` - 客户端首个 Initial 数据包的目标 Connection ID
+    /// `config` - 连接配置
+    /// `session` - 加密握手会话
+    /// # Returns
+    /// 返回服务端连接, 之后将客户端的首个数据报交给 `handle_datagram`;
+    /// Connection ID 超过 20 字节时返回错误
+    pub fn accept(
+        remote: SocketAddr,
+        local_cid: &[u8],
+        original_dst_cid: &[u8],
+        config: Config,
+        session: Box<dyn Session>,
+    ) -> Result<Self, ConnectionIdError> {
+        if original_dst_cid.len() > MAX_CID_LEN {
+            return Err(ConnectionIdError::InvalidLength(original_dst_cid.len()));
+        }
+
+        let mut conn = Self::new(Side::Server, remote, local_cid, config, session)?;
+
+        let keys = conn.session.initial_keys(original_dst_cid, Side::Server);
+        conn.spaces[PacketNumberSpace::Initial.index()].set_keys(keys);
+        Ok(conn)
+    }
+
+    fn new(
+        side: Side,
+        remote: SocketAddr,
+        local_cid: &[u8],
+        config: Config,
+        session: Box<dyn Session>,
+    ) -> Result<Self, ConnectionIdError> {
+        if local_cid.len() > MAX_CID_LEN {
+            return Err(ConnectionIdError::InvalidLength(local_cid.len()));
+        }
+        // 零长度的 Connection ID 不能通过 NEW_CONNECTION_ID 帧提供新的 Connection ID
+        let generator: Option<Box<dyn ConnectionIdGenerator>> = if local_cid.is_empty() {
+            None
+        } else {
+            Some(Box::new(RandomConnectionIdGenerator::new(
+                local_cid.len(),
+                session.random(),
+            )?))
+        };
+
+        let (params, congestion_controller) = config.into_parts();
+        let peer_params = TransportParameters::new();
+        Ok(Self {
+            side,
+            state: State::Handshake,
+            random: session.random(),
+            session,
+            local_cid: connection_id(local_cid),
+            cids: ConnectionIdManager::new(
+                connection_id(local_cid),
+                ConnectionID::new(),
+                params.get_active_connection_id_limit(),
+                generator,
+            ),
+            remote_cid_confirmed: false,
+            remote,
+            previous_remote: None,
+            path_validation: None,
+            path_responses: VecDeque::new(),
+            spaces: [PacketSpace::new(), PacketSpace::new(), PacketSpace::new()],
+            loss: LossDetector::new(
+                side == Side::Server,
+                DEFAULT_MAX_ACK_DELAY,
+                congestion_controller,
+            ),
+            ecn_counts: ReceivedEcnCounts::new(),
+            pacer: Pacer::new(),
+            pacing_deadline: None,
+            flow_control: ConnectionFlowControl::new(
+                params.get_initial_max_data(),
+                MAX_RECV_WINDOW,
+            ),
+            stream_flow_control: BTreeMap::new(),
+            streams: StreamMap::new(side),
+            stream_counts: StreamCountManager::new(side, &params, &peer_params),
+            scheduler: StreamScheduler::new(),
+            control_frames: ControlFrameTracker::new(),
+            events: VecDeque::new(),
+            // 客户端发送的数据不受放大限制
+            address_validated: side == Side::Client,
+            bytes_received: 0,
+            bytes_sent: 0,
+            handshake_confirmed: false,
+            idle_deadline: None,
+            idle_restart_on_send: true,
+            keep_alive_interval: None,
+            keep_alive_deadline: None,
+            ping_pending: false,
+            close_error: None,
+            close_pending: false,
+            close_deadline: None,
+            close_datagrams: 0,
+            close_next_response: 1,
+            local_params: params,
+            peer_params,
+        })
+    }
+
+    /// 获取本端角色
+    ///
+    /// # Returns
+    /// 返回本端角色
+    #[inline(always)]
+    pub const fn get_side(&self) -> Side {
+        self.side
+    }
+
+    /// 获取对端地址
+    ///
+    /// # Returns
+    /// 返回对端地址
+    #[inline(always)]
+    pub const fn get_remote(&self) -> SocketAddr {
+        self.remote
+    }
+
+    /// 获取 RTT 估算
+    ///
+    /// # Returns
+    /// 返回 RTT 估算
+    #[inline(always)]
+    pub const fn get_rtt(&self) -> &RttEstimator {
+        self.loss.get_rtt()
+    }
+
+    /// 判断握手是否已完成
+    ///
+    /// # Returns
+    /// 返回握手是否已完成
+    #[inline(always)]
+    pub fn is_established(&self) -> bool {
+        self.state == State::Established
+    }
+
+    /// 判断连接是否正在关闭, 即处于 closing 或 draining 状态
+    ///
+    /// # Returns
+    /// 返回连接是否正在关闭
+    #[inline(always)]
+    pub fn is_closing(&self) -> bool {
+        matches!(self.state, State::Closing | State::Draining)
+    }
+
+    /// 判断连接是否处于 draining 状态
+    ///
+    /// # Returns
+    /// 返回连接是否处于 draining 状态
+    #[inline(always)]
+    pub fn is_draining(&self) -> bool {
+        self.state == State::Draining
+    }
+
+    /// 判断连接状态是否已释放, 之后可以丢弃该连接
+    ///
+    /// # Returns
+    /// 返回连接状态是否已释放
+    #[inline(always)]
+    pub fn is_closed(&self) -> bool {
+        self.state == State::Closed
+    }
+
+    /// 设置保活间隔, 连接空闲达到该时长后发送 PING 帧以维持 NAT 绑定并避免空闲超时
+    ///
+    /// # Arguments
+    /// `interval` - 保活间隔, 为 None 时不发送保活 PING 帧
+    pub fn set_keep_alive_interval(&mut self, interval: Option<Duration>) {
+        self.keep_alive_interval = interval;
+        self.keep_alive_deadline = None;
+    }
+
+    /// 替换 Connection ID 生成器, 生成的 Connection ID 长度必须与本端建立连接时的
+    /// Connection ID 相同; 需要在握手完成之前设置
+    ///
+    /// # Arguments
+    /// `generator` - Connection ID 生成器
+    /// # Returns
+    /// 握手已经完成或 Connection ID 长度不一致时返回错误
+    pub fn set_connection_id_generator(
+        &mut self,
+        generator: Box<dyn ConnectionIdGenerator>,
+    ) -> Result<(), ConnectionIdError> {
+        if self.state != State::Handshake {
+            return Err(ConnectionIdError::InvalidState);
+        }
+        let expected = self.local_cid.get_id().len();
+        if generator.cid_len() != expected {
+            return Err(ConnectionIdError::LengthMismatch {
+                expected,
+                actual: generator.cid_len(),
+            });
+        }
+
+        self.cids.set_generator(generator);
+        Ok(())
+    }
+
+    /// 获取本端提供且尚未被对端废弃的 Connection ID, 目标为其中任意一个的数据报都属于该连接
+    ///
+    /// # Returns
+    /// 返回本端 Connection ID
+    pub fn get_local_connection_ids(&self) -> Vec<&[u8]> {
+        self.cids.get_local().map(ConnectionID::get_id).collect()
+    }
+
+    /// 换用对端提供的下一个 Connection ID, 并通过 RETIRE_CONNECTION_ID 帧废弃正在使用的 Connection ID
+    ///
+    /// # Returns
+    /// 返回是否换用了新的 Connection ID, 对端没有提供未使用的 Connection ID 时返回 false
+    pub fn rotate_connection_id(&mut self) -> bool {
+        self.state == State::Established && self.cids.rotate_remote(&mut self.control_frames)
+    }
+
+    /// 验证当前对端地址的可达性, 例如怀疑发生了 NAT 重新绑定时;
+    /// 结果以 `Event::PathValidated` 或 `Event::PathValidationFailed` 事件通知
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    pub fn validate_path(&mut self, now: Instant) {
+        if self.state != State::Established || self.path_validation.is_some() {
+            return;
+        }
+
+        self.path_validation = Some(PathValidator::new(self.remote, now, self.get_pto()));
+    }
+
+    /// 获取连接关闭的原因
+    ///
+    /// # Returns
+    /// 返回连接错误, 连接尚未关闭时返回 None
+    #[inline(always)]
+    pub const fn get_close_error(&self) -> Option<&ConnectionError> {
+        self.close_error.as_ref()
+    }
+
+    /// 取出一个连接事件
+    ///
+    /// # Returns
+    /// 返回最早产生的事件, 没有事件时返回 None
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    /// 处理收到的 UDP 数据报, 其中可能合并了多个数据包
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `remote` - 数据报的来源地址
+    /// `ecn` - 数据报 IP 头中的 ECN 码点
+    /// `data` - 数据报的内容
+    pub fn handle_datagram(
+        &mut self,
+        now: Instant,
+        remote: SocketAddr,
+        ecn: EcnCodepoint,
+        data: &[u8],
+    ) {
+        // 握手确认之前客户端不能迁移, 丢弃来自其他地址的数据报 (RFC 9000 §9)
+        let on_path = remote == self.remote;
+        let may_migrate = self.side == Side::Server && self.handshake_confirmed;
+        if !(on_path || may_migrate) {
+            return;
+        }
+        match self.state {
+            State::Closing if on_path => return self.on_closing_datagram(data.len()),
+            State::Closing | State::Draining | State::Closed => return,
+            State::Handshake | State::Established => {}
+        }
+        if on_path {
+            self.bytes_received += data.len();
+        }
+
+        let mut buf = data.to_vec();
+        let mut offset = 0;
+        while offset < buf.len() {
+            let Ok(packet) = PartialPacket::parse(&buf[offset..], self.local_cid.get_id().len())
+            else {
+                break;
+            };
+            let end = offset + packet.get_len();
+            let bytes = &mut buf[offset..end];
+            if let Err(error) = self.handle_packet(now, remote, ecn, data.len(), &packet, bytes) {
+                self.close_with_error(now, error);
+            }
+            if self.state.is_closed() {
+                return;
+            }
+            offset = end;
+        }
+
+        self.collect_closed_streams();
+    }
+
+    /// 取出一个待发送的数据报
+    ///
+    /// 依次在 Initial、Handshake 与应用数据空间中组装数据包, 并合并到同一个数据报中.
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// # Returns
+    /// 返回待发送的数据报, 没有可发送的数据或受拥塞控制限制时返回 None
+    pub fn poll_transmit(&mut self, now: Instant) -> Option<Transmit> {
+        if self.state.is_closed() {
+            return self.poll_close_transmit();
+        }
+        if let Some(transmit) = self.poll_path_response_transmit() {
+            return Some(transmit);
+        }
+
+        let mut max_size = MAX_DATAGRAM_SIZE;
+        if !self.address_validated {
+            max_size = max_size
+                .min((self.bytes_received * AMPLIFICATION_FACTOR).saturating_sub(self.bytes_sent));
+        }
+
+        let window = self.loss.get_congestion_window();
+        let mut blocked = self.loss.get_bytes_in_flight() >= window;
+        if !blocked {
+            let send_time = self.pacer.get_send_time(
+                now,
+                MAX_DATAGRAM_SIZE,
+                MAX_DATAGRAM_SIZE,
+                window,
+                self.loss.get_rtt().get_smoothed_rtt(),
+                self.loss.get_congestion().get_pacing_rate(),
+            );
+            blocked = send_time > now;
+            self.pacing_deadline = blocked.then_some(send_time);
+        }
+
+        let Some(last) = PacketNumberSpace::ALL
+            .into_iter()
+            .rev()
+            .find(|&space| self.wants_to_send(now, space, blocked))
+        else {
+            if !blocked {
+                self.loss.on_app_limited();
+            }
+            self.pacing_deadline = None;
+            return None;
+        };
+
+        // 携带 PATH_CHALLENGE 或 PATH_RESPONSE 帧的数据报同样需要填充 (RFC 9000 §8.2.1)
+        let has_path_frames = self.has_path_frames();
+        // 发送数据包会推进 ECN 验证的状态, 数据报中的数据包与数据报本身使用发送前的码点
+        let ecn = self.loss.get_ecn_codepoint();
+        let mut datagram = Vec::new();
+        let mut has_initial = false;
+        for space in PacketNumberSpace::ALL {
+            // 包含 Initial 数据包的数据报需要填充到 MIN_INITIAL_SIZE 字节
+            let is_last = space == last;
+            let padded = has_initial
+                || space == PacketNumberSpace::Initial
+                || (space == PacketNumberSpace::ApplicationData && has_path_frames);
+            let min_size = if is_last && padded {
+                MIN_INITIAL_SIZE.saturating_sub(datagram.len())
+            } else {
+                0
+            };
+
+            let remaining = max_size.saturating_sub(datagram.len());
+            if let Some(packet) = self.build_packet(now, space, remaining, min_size, blocked, ecn) {
+                has_initial |= space == PacketNumberSpace::Initial;
+                datagram.extend_from_slice(&packet);
+            }
+            if is_last {
+                break;
+            }
+        }
+
+        if datagram.is_empty() {
+            return None;
+        }
+        self.bytes_sent += datagram.len();
+        self.pacer.on_packet_sent(datagram.len());
+
+        Some(Transmit::new(self.remote, ecn, datagram))
+    }
+
+    /// 获取下一次需要调用 `handle_timeout` 的时刻
+    ///
+    /// # Returns
+    /// 返回丢包检测、延迟 ACK、发送节奏、空闲超时与保活定时器中最早的时刻,
+    /// 没有定时器时返回 None; 正在关闭时返回释放连接状态的时刻
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        if self.state.is_closed() {
+            return self.close_deadline;
+        }
+
+        let ack_deadlines = self
+            .spaces
+            .iter()
+            .filter(|space| space.get_keys().is_some())
+            .filter_map(|space| space.get_received().get_ack_deadline());
+
+        let keep_alive_deadline = self
+            .keep_alive_deadline
+            .filter(|_| self.state == State::Established);
+
+        self.loss
+            .get_loss_detection_timer()
+            .into_iter()
+            .chain(ack_deadlines)
+            .chain(self.pacing_deadline)
+            .chain(self.idle_deadline)
+            .chain(keep_alive_deadline)
+            .chain(
+                self.path_validation
+                    .as_ref()
+                    .map(PathValidator::get_timeout),
+            )
+            .min()
+    }
+
+    /// 处理到期的定时器
+    ///
+    /// 延迟 ACK、发送节奏、保活与路径验证重发定时器到期后, 由之后的 `poll_transmit` 发送数据包;
+    /// 空闲超时到期后不发送 CONNECTION_CLOSE 帧, 直接释放连接状态.
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    pub fn handle_timeout(&mut self, now: Instant) {
+        if self.state.is_closed() {
+            if self.close_deadline.is_some_and(|deadline| deadline <= now) {
+                self.release();
+            }
+            return;
+        }
+
+        if self.idle_deadline.is_some_and(|deadline| deadline <= now) {
+            self.events
+                .push_back(Event::Closed(ConnectionError::TimedOut));
+            self.close_error = Some(ConnectionError::TimedOut);
+            self.release();
+            return;
+        }
+
+        if self.pacing_deadline.is_some_and(|deadline| deadline <= now) {
+            self.pacing_deadline = None;
+        }
+
+        if self
+            .path_validation
+            .as_mut()
+            .is_some_and(|validation| validation.on_timeout(now))
+        {
+            self.on_path_validation_failed();
+        }
+
+        if self.state == State::Established
+            && self
+                .keep_alive_deadline
+                .is_some_and(|deadline| deadline <= now)
+        {
+            // 收到对端的数据包或再次重新开始空闲计时后才重新设置保活定时器
+            self.keep_alive_deadline = None;
+            self.ping_pending = true;
+        }
+
+        if self
+            .loss
+            .get_loss_detection_timer()
+            .is_some_and(|timer| timer <= now)
+        {
+            let space = self.loss.get_loss_space();
+            let lost = self.loss.on_loss_detection_timeout(now);
+            if let Some(space) = space {
+                self.on_packets_lost(space, &lost);
+            }
+        }
+    }
+
+    /// 打开一个本端发起的流
+    ///
+    /// # Arguments
+    /// `direction` - 流的方向
+    /// # Returns
+    /// 返回流标识; 握手尚未完成或超过对端允许的流数量时返回 None
+    pub fn open_stream(&mut self, direction: StreamDirection) -> Option<StreamId> {
+        if self.state != State::Established {
+            return None;
+        }
+
+        let stream_id = self.stream_counts.open_local(direction)?;
+        self.create_stream(stream_id);
+        Some(stream_id)
+    }
+
+    /// 向流写入数据
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `data` - 待发送的数据
+    /// # Returns
+    /// 返回写入的字节数; 流不存在、已结束或已被重置时返回错误
+    pub fn write(&mut self, stream_id: StreamId, data: &[u8]) -> Result<usize, StreamError> {
+        let stream = self
+            .streams
+            .get_send_mut(stream_id)
+            .ok_or(StreamError::UnknownStream)?;
+        let size = stream.write(data)?;
+        self.scheduler.push(stream_id);
+        Ok(size)
+    }
+
+    /// 结束流的发送, 之后发送携带 FIN 的 STREAM 帧
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// # Returns
+    /// 流不存在、已结束或已被重置时返回错误
+    pub fn finish(&mut self, stream_id: StreamId) -> Result<(), StreamError> {
+        let stream = self
+            .streams
+            .get_send_mut(stream_id)
+            .ok_or(StreamError::UnknownStream)?;
+        stream.finish()?;
+        self.scheduler.push(stream_id);
+        Ok(())
+    }
+
+    /// 从流读取数据
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `buf` - 接收数据的缓冲区
+    /// # Returns
+    /// 返回读取的字节数, 流已结束且数据已读完时返回 0; 流不存在或已被重置时返回错误
+    pub fn read(&mut self, stream_id: StreamId, buf: &mut [u8]) -> Result<usize, StreamError> {
+        let stream = self
+            .streams
+            .get_recv_mut(stream_id)
+            .ok_or(StreamError::UnknownStream)?;
+        let result = stream.read(buf);
+
+        self.release_stream_credit(stream_id);
+        self.collect_closed_streams();
+        result
+    }
+
+    /// 获取流的发送状态
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// # Returns
+    /// 返回发送流状态; 流不存在、已关闭或没有发送部分时返回 None
+    pub fn get_send_state(&self, stream_id: StreamId) -> Option<SendState> {
+        self.streams
+            .get_send(stream_id)
+            .map(|stream| stream.get_state())
+    }
+
+    /// 获取流的接收状态
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// # Returns
+    /// 返回接收流状态; 流不存在、已关闭或没有接收部分时返回 None
+    pub fn get_recv_state(&self, stream_id: StreamId) -> Option<RecvState> {
+        self.streams
+            .get_recv(stream_id)
+            .map(|stream| stream.get_state())
+    }
+
+    /// 重置流的发送部分, 之后发送 RESET_STREAM 帧
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `error_code` - 应用错误码
+    /// # Returns
+    /// 流不存在或没有发送部分时返回错误
+    pub fn reset_stream(
+        &mut self,
+        stream_id: StreamId,
+        error_code: u64,
+    ) -> Result<(), StreamError> {
+        self.streams.reset(stream_id, error_code)?;
+        self.scheduler.remove(stream_id);
+        Ok(())
+    }
+
+    /// 以 RESET_STREAM_AT 重置流的发送部分, 可靠大小之前的数据仍会被交付
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `error_code` - 应用错误码
+    /// `reliable_size` - 可靠大小, 不超过流量控制允许发送的最大偏移量
+    /// # Returns
+    /// 流不存在、没有发送部分或双方未协商 reset_stream_at 时返回错误
+    pub fn reset_stream_at(
+        &mut self,
+        stream_id: StreamId,
+        error_code: u64,
+        reliable_size: usize,
+    ) -> Result<(), StreamError> {
+        let max_offset = self.get_send_limit(stream_id);
+        let charged = self.get_charged_offset(stream_id);
+        self.streams
+            .reset_at(stream_id, error_code, reliable_size, max_offset)?;
+
+        // 最终大小超过已发送的数据时, 超出的部分同样消耗流量控制信用
+        let size = self.get_charged_offset(stream_id).saturating_sub(charged);
+        self.flow_control.on_data_sent(size);
+        if let Some(fc) = self.stream_flow_control.get_mut(&stream_id) {
+            fc.on_data_sent(size);
+        }
+        Ok(())
+    }
+
+    /// 要求对端停止在流上发送, 之后发送 STOP_SENDING 帧
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `error_code` - 应用错误码
+    /// # Returns
+    /// 流不存在或没有接收部分时返回错误
+    pub fn stop_sending(
+        &mut self,
+        stream_id: StreamId,
+        error_code: u64,
+    ) -> Result<(), StreamError> {
+        self.streams.stop_sending(stream_id, error_code)
+    }
+
+    /// 设置流的调度优先级
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `priority` - 优先级
+    /// # Returns
+    /// 流不存在或没有发送部分时返回错误
+    pub fn set_priority(
+        &mut self,
+        stream_id: StreamId,
+        priority: Priority,
+    ) -> Result<(), StreamError> {
+        if self.streams.get_send(stream_id).is_none() {
+            return Err(StreamError::UnknownStream);
+        }
+
+        self.scheduler.set_priority(stream_id, priority);
+        Ok(())
+    }
+
+    /// 以应用层错误关闭连接, 进入 closing 状态并发送 CONNECTION_CLOSE (0x1d) 帧
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `error_code` - 应用错误码
+    /// `reason` - 关闭原因
+    pub fn close(&mut self, now: Instant, error_code: u64, reason: &str) {
+        if self.state.is_closed() {
+            return;
+        }
+
+        self.close_with(now, ConnectionError::application(error_code, reason));
+    }
+
+    /// 处理数据报中的一个数据包
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `path` - 数据报的来源地址
+    /// `ecn` - 数据报的 ECN 码点
+    /// `datagram_len` - 数据报的字节数
+    /// `packet` - 尚未解密的数据包
+    /// `bytes` - 数据包的字节
+    /// # Returns
+    /// 数据包中的帧违反协议时返回传输层错误
+    fn handle_packet(
+        &mut self,
+        now: Instant,
+        path: SocketAddr,
+        ecn: EcnCodepoint,
+        datagram_len: usize,
+        packet: &PartialPacket,
+        bytes: &mut [u8],
+    ) -> Result<(), TransportError> {
+        let Some(space) = packet.get_space() else {
+            return Ok(());
+        };
+        let packet_type = packet.get_type();
+        // 其他路径上只处理 1-RTT 数据包
+        if path != self.remote && packet_type != PacketType::Short {
+            return Ok(());
+        }
+        if packet_type == PacketType::ZeroRTT
+            || (packet_type != PacketType::Short && packet.get_version() != QUIC_VERSION_1)
+        {
+            return Ok(());
+        }
+        // 握手完成之前不处理 1-RTT 数据包
+        if space == PacketNumberSpace::ApplicationData && self.state == State::Handshake {
+            return Ok(());
+        }
+
+        let index = space.index();
+        let Some(keys) = self.spaces[index].get_keys() else {
+            return Ok(());
+        };
+        let largest = self.spaces[index].get_received().get_largest();
+        let Some(decrypted) = packet.decrypt(bytes, keys.get_remote(), largest) else {
+            return Ok(());
+        };
+        let packet_number = decrypted.get_packet_number();
+        if self.spaces[index]
+            .get_received()
+            .is_duplicate(packet_number)
+        {
+            return Ok(());
+        }
+        self.restart_idle_timer(now);
+        self.idle_restart_on_send = true;
+        self.ecn_counts.on_packet_received(space, ecn);
+
+        if packet_type == PacketType::Initial && !self.remote_cid_confirmed {
+            self.cids.set_initial_remote(*packet.get_src());
+            self.remote_cid_confirmed = true;
+        }
+        // 服务端成功处理 Handshake 数据包即完成了对客户端地址的验证
+        if packet_type == PacketType::Handshake && self.side == Side::Server {
+            self.address_validated = true;
+            self.discard_space(now, PacketNumberSpace::Initial);
+        }
+
+        if decrypted.get_payload().is_empty() {
+            return Err(TransportError::new(
+                TransportErrorCode::ProtocolViolation,
+                0,
+                "packet without frames",
+            ));
+        }
+
+        let mut ack_eliciting = false;
+        let mut probing = true;
+        for frame in FrameParser::new(decrypted.get_payload()) {
+            let (type_byte, frame) = frame?;
+            if space != PacketNumberSpace::ApplicationData && !frame.is_allowed_in_handshake() {
+                return Err(TransportError::new(
+                    TransportErrorCode::ProtocolViolation,
+                    type_byte as u64,
+                    "frame not allowed in handshake packets",
+                ));
+            }
+
+            ack_eliciting |= frame.is_ack_eliciting();
+            probing &= frame.is_probing();
+            self.handle_frame(now, path, datagram_len, space, type_byte, frame)?;
+            if self.state.is_closed() {
+                return Ok(());
+            }
+        }
+
+        // 对端以编号最大的非探测数据包表明迁移到了新的地址 (RFC 9000 §9.3)
+        if path != self.remote && !probing && largest.is_none_or(|largest| packet_number > largest)
+        {
+            self.migrate(now, path, datagram_len);
+        }
+
+        self.spaces[index].get_received_mut().on_packet_received(
+            now,
+            space,
+            packet_number,
+            ack_eliciting,
+            self.local_params.get_max_ack_delay(),
+        );
+        Ok(())
+    }
+
+    /// 处理数据包中的一个帧
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `path` - 数据报的来源地址
+    /// `datagram_len` - 数据报的字节数
+    /// `space` - 数据包所在的编号空间
+    /// `type_byte` - 帧类型字节
+    /// `frame` - 帧
+    /// # Returns
+    /// 帧违反协议时返回传输层错误
+    fn handle_frame(
+        &mut self,
+        now: Instant,
+        path: SocketAddr,
+        datagram_len: usize,
+        space: PacketNumberSpace,
+        type_byte: u8,
+        frame: Frame,
+    ) -> Result<(), TransportError> {
+        match frame {
+            Frame::Padding | Frame::Ping => {}
+            Frame::Ack(frame) => self.on_ack_frame(now, space, &frame)?,
+            Frame::Crypto(frame) => {
+                let (offset, data) = frame.get_data();
+                let crypto = self.spaces[space.index()].get_crypto_recv_mut();
+                if offset + data.len() > crypto.get_read_offset() + MAX_CRYPTO_BUFFER {
+                    return Err(TransportError::new(
+                        TransportErrorCode::CryptoBufferExceeded,
+                        type_byte as u64,
+                        "too much buffered crypto data",
+                    ));
+                }
+                // 握手数据没有最终大小, 插入不会失败
+                let _ = crypto.insert(offset, data, false);
+                self.read_handshake(now, space)?;
+            }
+            Frame::HandshakeDone | Frame::NewToken(_) if self.side == Side::Server => {
+                return Err(TransportError::new(
+                    TransportErrorCode::ProtocolViolation,
+                    type_byte as u64,
+                    "frame sent by client",
+                ));
+            }
+            Frame::HandshakeDone => self.confirm_handshake(now),
+            Frame::NewToken(_) => {}
+            Frame::Stream(frame) => {
+                let stream_id = frame.get_stream_id();
+                frame.check_direction(self.side)?;
+                self.open_remote_stream(stream_id, type_byte)?;
+
+                let Some(stream) = self.streams.get_recv_mut(stream_id) else {
+                    return Ok(());
+                };
+                stream.on_stream_frame(&frame)?;
+                let max_offset = stream.get_buffer().get_max_offset();
+                let readable = stream.is_readable();
+                self.on_stream_data_received(stream_id, max_offset, type_byte)?;
+                if readable {
+                    self.events.push_back(Event::StreamReadable(stream_id));
+                }
+            }
+            Frame::ResetStream(frame) => {
+                let stream_id = frame.get_stream_id();
+                stream_id.check_receivable(self.side, type_byte as u64)?;
+                self.open_remote_stream(stream_id, type_byte)?;
+
+                let Some(stream) = self.streams.get_recv_mut(stream_id) else {
+                    return Ok(());
+                };
+                stream.on_reset_stream_frame(&frame)?;
+                self.on_stream_data_received(stream_id, frame.get_final_size(), type_byte)?;
+                self.release_stream_credit(stream_id);
+                self.events.push_back(Event::StreamReadable(stream_id));
+            }
+            Frame::ResetStreamAt(frame) => {
+                // 未协商 reset_stream_at 扩展时, 该帧类型与未知帧一样处理
+                if !self.streams.get_reset_stream_at() {
+                    return Err(TransportError::new(
+                        TransportErrorCode::FrameEncodingError,
+                        type_byte as u64,
+                        "reset_stream_at not negotiated",
+                    ));
+                }
+                let stream_id = frame.get_stream_id();
+                frame.check_direction(self.side)?;
+                self.open_remote_stream(stream_id, type_byte)?;
+
+                let Some(stream) = self.streams.get_recv_mut(stream_id) else {
+                    return Ok(());
+                };
+                stream.on_reset_stream_at_frame(&frame)?;
+                self.on_stream_data_received(stream_id, frame.get_final_size(), type_byte)?;
+                self.release_stream_credit(stream_id);
+                self.events.push_back(Event::StreamReadable(stream_id));
+            }
+            Frame::StopSending(frame) => {
+                let stream_id = frame.get_stream_id();
+                frame.check_direction(self.side)?;
+                self.open_remote_stream(stream_id, type_byte)?;
+
+                self.streams.on_stop_sending_frame(&frame)?;
+                self.scheduler.remove(stream_id);
+                self.events.push_back(Event::StreamStopped {
+                    stream_id,
+                    error_code: frame.get_error_code(),
+                });
+            }
+            Frame::MaxData(frame) => self.flow_control.on_max_data_frame(&frame),
+            Frame::MaxStreamData(frame) => {
+                let stream_id = frame.get_stream_id();
+                frame.check_direction(self.side)?;
+                self.open_remote_stream(stream_id, type_byte)?;
+
+                if let Some(fc) = self.stream_flow_control.get_mut(&stream_id) {
+                    fc.on_max_stream_data_frame(&frame);
+                }
+            }
+            Frame::MaxStreams(frame) => self.stream_counts.on_max_streams_frame(&frame)?,
+            Frame::DataBlocked(_) => {}
+            Frame::StreamDataBlocked(frame) => {
+                let stream_id = frame.get_stream_id();
+                frame.check_direction(self.side)?;
+                self.open_remote_stream(stream_id, type_byte)?;
+            }
+            Frame::StreamsBlocked(frame) => self.stream_counts.on_streams_blocked_frame(&frame)?,
+            Frame::NewConnectionID(frame) => self
+                .cids
+                .on_new_connection_id_frame(&frame, &mut self.control_frames)?,
+            Frame::RetireConnectionID(frame) => self
+                .cids
+                .on_retire_connection_id_frame(&frame, &mut self.control_frames)?,
+            Frame::PathChallenge(frame) => {
+                self.on_path_challenge(path, frame.get_data(), datagram_len)
+            }
+            Frame::PathResponse(frame) => self.on_path_response(path, frame.get_data()),
+            Frame::ConnectionClose(frame) => {
+                let error = frame.get_error();
+                self.events.push_back(Event::Closed(error.clone()));
+                self.close_error = Some(error);
+                self.state = State::Draining;
+                self.close_deadline = Some(now + self.get_close_timeout());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 处理 ACK 帧, 释放被确认的帧并处理丢失的帧
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `space` - 数据包所在的编号空间
+    /// `frame` - ACK 帧
+    /// # Returns
+    /// ACK 帧中的范围不合法时返回 FRAME_ENCODING_ERROR, 确认了从未发送的数据包时返回
+    /// PROTOCOL_VIOLATION
+    fn on_ack_frame(
+        &mut self,
+        now: Instant,
+        space: PacketNumberSpace,
+        frame: &ACKFrame,
+    ) -> Result<(), TransportError> {
+        let (acked, lost) = self.loss.on_ack_received(now, space, frame)?;
+
+        // 客户端收到 Handshake 数据包的确认, 说明服务端已完成地址验证
+        if space == PacketNumberSpace::Handshake && self.side == Side::Client {
+            self.loss.set_peer_completed_address_validation(now);
+        }
+
+        for packet in &acked {
+            for sent in packet.get_frames() {
+                self.on_frame_acked(space, sent);
+            }
+        }
+        self.on_packets_lost(space, &lost);
+        Ok(())
+    }
+
+    /// 数据包中的帧被确认
+    ///
+    /// # Arguments
+    /// `space` - 数据包所在的编号空间
+    /// `frame` - 被确认的帧的记录
+    fn on_frame_acked(&mut self, space: PacketNumberSpace, frame: &SentFrame) {
+        match *frame {
+            SentFrame::Ack { largest } => {
+                self.spaces[space.index()]
+                    .get_received_mut()
+                    .on_ack_acked(largest);
+            }
+            SentFrame::Crypto { offset, len } => {
+                self.spaces[space.index()]
+                    .get_crypto_send_mut()
+                    .on_ack(offset, len, false);
+            }
+            SentFrame::Stream {
+                stream_id,
+                offset,
+                len,
+                fin,
+            } => {
+                if let Some(stream) = self.streams.get_send_mut(stream_id) {
+                    stream.on_stream_frame_acked(offset, len, fin);
+                }
+            }
+            SentFrame::ResetStream { stream_id } => {
+                if let Some(stream) = self.streams.get_send_mut(stream_id) {
+                    stream.on_reset_acked();
+                }
+            }
+            _ => self.control_frames.on_frame_acked(frame),
+        }
+    }
+
+    /// 处理丢失的数据包, 重新发送其中的数据
+    ///
+    /// # Arguments
+    /// `space` - 数据包所在的编号空间
+    /// `lost` - 丢失的数据包
+    fn on_packets_lost(&mut self, space: PacketNumberSpace, lost: &[SentPacket]) {
+        for packet in lost {
+            for frame in packet.get_frames() {
+                self.on_frame_lost(space, frame);
+            }
+        }
+    }
+
+    /// 数据包中的帧丢失
+    ///
+    /// # Arguments
+    /// `space` - 数据包所在的编号空间
+    /// `frame` - 丢失的帧的记录
+    fn on_frame_lost(&mut self, space: PacketNumberSpace, frame: &SentFrame) {
+        match *frame {
+            SentFrame::Padding | SentFrame::Ping | SentFrame::Ack { .. } => {}
+            // 路径验证由重发定时器发送新的 PATH_CHALLENGE 帧, PATH_RESPONSE 帧不重传
+            SentFrame::ConnectionClose | SentFrame::PathChallenge | SentFrame::PathResponse => {}
+            SentFrame::Crypto { offset, len } => {
+                self.spaces[space.index()]
+                    .get_crypto_send_mut()
+                    .on_lost(offset, len, false);
+            }
+            SentFrame::Stream {
+                stream_id,
+                offset,
+                len,
+                fin,
+            } => {
+                if let Some(stream) = self.streams.get_send_mut(stream_id) {
+                    stream.on_stream_frame_lost(offset, len, fin);
+                    self.scheduler.push(stream_id);
+                }
+            }
+            _ => self.control_frames.on_frame_lost(
+                frame,
+                &mut self.flow_control,
+                &mut self.stream_flow_control,
+                &mut self.streams,
+                &mut self.stream_counts,
+            ),
+        }
+    }
+
+    /// 将收到的握手数据交给会话, 并取出会话产生的握手数据与密钥
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `space` - 握手数据所在的编号空间
+    /// # Returns
+    /// 握手失败或对端传输参数缺失时返回传输层错误
+    fn read_handshake(
+        &mut self,
+        now: Instant,
+        space: PacketNumberSpace,
+    ) -> Result<(), TransportError> {
+        let level = EncryptionLevel::from_space(space);
+        let mut buf = [0u8; 4096];
+        loop {
+            let size = self.spaces[space.index()]
+                .get_crypto_recv_mut()
+                .read(&mut buf);
+            if size == 0 {
+                break;
+            }
+            self.session
+                .read_handshake(level, &buf[..size])
+                .map_err(|alert| {
+                    TransportError::new(
+                        TransportErrorCode::Crypto(alert),
+                        u8::from(FrameType::Crypto) as u64,
+                        "handshake failed",
+                    )
+                })?;
+        }
+
+        self.poll_session();
+        if self.state == State::Handshake && !self.session.is_handshaking() {
+            self.on_handshake_complete(now)?;
+        }
+        Ok(())
+    }
+
+    /// 取出会话产生的握手数据与密钥
+    fn poll_session(&mut self) {
+        while let Some((level, data)) = self.session.poll_handshake() {
+            self.spaces[level.get_space().index()]
+                .get_crypto_send_mut()
+                .write(&data);
+        }
+
+        while let Some((level, keys)) = self.session.poll_keys() {
+            if level == EncryptionLevel::Handshake {
+                self.loss.set_handshake_keys_available();
+            }
+            self.spaces[level.get_space().index()].set_keys(keys);
+        }
+    }
+
+    /// 握手完成, 应用对端的传输参数
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// # Returns
+    /// 对端没有通告传输参数时返回 TRANSPORT_PARAMETER_ERROR
+    fn on_handshake_complete(&mut self, now: Instant) -> Result<(), TransportError> {
+        let params = self
+            .session
+            .get_peer_transport_parameters()
+            .ok_or(TransportError::new(
+                TransportErrorCode::TransportParameterError,
+                u8::from(FrameType::Crypto) as u64,
+                "missing transport parameters",
+            ))?;
+        if params.get_active_connection_id_limit() < DEFAULT_ACTIVE_CONNECTION_ID_LIMIT {
+            return Err(TransportError::new(
+                TransportErrorCode::TransportParameterError,
+                u8::from(FrameType::Crypto) as u64,
+                "active_connection_id_limit below 2",
+            ));
+        }
+
+        self.loss.set_max_ack_delay(params.get_max_ack_delay());
+        self.loss
+            .set_ack_delay_exponent(params.get_ack_delay_exponent());
+        self.flow_control
+            .set_peer_initial_max_data(params.get_initial_max_data());
+        self.stream_counts = StreamCountManager::new(self.side, &self.local_params, &params);
+        self.streams.set_reset_stream_at(
+            self.local_params.get_reset_stream_at() && params.get_reset_stream_at(),
+        );
+        self.cids
+            .set_peer_limit(params.get_active_connection_id_limit());
+        self.cids.issue(&mut self.control_frames);
+        self.peer_params = params;
+
+        self.state = State::Established;
+        self.events.push_back(Event::Connected);
+
+        // 服务端在握手完成时即确认握手 (RFC 9001 §4.1.2)
+        if self.side == Side::Server {
+            self.control_frames.push_handshake_done();
+            self.confirm_handshake(now);
+        }
+        Ok(())
+    }
+
+    /// 确认握手, 丢弃 Handshake 密钥
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    fn confirm_handshake(&mut self, now: Instant) {
+        if self.handshake_confirmed {
+            return;
+        }
+
+        self.handshake_confirmed = true;
+        self.loss.set_handshake_confirmed(now);
+        if self.side == Side::Client {
+            self.loss.set_peer_completed_address_validation(now);
+        }
+        self.discard_space(now, PacketNumberSpace::Handshake);
+    }
+
+    /// 丢弃编号空间的密钥与在途数据包
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `space` - 编号空间
+    fn discard_space(&mut self, now: Instant, space: PacketNumberSpace) {
+        if self.spaces[space.index()].get_keys().is_none() {
+            return;
+        }
+
+        self.spaces[space.index()].discard();
+        self.loss.discard_space(now, space);
+    }
+
+    /// 处理对端发起的流上收到的帧, 隐式打开序号更小的流
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `type_byte` - 帧类型字节
+    /// # Returns
+    /// 超过流数量限制时返回 STREAM_LIMIT_ERROR, 本端发起的流尚未打开时返回 STREAM_STATE_ERROR
+    fn open_remote_stream(
+        &mut self,
+        stream_id: StreamId,
+        type_byte: u8,
+    ) -> Result<(), TransportError> {
+        if stream_id.is_local(self.side) {
+            if !self.stream_counts.is_local_opened(stream_id) {
+                return Err(TransportError::new(
+                    TransportErrorCode::StreamStateError,
+                    type_byte as u64,
+                    "frame received on an unopened local stream",
+                ));
+            }
+            return Ok(());
+        }
+
+        for stream_id in self
+            .stream_counts
+            .on_remote_stream(stream_id, FrameType::from(type_byte))?
+        {
+            self.create_stream(stream_id);
+            self.events.push_back(Event::StreamOpened(stream_id));
+        }
+        Ok(())
+    }
+
+    /// 创建流及其流量控制
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    fn create_stream(&mut self, stream_id: StreamId) {
+        self.streams.insert(stream_id);
+        self.stream_flow_control.insert(
+            stream_id,
+            StreamFlowControl::new(
+                stream_id,
+                self.side,
+                &self.local_params,
+                &self.peer_params,
+                MAX_RECV_WINDOW,
+            ),
+        );
+    }
+
+    /// 按流上接收数据的最大偏移量更新流量控制
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `max_offset` - 流上已接收数据的最大偏移量
+    /// `type_byte` - 帧类型字节
+    /// # Returns
+    /// 超过流或连接的限制时返回 FLOW_CONTROL_ERROR
+    fn on_stream_data_received(
+        &mut self,
+        stream_id: StreamId,
+        max_offset: usize,
+        type_byte: u8,
+    ) -> Result<(), TransportError> {
+        let Some(fc) = self.stream_flow_control.get_mut(&stream_id) else {
+            return Ok(());
+        };
+
+        let size = fc.on_data_received(max_offset, FrameType::from(type_byte))?;
+        self.flow_control
+            .on_data_received(size, FrameType::from(type_byte))
+    }
+
+    /// 归还流上已读取或因重置而丢弃的数据所占用的流级与连接级信用
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    fn release_stream_credit(&mut self, stream_id: StreamId) {
+        let (Some(stream), Some(fc)) = (
+            self.streams.get_recv(stream_id),
+            self.stream_flow_control.get_mut(&stream_id),
+        ) else {
+            return;
+        };
+
+        let size = stream.get_consumed() - fc.get_recv().get_consumed();
+        fc.on_data_consumed(size);
+        self.flow_control.on_data_consumed(size);
+    }
+
+    /// 移除已经终止的流, 并允许对端打开新的流
+    fn collect_closed_streams(&mut self) {
+        for stream_id in self.streams.collect_closed() {
+            self.stream_flow_control.remove(&stream_id);
+            self.scheduler.remove(stream_id);
+            if !stream_id.is_local(self.side) {
+                self.stream_counts
+                    .on_remote_stream_closed(stream_id.get_direction());
+            }
+        }
+    }
+
+    /// 对端迁移到新的地址: 在新路径上受放大限制, 并开始验证新路径
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `remote` - 对端的新地址
+    /// `datagram_len` - 新地址上收到的数据报的字节数
+    fn migrate(&mut self, now: Instant, remote: SocketAddr, datagram_len: usize) {
+        // 迁移回之前已验证的地址时不需要再次验证
+        if self.previous_remote == Some(remote) {
+            self.set_remote(remote);
+            self.previous_remote = None;
+            self.path_validation = None;
+            self.address_validated = true;
+            return;
+        }
+
+        if self.previous_remote.is_none() && self.address_validated {
+            self.previous_remote = Some(self.remote);
+        }
+        self.set_remote(remote);
+        self.address_validated = false;
+        self.bytes_received = datagram_len;
+        self.bytes_sent = 0;
+        self.path_validation = Some(PathValidator::new(remote, now, self.get_pto()));
+    }
+
+    /// 切换对端地址
+    ///
+    /// 向新的目标地址发送时换用未使用过的对端 Connection ID (RFC 9000 §9.5);
+    /// IP 地址改变时将拥塞控制与 RTT 估算恢复到初始状态, 仅端口改变时通常是 NAT 重新绑定,
+    /// 路径的特性没有变化 (RFC 9000 §9.4)
+    ///
+    /// # Arguments
+    /// `remote` - 对端的新地址
+    fn set_remote(&mut self, remote: SocketAddr) {
+        if remote == self.remote {
+            return;
+        }
+
+        if remote.ip() != self.remote.ip() {
+            self.loss.on_path_changed();
+            self.pacer = Pacer::new();
+            self.pacing_deadline = None;
+        }
+        self.cids.rotate_remote(&mut self.control_frames);
+        self.remote = remote;
+    }
+
+    /// 处理 PATH_CHALLENGE 帧, 在收到该帧的路径上应答
+    ///
+    /// 每条路径只应答最近的 PATH_CHALLENGE 帧, 且等待发送的应答数量有限,
+    /// 以免对端借此放大流量.
+    ///
+    /// # Arguments
+    /// `path` - 收到 PATH_CHALLENGE 帧的路径
+    /// `data` - PATH_CHALLENGE 帧中的数据
+    /// `datagram_len` - 携带 PATH_CHALLENGE 帧的数据报的字节数
+    fn on_path_challenge(&mut self, path: SocketAddr, data: &[u8], datagram_len: usize) {
+        let budget = datagram_len * AMPLIFICATION_FACTOR;
+        if let Some(response) = self
+            .path_responses
+            .iter_mut()
+            .find(|response| response.get_remote() == path)
+        {
+            response.update(data, budget);
+        } else if self.path_responses.len() < MAX_PATH_RESPONSES {
+            self.path_responses
+                .push_back(PendingPathResponse::new(path, data, budget));
+        }
+    }
+
+    /// 处理 PATH_RESPONSE 帧, 在被验证的路径上应答了 PATH_CHALLENGE 帧即验证成功
+    ///
+    /// # Arguments
+    /// `path` - 收到 PATH_RESPONSE 帧的路径
+    /// `data` - PATH_RESPONSE 帧中的数据
+    fn on_path_response(&mut self, path: SocketAddr, data: &[u8]) {
+        if !self
+            .path_validation
+            .as_ref()
+            .is_some_and(|validation| validation.on_path_response(path, data))
+        {
+            return;
+        }
+
+        self.path_validation = None;
+        self.previous_remote = None;
+        self.address_validated = true;
+        self.events.push_back(Event::PathValidated(path));
+    }
+
+    /// 路径验证失败, 对端迁移之前的地址已验证时恢复使用该地址
+    fn on_path_validation_failed(&mut self) {
+        let Some(validation) = self.path_validation.take() else {
+            return;
+        };
+
+        self.events
+            .push_back(Event::PathValidationFailed(validation.get_remote()));
+        if let Some(previous) = self.previous_remote.take() {
+            self.set_remote(previous);
+            self.address_validated = true;
+        }
+    }
+
+    /// 判断是否有需要在当前路径上发送的 PATH_CHALLENGE 或 PATH_RESPONSE 帧
+    ///
+    /// # Returns
+    /// 返回是否有待发送的路径验证帧
+    fn has_path_frames(&self) -> bool {
+        self.path_validation
+            .as_ref()
+            .is_some_and(PathValidator::is_challenge_pending)
+            || self
+                .path_responses
+                .iter()
+                .any(|response| response.get_remote() == self.remote)
+    }
+
+    /// 以传输层错误关闭连接, 之后发送 CONNECTION_CLOSE (0x1c) 帧
+    ///
+    /// # Arguments
+    /// `error` - 传输层错误
+    fn close_with_error(&mut self, now: Instant, error: TransportError) {
+        self.close_with(now, error.into());
+    }
+
+    /// 由本端关闭连接, 进入 closing 状态并发送 CONNECTION_CLOSE 帧
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `error` - 连接错误
+    fn close_with(&mut self, now: Instant, error: ConnectionError) {
+        self.events.push_back(Event::Closed(error.clone()));
+        self.close_error = Some(error);
+        self.close_pending = true;
+        self.state = State::Closing;
+        self.close_deadline = Some(now + self.get_close_timeout());
+    }
+
+    /// closing 状态中收到数据报, 按指数递减的频率以 CONNECTION_CLOSE 帧应答
+    ///
+    /// # Arguments
+    /// `size` - 数据报的字节数, 计入地址验证前的放大限制
+    fn on_closing_datagram(&mut self, size: usize) {
+        self.bytes_received += size;
+        self.close_datagrams += 1;
+        if self.close_datagrams >= self.close_next_response {
+            self.close_next_response *= 2;
+            self.close_pending = true;
+        }
+    }
+
+    /// 获取 PTO 时长
+    ///
+    /// # Returns
+    /// 返回包含对端 max_ack_delay 的 PTO 时长
+    fn get_pto(&self) -> Duration {
+        self.loss.get_rtt().get_pto_base() + self.peer_params.get_max_ack_delay()
+    }
+
+    /// 获取关闭后保留连接状态的时长
+    ///
+    /// # Returns
+    /// 返回三倍的 PTO
+    fn get_close_timeout(&self) -> Duration {
+        self.get_pto() * CLOSE_PTO_COUNT
+    }
+
+    /// 获取生效的空闲超时时长
+    ///
+    /// # Returns
+    /// 返回双方通告的非零 max_idle_timeout 中较小的一个, 且不小于三倍的 PTO;
+    /// 双方均未启用空闲超时时返回 None
+    fn get_idle_timeout(&self) -> Option<Duration> {
+        [
+            self.local_params.get_max_idle_timeout(),
+            self.peer_params.get_max_idle_timeout(),
+        ]
+        .into_iter()
+        .filter(|timeout| !timeout.is_zero())
+        .min()
+        .map(|timeout| timeout.max(self.get_pto() * MIN_IDLE_PTO_COUNT))
+    }
+
+    /// 重新开始空闲计时, 同时重新设置保活定时器
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    fn restart_idle_timer(&mut self, now: Instant) {
+        self.idle_deadline = self.get_idle_timeout().map(|timeout| now + timeout);
+        self.keep_alive_deadline = self.keep_alive_interval.map(|interval| now + interval);
+    }
+
+    /// 释放连接状态, 之后不再处理任何数据报
+    fn release(&mut self) {
+        self.state = State::Closed;
+        self.close_deadline = None;
+        self.close_pending = false;
+        self.idle_deadline = None;
+        self.keep_alive_deadline = None;
+        self.ping_pending = false;
+        self.path_validation = None;
+        self.path_responses.clear();
+        for space in self.spaces.iter_mut() {
+            space.discard();
+        }
+        self.streams = StreamMap::new(self.side);
+        self.stream_flow_control.clear();
+        self.scheduler = StreamScheduler::new();
+        self.control_frames = ControlFrameTracker::new();
+    }
+
+    /// 判断编号空间中是否有待发送的数据包
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `space` - 编号空间
+    /// `blocked` - 是否受拥塞控制或发送节奏限制
+    /// # Returns
+    /// 需要发送 ACK、探测数据包或可以发送数据时返回 true
+    fn wants_to_send(&self, now: Instant, space: PacketNumberSpace, blocked: bool) -> bool {
+        let state = &self.spaces[space.index()];
+        state.get_keys().is_some()
+            && (state.get_received().should_send_ack(now)
+                || self.loss.has_probe(space)
+                || (!blocked && self.has_pending_data(space)))
+    }
+
+    /// 判断编号空间中是否有待发送的数据
+    ///
+    /// # Arguments
+    /// `space` - 编号空间
+    /// # Returns
+    /// 返回是否有待发送的握手数据、控制帧或流数据
+    fn has_pending_data(&self, space: PacketNumberSpace) -> bool {
+        if self.spaces[space.index()]
+            .get_crypto_send()
+            .has_pending(usize::MAX)
+        {
+            return true;
+        }
+        if space != PacketNumberSpace::ApplicationData || self.state != State::Established {
+            return false;
+        }
+
+        self.ping_pending
+            || self.has_path_frames()
+            || self.control_frames.has_pending()
+            || self.flow_control.get_recv().should_update()
+            || self.stream_counts.should_update()
+            || self.streams.has_pending_frames()
+            || self.stream_flow_control.iter().any(|(&stream_id, fc)| {
+                let receiving = self
+                    .streams
+                    .get_recv(stream_id)
+                    .is_some_and(|stream| stream.get_state() == RecvState::Recv);
+                let limit = self.get_send_limit(stream_id);
+                (receiving && fc.get_recv().should_update())
+                    || self
+                        .streams
+                        .get_send(stream_id)
+                        .is_some_and(|stream| stream.has_pending(limit))
+            })
+    }
+
+    /// 获取流上当前允许发送的最大偏移量
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// # Returns
+    /// 返回流级与连接级流量控制共同允许的最大偏移量
+    fn get_send_limit(&self, stream_id: StreamId) -> usize {
+        let max_offset = self
+            .stream_flow_control
+            .get(&stream_id)
+            .map_or(0, |fc| fc.get_max_send_offset());
+        max_offset.min(self.get_charged_offset(stream_id) + self.flow_control.get_available())
+    }
+
+    /// 获取流上已消耗流量控制信用的偏移量
+    ///
+    /// 即流的最终大小: 以 RESET_STREAM_AT 重置后, 可靠大小之前尚未发送的数据已预先消耗信用.
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// # Returns
+    /// 返回已消耗信用的偏移量, 流不存在时返回 0
+    fn get_charged_offset(&self, stream_id: StreamId) -> usize {
+        self.streams
+            .get_send(stream_id)
+            .map_or(0, |stream| stream.get_final_size())
+    }
+
+    /// 构造编号空间的包头
+    ///
+    /// # Arguments
+    /// `space` - 编号空间
+    /// `packet_number` - 数据包编号
+    /// `packet_number_len` - 数据包编号的编码长度
+    /// # Returns
+    /// 返回包头
+    fn build_header(
+        &self,
+        space: PacketNumberSpace,
+        packet_number: PacketNumber,
+        packet_number_len: usize,
+    ) -> PacketHeader {
+        let remote_cid = self.cids.get_remote();
+        match space {
+            PacketNumberSpace::Initial => {
+                let mut header = InitialHeader::new(packet_number_len);
+                let long = header.get_header_mut();
+                long.set_version(QUIC_VERSION_1);
+                long.set_dst(remote_cid);
+                long.set_src(self.local_cid);
+                header.set_packet_number(packet_number, packet_number_len);
+                PacketHeader::Initial(header)
+            }
+            PacketNumberSpace::Handshake => {
+                let mut header = HandshakeHeader::new(packet_number_len);
+                let long = header.get_header_mut();
+                long.set_version(QUIC_VERSION_1);
+                long.set_dst(remote_cid);
+                long.set_src(self.local_cid);
+                header.set_packet_number(packet_number, packet_number_len);
+                PacketHeader::Handshake(header)
+            }
+            PacketNumberSpace::ApplicationData => {
+                let mut header = ShortHeader::new(remote_cid.get_id().len(), packet_number_len);
+                header.set_dst(remote_cid);
+                header.set_packet_number(packet_number, packet_number_len);
+                PacketHeader::Short(header)
+            }
+        }
+    }
+
+    /// 在编号空间中组装并加密一个数据包
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `space` - 编号空间
+    /// `max_size` - 数据包最多可占用的字节数
+    /// `min_size` - 数据包至少需要占用的字节数
+    /// `blocked` - 是否受拥塞控制或发送节奏限制, 受限时仅发送 ACK 与探测数据包
+    /// `ecn` - 数据包所在的数据报使用的 ECN 码点
+    /// # Returns
+    /// 返回加密后的数据包, 没有可发送的帧时返回 None
+    fn build_packet(
+        &mut self,
+        now: Instant,
+        space: PacketNumberSpace,
+        max_size: usize,
+        min_size: usize,
+        blocked: bool,
+        ecn: EcnCodepoint,
+    ) -> Option<Vec<u8>> {
+        let index = space.index();
+        let tag_len = self.spaces[index]
+            .get_keys()?
+            .get_local()
+            .get_packet()
+            .tag_len();
+
+        // 探测数据包重新发送最早的在途数据
+        let probe = self.loss.poll_probe(space);
+        if let Some(frames) = &probe {
+            for frame in frames {
+                self.on_frame_lost(space, frame);
+            }
+        }
+
+        let sending = probe.is_some() || (!blocked && self.has_pending_data(space));
+        if !sending && !self.spaces[index].get_received().should_send_ack(now) {
+            return None;
+        }
+
+        let packet_number = self.spaces[index].get_next_packet_number();
+        let packet_number_len =
+            encode_packet_number_len(packet_number, self.loss.get_largest_acked(space));
+        let header = self.build_header(space, packet_number, packet_number_len);
+        let mut builder = PacketBuilder::new(header, max_size, tag_len).ok()?;
+        builder.set_min_size(min_size);
+
+        let received = self.spaces[index].get_received();
+        if received.has_unacked() {
+            let exponent = self.local_params.get_ack_delay_exponent();
+            let ecn_counts = self.ecn_counts.get_counts(space);
+            if let Some(ack) = received.build_ack(now, exponent, ecn_counts) {
+                if builder.push_ack(&ack) {
+                    self.spaces[index].get_received_mut().on_ack_sent();
+                }
+            }
+        }
+
+        if sending {
+            while builder.push_crypto(self.spaces[index].get_crypto_send_mut()) {}
+            if space == PacketNumberSpace::ApplicationData && self.state == State::Established {
+                self.write_app_frames(now, &mut builder);
+            }
+        }
+        if probe.is_some() && !builder.is_ack_eliciting() {
+            builder.push_frame(&PingFrame::new(), SentFrame::Ping);
+        }
+        if builder.is_empty() {
+            return None;
+        }
+
+        let mut packet = builder.finish().ok()?;
+        let header_len = packet.get_header_len();
+        let packet_number_offset = packet.get_packet_number_offset();
+        let keys = self.spaces[index].get_keys()?.get_local();
+        let buf = packet.get_buf_mut();
+        let (header, payload) = buf.split_at_mut(header_len);
+        keys.get_packet().seal(packet_number, header, payload);
+        protect_header(
+            keys.get_header(),
+            buf,
+            packet_number_offset,
+            packet_number_len,
+        );
+
+        let (buf, frames) = packet.into_parts();
+        let mut sent = SentPacket::new(packet_number, now, buf.len(), frames);
+        sent.set_ecn(ecn);
+        if sent.is_ack_eliciting() && self.idle_restart_on_send {
+            self.restart_idle_timer(now);
+            self.idle_restart_on_send = false;
+        }
+        self.loss.on_packet_sent(space, sent);
+        self.spaces[index].on_packet_sent();
+
+        // 客户端发送首个 Handshake 数据包后丢弃 Initial 密钥 (RFC 9001 §4.9.1)
+        if space == PacketNumberSpace::Handshake && self.side == Side::Client {
+            self.discard_space(now, PacketNumberSpace::Initial);
+        }
+        Some(buf)
+    }
+
+    /// 向 1-RTT 数据包写入控制帧与流数据
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `builder` - 数据包组装器
+    fn write_app_frames(&mut self, now: Instant, builder: &mut PacketBuilder) {
+        if let Some(data) = self
+            .path_validation
+            .as_mut()
+            .and_then(|validation| validation.poll_challenge(now, self.random.as_mut()))
+        {
+            let mut frame = PathChallengeFrame::new();
+            frame.set_data(&data);
+            builder.push_frame(&frame, SentFrame::PathChallenge);
+        }
+
+        let remote = self.remote;
+        self.path_responses.retain(|response| {
+            if response.get_remote() != remote {
+                return true;
+            }
+            let mut frame = PathResponseFrame::new();
+            frame.set_data(response.get_data());
+            !builder.push_frame(&frame, SentFrame::PathResponse)
+        });
+
+        self.control_frames.write_frames(builder);
+
+        let smoothed_rtt = self.loss.get_rtt().get_smoothed_rtt();
+        if let Some(frame) = self.flow_control.poll_max_data_frame(now, smoothed_rtt) {
+            let sent = SentFrame::MaxData {
+                max: frame.get_maximum_data(),
+            };
+            if !builder.push_frame(&frame, sent) {
+                self.control_frames.push(sent);
+            }
+        }
+
+        for (&stream_id, fc) in self.stream_flow_control.iter_mut() {
+            let receiving = self
+                .streams
+                .get_recv(stream_id)
+                .is_some_and(|stream| stream.get_state() == RecvState::Recv);
+            if !receiving {
+                continue;
+            }
+            if let Some(frame) = fc.poll_max_stream_data_frame(now, smoothed_rtt) {
+                let sent = SentFrame::MaxStreamData {
+                    stream_id,
+                    max: frame.get_maximum_data(),
+                };
+                if !builder.push_frame(&frame, sent) {
+                    self.control_frames.push(sent);
+                }
+            }
+        }
+
+        while let Some(frame) = self.stream_counts.poll_max_streams_frame() {
+            let sent = SentFrame::MaxStreams {
+                bidi: frame.is_bidi(),
+                max: frame.get_maximum_streams(),
+            };
+            if !builder.push_frame(&frame, sent) {
+                self.control_frames.push(sent);
+            }
+        }
+
+        if let Some(frame) = self.flow_control.poll_data_blocked_frame() {
+            let max = frame.get_maximum_data();
+            if !builder.push_frame(&frame, SentFrame::DataBlocked { max }) {
+                self.flow_control.on_data_blocked_lost(max);
+            }
+        }
+
+        for (&stream_id, fc) in self.stream_flow_control.iter_mut() {
+            // 只接收的单向流没有发送窗口, 不能通告 STREAM_DATA_BLOCKED
+            if self.streams.get_send(stream_id).is_none() {
+                continue;
+            }
+            if let Some(frame) = fc.poll_stream_data_blocked_frame() {
+                let max = frame.get_maximum_data();
+                if !builder.push_frame(&frame, SentFrame::StreamDataBlocked { stream_id, max }) {
+                    fc.on_stream_data_blocked_lost(max);
+                }
+            }
+        }
+
+        while let Some(frame) = self.stream_counts.poll_streams_blocked_frame() {
+            let bidi = frame.is_bidi();
+            let max = frame.get_maximum_streams();
+            if !builder.push_frame(&frame, SentFrame::StreamsBlocked { bidi, max }) {
+                self.stream_counts.on_streams_blocked_lost(bidi, max);
+                break;
+            }
+        }
+
+        while let Some(frame) = self.streams.poll_reset_stream_frame() {
+            let stream_id = frame.get_stream_id();
+            if !builder.push_frame(&frame, SentFrame::ResetStream { stream_id }) {
+                if let Some(stream) = self.streams.get_send_mut(stream_id) {
+                    stream.on_reset_lost();
+                }
+                break;
+            }
+        }
+
+        while let Some(frame) = self.streams.poll_reset_stream_at_frame() {
+            let stream_id = frame.get_stream_id();
+            if !builder.push_frame(&frame, SentFrame::ResetStream { stream_id }) {
+                if let Some(stream) = self.streams.get_send_mut(stream_id) {
+                    stream.on_reset_lost();
+                }
+                break;
+            }
+        }
+
+        while let Some(frame) = self.streams.poll_stop_sending_frame() {
+            let stream_id = frame.get_stream_id();
+            if !builder.push_frame(&frame, SentFrame::StopSending { stream_id }) {
+                if let Some(stream) = self.streams.get_recv_mut(stream_id) {
+                    stream.on_stop_sending_lost();
+                }
+                break;
+            }
+        }
+
+        self.write_stream_frames(builder);
+
+        // 保活 PING 帧仅在数据包中没有其他 ack-eliciting 帧时才需要
+        if self.ping_pending {
+            if !builder.is_ack_eliciting() {
+                builder.push_frame(&PingFrame::new(), SentFrame::Ping);
+            }
+            self.ping_pending = false;
+        }
+    }
+
+    /// 按调度顺序向数据包写入流数据
+    ///
+    /// # Arguments
+    /// `builder` - 数据包组装器
+    fn write_stream_frames(&mut self, builder: &mut PacketBuilder) {
+        // 重传或流量控制限制解除后, 流需要重新加入调度
+        let stream_ids = self.stream_flow_control.keys().copied().collect::<Vec<_>>();
+        for stream_id in stream_ids {
+            let limit = self.get_send_limit(stream_id);
+            if self
+                .streams
+                .get_send(stream_id)
+                .is_some_and(|stream| stream.has_pending(limit))
+            {
+                self.scheduler.push(stream_id);
+            }
+        }
+
+        let scheduled = self.scheduler.iter().collect::<Vec<_>>();
+        for stream_id in scheduled {
+            let limit = self.get_send_limit(stream_id);
+            let Some(stream) = self.streams.get_send_mut(stream_id) else {
+                self.scheduler.remove(stream_id);
+                continue;
+            };
+            if !stream.has_pending(limit) {
+                self.scheduler.on_sent(stream_id, true);
+                continue;
+            }
+
+            let charged = stream.get_final_size();
+            if !builder.push_stream(stream, limit) {
+                break;
+            }
+            let size = stream.get_final_size() - charged;
+            let exhausted = !stream.has_pending(limit);
+
+            self.flow_control.on_data_sent(size);
+            if let Some(fc) = self.stream_flow_control.get_mut(&stream_id) {
+                fc.on_data_sent(size);
+            }
+            self.scheduler.on_sent(stream_id, exhausted);
+        }
+    }
+
+    /// 取出在当前路径之外应答 PATH_CHALLENGE 帧的数据报
+    ///
+    /// 未验证的路径受放大限制, 数据报在限制范围内填充到 MIN_INITIAL_SIZE 字节.
+    ///
+    /// # Returns
+    /// 返回数据报, 没有需要在其他路径上发送的 PATH_RESPONSE 帧时返回 None
+    fn poll_path_response_transmit(&mut self) -> Option<Transmit> {
+        let index = self
+            .path_responses
+            .iter()
+            .position(|response| response.get_remote() != self.remote)?;
+        let response = self.path_responses.remove(index)?;
+
+        let mut frame = PathResponseFrame::new();
+        frame.set_data(response.get_data());
+        let max_size = MAX_DATAGRAM_SIZE.min(response.get_budget());
+        let packet = self.build_frame_packet(
+            PacketNumberSpace::ApplicationData,
+            &frame,
+            SentFrame::PathResponse,
+            max_size,
+            MIN_INITIAL_SIZE,
+        )?;
+
+        // 数据包不参与丢包检测, 也不计入 ECN 验证, 不能携带 ECT 码点
+        Some(Transmit::new(
+            response.get_remote(),
+            EcnCodepoint::NotEct,
+            packet,
+        ))
+    }
+
+    /// 取出 closing 状态中携带 CONNECTION_CLOSE 帧的数据报
+    ///
+    /// 握手确认之前无法确定对端持有哪些密钥, 在所有可用的加密级别中各发送一个
+    /// CONNECTION_CLOSE 帧并合并到同一个数据报中 (RFC 9000 §10.2.3).
+    ///
+    /// # Returns
+    /// 返回数据报, CONNECTION_CLOSE 帧已发送或没有可用的密钥时返回 None
+    fn poll_close_transmit(&mut self) -> Option<Transmit> {
+        if !self.close_pending {
+            return None;
+        }
+        self.close_pending = false;
+        let error = self.close_error.clone()?;
+
+        let spaces = PacketNumberSpace::ALL
+            .into_iter()
+            .filter(|space| self.spaces[space.index()].get_keys().is_some())
+            .filter(|&space| {
+                !self.handshake_confirmed || space == PacketNumberSpace::ApplicationData
+            })
+            .collect::<Vec<_>>();
+        let last = *spaces.last()?;
+
+        let mut max_size = MAX_DATAGRAM_SIZE;
+        if !self.address_validated {
+            max_size = max_size
+                .min((self.bytes_received * AMPLIFICATION_FACTOR).saturating_sub(self.bytes_sent));
+        }
+
+        // 包含 Initial 数据包的数据报需要填充到 MIN_INITIAL_SIZE 字节
+        let has_initial = spaces[0] == PacketNumberSpace::Initial;
+        let mut datagram = Vec::new();
+        for space in spaces {
+            let min_size = if space == last && has_initial {
+                MIN_INITIAL_SIZE.saturating_sub(datagram.len())
+            } else {
+                0
+            };
+            let frame = ConnectionCloseFrame::from_error(&error, space);
+            let remaining = max_size.saturating_sub(datagram.len());
+            let sent = SentFrame::ConnectionClose;
+            if let Some(packet) = self.build_frame_packet(space, &frame, sent, remaining, min_size)
+            {
+                datagram.extend_from_slice(&packet);
+            }
+        }
+
+        if datagram.is_empty() {
+            return None;
+        }
+        self.bytes_sent += datagram.len();
+        // 数据包不参与丢包检测, 也不计入 ECN 验证, 不能携带 ECT 码点
+        Some(Transmit::new(self.remote, EcnCodepoint::NotEct, datagram))
+    }
+
+    /// 组装并加密一个仅携带单个帧的数据包, 该数据包不参与丢包检测与拥塞控制
+    ///
+    /// # Arguments
+    /// `space` - 编号空间
+    /// `frame` - 帧
+    /// `sent` - 帧的发送记录
+    /// `max_size` - 数据包最多可占用的字节数
+    /// `min_size` - 数据包至少需要占用的字节数
+    /// # Returns
+    /// 返回加密后的数据包, 剩余空间不足时返回 None
+    fn build_frame_packet(
+        &mut self,
+        space: PacketNumberSpace,
+        frame: &dyn Serializer,
+        sent: SentFrame,
+        max_size: usize,
+        min_size: usize,
+    ) -> Option<Vec<u8>> {
+        let index = space.index();
+        let packet_number = self.spaces[index].get_next_packet_number();
+        let packet_number_len =
+            encode_packet_number_len(packet_number, self.loss.get_largest_acked(space));
+        let header = self.build_header(space, packet_number, packet_number_len);
+        let keys = self.spaces[index].get_keys()?.get_local();
+
+        let mut builder = PacketBuilder::new(header, max_size, keys.get_packet().tag_len()).ok()?;
+        builder.set_min_size(min_size);
+        if !builder.push_frame(frame, sent) {
+            return None;
+        }
+        let mut packet = builder.finish().ok()?;
+
+        let header_len = packet.get_header_len();
+        let packet_number_offset = packet.get_packet_number_offset();
+        let buf = packet.get_buf_mut();
+        let (header, payload) = buf.split_at_mut(header_len);
+        keys.get_packet().seal(packet_number, header, payload);
+        protect_header(
+            keys.get_header(),
+            buf,
+            packet_number_offset,
+            packet_number_len,
+        );
+        self.spaces[index].on_packet_sent();
+
+        let (buf, _) = packet.into_parts();
+        Some(buf)
+    }
+}
+
+/// 由字节构造 Connection ID
+///
+/// # Arguments
+/// `id` - Connection ID 的字节, 可以为空
+/// # Returns
+/// 返回 Connection ID
+fn connection_id(id: &[u8]) -> ConnectionID {
+    let mut connection_id = ConnectionID::new();
+    if !id.is_empty() {
+        connection_id.set_id(id);
+    }
+    connection_id
+}
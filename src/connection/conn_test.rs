@@ -0,0 +1,1176 @@
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    net::SocketAddr,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    attr::{EcnCodepoint, Side, StreamDirection, StreamId, TransportParameters},
+    cid::RandomConnectionIdGenerator,
+    congestion::{CongestionController, NewReno},
+    crypto::{EncryptionLevel, HeaderKey, KeyPair, Keys, PacketKey, Random, Session},
+    error::{ConnectionError, ConnectionIdError, StreamError, TransportErrorCode},
+    recovery::{RttEstimator, SentPacket},
+    stream::{Priority, RecvState, SendState},
+};
+
+use super::{Config, Connection, Event};
+
+const TAG_LEN: usize = 16;
+
+/// 以异或代替加密, 以校验和代替认证标签的数据包密钥
+struct XorPacketKey(u8);
+
+impl XorPacketKey {
+    fn tag(&self, packet_number: u64, header: &[u8], payload: &[u8]) -> [u8; TAG_LEN] {
+        let sum = header
+            .iter()
+            .chain(payload)
+            .fold(0u8, |acc, &b| acc.wrapping_add(b));
+        let mut tag = [0u8; TAG_LEN];
+        for (i, b) in tag.iter_mut().enumerate() {
+            *b = sum ^ self.0 ^ packet_number as u8 ^ i as u8;
+        }
+        tag
+    }
+}
+
+impl PacketKey for XorPacketKey {
+    fn tag_len(&self) -> usize {
+        TAG_LEN
+    }
+
+    fn seal(&self, packet_number: u64, header: &[u8], payload: &mut [u8]) {
+        let len = payload.len() - TAG_LEN;
+        payload[..len].iter_mut().for_each(|b| *b ^= self.0);
+        let tag = self.tag(packet_number, header, &payload[..len]);
+        payload[len..].copy_from_slice(&tag);
+    }
+
+    fn open(&self, packet_number: u64, header: &[u8], payload: &mut [u8]) -> Option<usize> {
+        let len = payload.len().checked_sub(TAG_LEN)?;
+        if payload[len..] != self.tag(packet_number, header, &payload[..len]) {
+            return None;
+        }
+        payload[..len].iter_mut().for_each(|b| *b ^= self.0);
+        Some(len)
+    }
+}
+
+struct XorHeaderKey(u8);
+
+impl HeaderKey for XorHeaderKey {
+    fn sample_len(&self) -> usize {
+        16
+    }
+
+    fn mask(&self, sample: &[u8]) -> [u8; 5] {
+        [
+            sample[0] ^ self.0,
+            sample[1],
+            sample[2],
+            sample[3],
+            sample[4],
+        ]
+    }
+}
+
+fn keys(level: EncryptionLevel, sender: Side) -> Keys {
+    let key = level as u8 * 2 + sender as u8 + 1;
+    Keys::new(Box::new(XorPacketKey(key)), Box::new(XorHeaderKey(key)))
+}
+
+fn key_pair(level: EncryptionLevel, local: Side) -> KeyPair {
+    KeyPair::new(keys(level, local), keys(level, local.peer()))
+}
+
+const CLIENT_HELLO: &[u8] = b"client hello";
+const SERVER_HELLO: &[u8] = b"server hello";
+const SERVER_FINISHED: &[u8] = b"server finished";
+const CLIENT_FINISHED: &[u8] = b"client finished";
+
+/// 按固定脚本交换握手消息的会话
+struct ScriptedSession {
+    side: Side,
+    received: [Vec<u8>; 3],
+    handshake: VecDeque<(EncryptionLevel, Vec<u8>)>,
+    keys: VecDeque<(EncryptionLevel, KeyPair)>,
+    handshaking: bool,
+    params: TransportParameters,
+}
+
+impl ScriptedSession {
+    fn new(side: Side, params: TransportParameters) -> Self {
+        let mut handshake = VecDeque::new();
+        if side == Side::Client {
+            handshake.push_back((EncryptionLevel::Initial, CLIENT_HELLO.to_vec()));
+        }
+        Self {
+            side,
+            received: [Vec::new(), Vec::new(), Vec::new()],
+            handshake,
+            keys: VecDeque::new(),
+            handshaking: true,
+            params,
+        }
+    }
+}
+
+impl Session for ScriptedSession {
+    fn initial_keys(&self, _dst_cid: &[u8], side: Side) -> KeyPair {
+        key_pair(EncryptionLevel::Initial, side)
+    }
+
+    fn read_handshake(&mut self, level: EncryptionLevel, data: &[u8]) -> Result<(), u8> {
+        let received = &mut self.received[level as usize];
+        received.extend_from_slice(data);
+
+        match (self.side, level, received.as_slice()) {
+            (Side::Server, EncryptionLevel::Initial, CLIENT_HELLO) => {
+                self.handshake
+                    .push_back((EncryptionLevel::Initial, SERVER_HELLO.to_vec()));
+                self.keys.push_back((
+                    EncryptionLevel::Handshake,
+                    key_pair(EncryptionLevel::Handshake, self.side),
+                ));
+                self.handshake
+                    .push_back((EncryptionLevel::Handshake, SERVER_FINISHED.to_vec()));
+                self.keys.push_back((
+                    EncryptionLevel::OneRtt,
+                    key_pair(EncryptionLevel::OneRtt, self.side),
+                ));
+            }
+            (Side::Client, EncryptionLevel::Initial, SERVER_HELLO) => {
+                self.keys.push_back((
+                    EncryptionLevel::Handshake,
+                    key_pair(EncryptionLevel::Handshake, self.side),
+                ));
+            }
+            (Side::Client, EncryptionLevel::Handshake, SERVER_FINISHED) => {
+                self.keys.push_back((
+                    EncryptionLevel::OneRtt,
+                    key_pair(EncryptionLevel::OneRtt, self.side),
+                ));
+                self.handshake
+                    .push_back((EncryptionLevel::Handshake, CLIENT_FINISHED.to_vec()));
+                self.handshaking = false;
+            }
+            (Side::Server, EncryptionLevel::Handshake, CLIENT_FINISHED) => {
+                self.handshaking = false;
+            }
+            // unexpected_message
+            _ => return Err(10),
+        }
+        Ok(())
+    }
+
+    fn poll_handshake(&mut self) -> Option<(EncryptionLevel, Vec<u8>)> {
+        self.handshake.pop_front()
+    }
+
+    fn poll_keys(&mut self) -> Option<(EncryptionLevel, KeyPair)> {
+        self.keys.pop_front()
+    }
+
+    fn is_handshaking(&self) -> bool {
+        self.handshaking
+    }
+
+    fn get_peer_transport_parameters(&self) -> Option<TransportParameters> {
+        (!self.handshaking).then(|| self.params.clone())
+    }
+
+    fn random(&self) -> Box<dyn Random> {
+        let seed = match self.side {
+            Side::Client => 1,
+            Side::Server => 2,
+        };
+        Box::new(XorShiftRandom(seed))
+    }
+}
+
+/// 以 xorshift 代替加密库随机数生成器, 输出确定
+struct XorShiftRandom(u64);
+
+impl Random for XorShiftRandom {
+    fn fill(&mut self, buf: &mut [u8]) {
+        for b in buf {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            *b = self.0 as u8;
+        }
+    }
+}
+
+fn params() -> TransportParameters {
+    let mut params = TransportParameters::new();
+    params.set_initial_max_data(1 << 20);
+    params.set_initial_max_stream_data_bidi_local(1 << 16);
+    params.set_initial_max_stream_data_bidi_remote(1 << 16);
+    params.set_initial_max_stream_data_uni(1 << 16);
+    params.set_initial_max_streams_bidi(4);
+    params.set_initial_max_streams_uni(4);
+    params
+}
+
+fn client_addr() -> SocketAddr {
+    "127.0.0.1:4433".parse().unwrap()
+}
+
+fn server_addr() -> SocketAddr {
+    "127.0.0.1:443".parse().unwrap()
+}
+
+fn pair() -> (Connection, Connection) {
+    pair_with(params(), params())
+}
+
+/// 构造使用指定传输参数的一对连接
+fn pair_with(
+    client_params: TransportParameters,
+    server_params: TransportParameters,
+) -> (Connection, Connection) {
+    pair_with_config(Config::new(client_params), Config::new(server_params))
+}
+
+/// 构造使用指定配置的一对连接, 会话将对端的传输参数交给本端
+fn pair_with_config(client_config: Config, server_config: Config) -> (Connection, Connection) {
+    let client_params = client_config.get_params().clone();
+    let server_params = server_config.get_params().clone();
+    let client = Connection::connect(
+        server_addr(),
+        &[1; 8],
+        &[9; 8],
+        client_config,
+        Box::new(ScriptedSession::new(Side::Client, server_params)),
+    )
+    .unwrap();
+    let server = Connection::accept(
+        client_addr(),
+        &[2; 8],
+        &[9; 8],
+        server_config,
+        Box::new(ScriptedSession::new(Side::Server, client_params)),
+    )
+    .unwrap();
+    (client, server)
+}
+
+fn params_with_idle_timeout(timeout: Duration) -> TransportParameters {
+    let mut params = params();
+    params.set_max_idle_timeout(timeout);
+    params
+}
+
+/// 在两端之间交换数据报, 直到双方都没有可发送的数据
+fn drive(client: &mut Connection, server: &mut Connection, now: Instant) {
+    loop {
+        let mut progressed = false;
+        while let Some(transmit) = client.poll_transmit(now) {
+            assert_eq!(transmit.get_destination(), server_addr());
+            server.handle_datagram(
+                now,
+                client_addr(),
+                transmit.get_ecn(),
+                transmit.get_contents(),
+            );
+            progressed = true;
+        }
+        while let Some(transmit) = server.poll_transmit(now) {
+            assert_eq!(transmit.get_destination(), client_addr());
+            client.handle_datagram(
+                now,
+                server_addr(),
+                transmit.get_ecn(),
+                transmit.get_contents(),
+            );
+            progressed = true;
+        }
+        if !progressed {
+            break;
+        }
+    }
+}
+
+fn events(conn: &mut Connection) -> Vec<Event> {
+    std::iter::from_fn(|| conn.poll_event()).collect()
+}
+
+#[test]
+fn test_connection_handshake_and_stream() {
+    let now = Instant::now();
+    let (mut client, mut server) = pair();
+
+    // 客户端的首个数据报被填充到 1200 字节
+    let first = client.poll_transmit(now).unwrap();
+    assert_eq!(first.get_contents().len(), 1200);
+    server.handle_datagram(now, client_addr(), first.get_ecn(), first.get_contents());
+
+    drive(&mut client, &mut server, now);
+    assert!(client.is_established());
+    assert!(server.is_established());
+    assert_eq!(events(&mut client), vec![Event::Connected]);
+    assert_eq!(events(&mut server), vec![Event::Connected]);
+
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+    assert_eq!(client.write(stream_id, b"hello").unwrap(), 5);
+    client.finish(stream_id).unwrap();
+    drive(&mut client, &mut server, now);
+
+    let server_events = events(&mut server);
+    assert_eq!(server_events[0], Event::StreamOpened(stream_id));
+    assert!(server_events.contains(&Event::StreamReadable(stream_id)));
+
+    let mut buf = [0u8; 16];
+    assert_eq!(server.read(stream_id, &mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+    assert_eq!(server.read(stream_id, &mut buf).unwrap(), 0);
+
+    server.write(stream_id, b"world").unwrap();
+    server.finish(stream_id).unwrap();
+    drive(&mut client, &mut server, now);
+
+    assert!(events(&mut client).contains(&Event::StreamReadable(stream_id)));
+    assert_eq!(client.read(stream_id, &mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"world");
+}
+
+#[test]
+fn test_connection_retransmits_lost_initial() {
+    let mut now = Instant::now();
+    let (mut client, mut server) = pair();
+
+    // 丢弃客户端的首个数据报, PTO 到期后重传 ClientHello
+    assert!(client.poll_transmit(now).is_some());
+    assert!(client.poll_transmit(now).is_none());
+
+    let timeout = client.poll_timeout().unwrap();
+    assert!(timeout > now);
+    now = timeout;
+    client.handle_timeout(now);
+
+    drive(&mut client, &mut server, now);
+    assert!(client.is_established());
+    assert!(server.is_established());
+}
+
+#[test]
+fn test_connection_close() {
+    let now = Instant::now();
+    let (mut client, mut server) = pair();
+    drive(&mut client, &mut server, now);
+    events(&mut client);
+    events(&mut server);
+
+    client.close(now, 42, "bye");
+    assert!(client.is_closing());
+    assert!(!client.is_closed());
+    drive(&mut client, &mut server, now + Duration::from_millis(1));
+
+    assert!(server.is_draining());
+    assert_eq!(
+        events(&mut server),
+        vec![Event::Closed(ConnectionError::application(42, "bye"))]
+    );
+
+    // 三个 PTO 之后两端都释放连接状态
+    let deadline = server.poll_timeout().unwrap();
+    server.handle_timeout(deadline);
+    assert!(server.is_closed());
+    assert!(server.poll_timeout().is_none());
+
+    let deadline = client.poll_timeout().unwrap();
+    client.handle_timeout(deadline - Duration::from_millis(1));
+    assert!(client.is_closing());
+    client.handle_timeout(deadline);
+    assert!(client.is_closed());
+    assert!(client.poll_transmit(deadline).is_none());
+}
+
+#[test]
+fn test_connection_closing_responds_at_limited_rate() {
+    let now = Instant::now();
+    let (mut client, mut server) = pair();
+    drive(&mut client, &mut server, now);
+
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    let stray = client.poll_transmit(now).unwrap();
+
+    server.close(now, 7, "");
+    assert!(server.poll_transmit(now).is_some());
+    assert!(server.poll_transmit(now).is_none());
+
+    // 收到的数据报数量依次达到 1, 2, 4, 8 时才应答 CONNECTION_CLOSE 帧
+    let mut responses = Vec::new();
+    for i in 1..=12 {
+        server.handle_datagram(now, client_addr(), stray.get_ecn(), stray.get_contents());
+        if server.poll_transmit(now).is_some() {
+            responses.push(i);
+        }
+    }
+    assert_eq!(responses, vec![1, 2, 4, 8]);
+    assert!(events(&mut server)
+        .iter()
+        .all(|event| !matches!(event, Event::StreamOpened(_))));
+}
+
+#[test]
+fn test_connection_close_during_handshake() {
+    let now = Instant::now();
+    let (mut client, mut server) = pair();
+    let first = client.poll_transmit(now).unwrap();
+    server.handle_datagram(now, client_addr(), first.get_ecn(), first.get_contents());
+
+    // Initial 数据包中的应用层关闭被转换为 APPLICATION_ERROR 传输层关闭
+    client.close(now, 42, "bye");
+    let transmit = client.poll_transmit(now).unwrap();
+    assert_eq!(transmit.get_contents().len(), 1200);
+    assert!(client.poll_transmit(now).is_none());
+    server.handle_datagram(
+        now,
+        client_addr(),
+        transmit.get_ecn(),
+        transmit.get_contents(),
+    );
+
+    assert!(server.is_draining());
+    assert!(server.poll_transmit(now).is_none());
+    assert_eq!(
+        server.get_close_error(),
+        Some(&ConnectionError::Transport {
+            code: TransportErrorCode::ApplicationError,
+            frame_type: 0,
+            reason: String::new(),
+        })
+    );
+}
+
+#[test]
+fn test_connection_idle_timeout() {
+    let now = Instant::now();
+    let (mut client, mut server) = pair_with(
+        params_with_idle_timeout(Duration::from_secs(10)),
+        params_with_idle_timeout(Duration::from_secs(5)),
+    );
+    drive(&mut client, &mut server, now);
+    events(&mut client);
+
+    // 使用双方通告的空闲超时中较小的一个, 超时后静默关闭
+    let later = now + Duration::from_secs(6);
+    client.handle_timeout(later);
+    assert!(client.is_closed());
+    assert_eq!(client.get_close_error(), Some(&ConnectionError::TimedOut));
+    assert_eq!(
+        events(&mut client),
+        vec![Event::Closed(ConnectionError::TimedOut)]
+    );
+    assert!(client.poll_transmit(later).is_none());
+    assert!(client.poll_timeout().is_none());
+}
+
+#[test]
+fn test_connection_keep_alive() {
+    let start = Instant::now();
+    let (mut client, mut server) = pair_with(
+        params_with_idle_timeout(Duration::from_secs(5)),
+        params_with_idle_timeout(Duration::from_secs(5)),
+    );
+    client.set_keep_alive_interval(Some(Duration::from_secs(2)));
+    drive(&mut client, &mut server, start);
+
+    // 保活 PING 帧使两端在空闲超时之后仍保持连接
+    let mut now = start;
+    while now < start + Duration::from_secs(20) {
+        now = client
+            .poll_timeout()
+            .into_iter()
+            .chain(server.poll_timeout())
+            .min()
+            .unwrap();
+        client.handle_timeout(now);
+        server.handle_timeout(now);
+        drive(&mut client, &mut server, now);
+    }
+    assert!(client.is_established());
+    assert!(server.is_established());
+}
+
+#[test]
+fn test_connection_rotate_connection_id() {
+    let now = Instant::now();
+    let (mut client, mut server) = pair();
+    assert!(!client.rotate_connection_id());
+    drive(&mut client, &mut server, now);
+
+    // 握手完成后双方按对端的 active_connection_id_limit 各提供一个新的 Connection ID
+    assert_eq!(server.get_local_connection_ids().len(), 2);
+    assert!(client.rotate_connection_id());
+    assert!(!client.rotate_connection_id());
+
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    drive(&mut client, &mut server, now);
+
+    // 服务端收到 RETIRE_CONNECTION_ID 帧后废弃握手期间的 Connection ID 并补充新的
+    let local_ids = server.get_local_connection_ids();
+    assert_eq!(local_ids.len(), 2);
+    assert!(!local_ids.contains(&&[2u8; 8][..]));
+    assert!(events(&mut server).contains(&Event::StreamReadable(stream_id)));
+    assert!(client.rotate_connection_id());
+}
+
+fn migrated_addr() -> SocketAddr {
+    "127.0.0.1:5555".parse().unwrap()
+}
+
+/// 建立连接, 并在延迟 ACK 定时器到期后交换完所有数据报
+fn established() -> (Connection, Connection, Instant) {
+    let (mut client, mut server) = pair();
+    let start = Instant::now();
+    drive(&mut client, &mut server, start);
+
+    let now = start + Duration::from_millis(100);
+    client.handle_timeout(now);
+    server.handle_timeout(now);
+    drive(&mut client, &mut server, now);
+    events(&mut client);
+    events(&mut server);
+    (client, server, now)
+}
+
+#[test]
+fn test_connection_path_validation() {
+    let (mut client, mut server, now) = established();
+
+    // 携带 PATH_CHALLENGE 与 PATH_RESPONSE 帧的数据报均填充到 1200 字节
+    client.validate_path(now);
+    let challenge = client.poll_transmit(now).unwrap();
+    assert_eq!(challenge.get_contents().len(), 1200);
+    server.handle_datagram(
+        now,
+        client_addr(),
+        challenge.get_ecn(),
+        challenge.get_contents(),
+    );
+
+    let response = server.poll_transmit(now).unwrap();
+    assert_eq!(response.get_destination(), client_addr());
+    assert_eq!(response.get_contents().len(), 1200);
+    client.handle_datagram(
+        now,
+        server_addr(),
+        response.get_ecn(),
+        response.get_contents(),
+    );
+    assert_eq!(
+        events(&mut client),
+        vec![Event::PathValidated(server_addr())]
+    );
+}
+
+#[test]
+fn test_connection_probe_from_other_path() {
+    let (mut client, mut server, now) = established();
+
+    // 仅含探测帧的数据包不会引起迁移, 应答在收到挑战的路径上单独发送
+    client.validate_path(now);
+    let challenge = client.poll_transmit(now).unwrap();
+    server.handle_datagram(
+        now,
+        migrated_addr(),
+        challenge.get_ecn(),
+        challenge.get_contents(),
+    );
+    assert_eq!(server.get_remote(), client_addr());
+
+    let response = server.poll_transmit(now).unwrap();
+    assert_eq!(response.get_destination(), migrated_addr());
+    assert_eq!(response.get_contents().len(), 1200);
+    assert!(server.poll_transmit(now).is_none());
+
+    // 应答来自其他路径, 不能完成对服务端地址的验证
+    client.handle_datagram(
+        now,
+        migrated_addr(),
+        response.get_ecn(),
+        response.get_contents(),
+    );
+    assert!(events(&mut client).is_empty());
+}
+
+#[test]
+fn test_connection_migration() {
+    let (mut client, mut server, now) = established();
+
+    // 客户端的地址变化后, 服务端在新路径上发起验证
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    let datagram = client.poll_transmit(now).unwrap();
+    let received = datagram.get_contents().len();
+    server.handle_datagram(
+        now,
+        migrated_addr(),
+        datagram.get_ecn(),
+        datagram.get_contents(),
+    );
+    assert_eq!(server.get_remote(), migrated_addr());
+
+    // 新路径验证完成之前受放大限制
+    let mut sent = 0;
+    while let Some(transmit) = server.poll_transmit(now) {
+        assert_eq!(transmit.get_destination(), migrated_addr());
+        sent += transmit.get_contents().len();
+        client.handle_datagram(
+            now,
+            server_addr(),
+            transmit.get_ecn(),
+            transmit.get_contents(),
+        );
+    }
+    assert!(sent <= received * 3);
+
+    while let Some(transmit) = client.poll_transmit(now) {
+        server.handle_datagram(
+            now,
+            migrated_addr(),
+            transmit.get_ecn(),
+            transmit.get_contents(),
+        );
+    }
+    let server_events = events(&mut server);
+    assert!(server_events.contains(&Event::PathValidated(migrated_addr())));
+    assert!(server_events.contains(&Event::StreamReadable(stream_id)));
+}
+
+#[test]
+fn test_connection_migration_validation_failed() {
+    let (mut client, mut server, start) = established();
+
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    let datagram = client.poll_transmit(start).unwrap();
+    server.handle_datagram(
+        start,
+        migrated_addr(),
+        datagram.get_ecn(),
+        datagram.get_contents(),
+    );
+
+    // 新路径始终没有应答, 受放大限制不能再重发, 验证超时后恢复使用之前的地址
+    let mut now = start;
+    let mut challenges = 0;
+    while server.get_remote() == migrated_addr() {
+        while let Some(transmit) = server.poll_transmit(now) {
+            assert_eq!(transmit.get_destination(), migrated_addr());
+            challenges += 1;
+        }
+        now = server.poll_timeout().unwrap();
+        server.handle_timeout(now);
+    }
+    assert_eq!(challenges, 1);
+    assert_eq!(server.get_remote(), client_addr());
+    assert!(now - start >= Duration::from_millis(6 * 333));
+    assert!(events(&mut server).contains(&Event::PathValidationFailed(migrated_addr())));
+}
+
+#[test]
+fn test_connection_applies_peer_ack_delay() {
+    let mut server_params = params();
+    server_params.set_ack_delay_exponent(8);
+    server_params.set_max_ack_delay(Duration::from_millis(200));
+    let (mut client, mut server) = pair_with(params(), server_params);
+
+    let start = Instant::now();
+    drive(&mut client, &mut server, start);
+    let now = start + Duration::from_millis(200);
+    client.handle_timeout(now);
+    server.handle_timeout(now);
+    drive(&mut client, &mut server, now);
+
+    // 服务端按自己的 max_ack_delay 延迟确认, 并以自己的指数编码 ACK Delay
+    let stream_id = client.open_stream(StreamDirection::Uni).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    let datagram = client.poll_transmit(now).unwrap();
+    server.handle_datagram(
+        now,
+        client_addr(),
+        datagram.get_ecn(),
+        datagram.get_contents(),
+    );
+    assert!(server.poll_transmit(now).is_none());
+
+    let later = now + Duration::from_millis(200);
+    assert_eq!(server.poll_timeout(), Some(later));
+    server.handle_timeout(later);
+    let ack = server.poll_transmit(later).unwrap();
+    client.handle_datagram(later, server_addr(), ack.get_ecn(), ack.get_contents());
+
+    // 整个 RTT 样本都是对端的 ACK 延迟, 扣除后只剩下 ACK Delay 编码的精度误差
+    assert_eq!(
+        client.get_rtt().get_latest_rtt(),
+        Duration::from_millis(200)
+    );
+    assert!(client.get_rtt().get_smoothed_rtt() < Duration::from_millis(1));
+}
+
+#[test]
+fn test_connection_uni_stream_not_blocked_on_receiver() {
+    let (mut client, mut server, now) = established();
+
+    let stream_id = client.open_stream(StreamDirection::Uni).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    drive(&mut client, &mut server, now);
+
+    // 接收端对单向流没有发送窗口, 发送其他数据时不能附带 STREAM_DATA_BLOCKED
+    let reply = server.open_stream(StreamDirection::Uni).unwrap();
+    server.write(reply, b"world").unwrap();
+    drive(&mut client, &mut server, now);
+
+    assert!(client.get_close_error().is_none());
+    assert!(client.is_established());
+    let mut buf = [0u8; 16];
+    assert_eq!(server.read(stream_id, &mut buf).unwrap(), 5);
+    assert_eq!(client.read(reply, &mut buf).unwrap(), 5);
+}
+
+#[test]
+fn test_connection_ecn_validation() {
+    let mut now = Instant::now();
+    let (mut client, mut server) = pair();
+    drive(&mut client, &mut server, now);
+
+    // 验证阶段发送的 ECT(0) 数据包被对端在 ACK 帧中如实计数, 路径通过验证
+    let stream_id = client.open_stream(StreamDirection::Uni).unwrap();
+    for _ in 0..4 {
+        client.write(stream_id, &[0; 8000]).unwrap();
+        now += Duration::from_millis(100);
+        client.handle_timeout(now);
+        server.handle_timeout(now);
+        drive(&mut client, &mut server, now);
+    }
+    now += Duration::from_millis(100);
+    client.handle_timeout(now);
+
+    client.write(stream_id, b"hello").unwrap();
+    let transmit = client.poll_transmit(now).unwrap();
+    assert_eq!(transmit.get_ecn(), EcnCodepoint::Ect0);
+}
+
+#[test]
+fn test_connection_rejects_unnegotiated_reset_stream_at() {
+    // 客户端的会话误报服务端通告了 reset_stream_at
+    let mut client_params = params();
+    client_params.set_reset_stream_at(true);
+    let mut claimed = params();
+    claimed.set_reset_stream_at(true);
+    let mut client = Connection::connect(
+        server_addr(),
+        &[1; 8],
+        &[9; 8],
+        Config::new(client_params.clone()),
+        Box::new(ScriptedSession::new(Side::Client, claimed)),
+    )
+    .unwrap();
+    let mut server = Connection::accept(
+        client_addr(),
+        &[2; 8],
+        &[9; 8],
+        Config::new(params()),
+        Box::new(ScriptedSession::new(Side::Server, client_params)),
+    )
+    .unwrap();
+    let now = Instant::now();
+    drive(&mut client, &mut server, now);
+
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    client.reset_stream_at(stream_id, 7, 2).unwrap();
+    drive(&mut client, &mut server, now);
+
+    // 服务端没有通告 reset_stream_at, 收到 RESET_STREAM_AT 帧时关闭连接
+
+    let Some(ConnectionError::Transport {
+        code, frame_type, ..
+    }) = server.get_close_error()
+    else {
+        panic!("connection not closed with a transport error");
+    };
+    assert_eq!(*code, TransportErrorCode::FrameEncodingError);
+    assert_eq!(*frame_type, 0x24);
+}
+
+#[test]
+fn test_connection_rejects_frame_on_unopened_local_stream() {
+    // 两对连接的握手完全相同, 第二对服务端的数据包可以被第一对客户端接受
+    let (mut client, _server, now) = established();
+    let (mut other_client, mut other_server, _) = established();
+
+    // 客户端在另一个流上发送同样编号的数据包, 使第二对服务端的 ACK 帧只确认已发送的数据包
+    let uni = client.open_stream(StreamDirection::Uni).unwrap();
+    client.write(uni, b"hello").unwrap();
+    client.poll_transmit(now).unwrap();
+
+    let stream_id = other_client.open_stream(StreamDirection::Bidi).unwrap();
+    other_client.write(stream_id, b"hello").unwrap();
+    drive(&mut other_client, &mut other_server, now);
+    other_server.write(stream_id, b"world").unwrap();
+    let transmit = other_server.poll_transmit(now).unwrap();
+
+    // 客户端从未打开该流, 收到其上的 STREAM 帧时关闭连接
+    client.handle_datagram(
+        now,
+        server_addr(),
+        transmit.get_ecn(),
+        transmit.get_contents(),
+    );
+    let Some(ConnectionError::Transport { code, .. }) = client.get_close_error() else {
+        panic!("connection not closed with a transport error");
+    };
+    assert_eq!(*code, TransportErrorCode::StreamStateError);
+}
+
+#[test]
+fn test_connection_reset_streams_release_connection_credit() {
+    let mut server_params = params();
+    server_params.set_initial_max_data(4000);
+    server_params.set_initial_max_streams_uni(16);
+    let (mut client, mut server) = pair_with(params(), server_params);
+    let mut now = Instant::now();
+    drive(&mut client, &mut server, now);
+    events(&mut server);
+
+    // 被重置的流上未读取的数据不再占用连接级信用, 累计发送量可以超过初始限制
+    let mut buf = [0u8; 16];
+    for _ in 0..10 {
+        let stream_id = client.open_stream(StreamDirection::Uni).unwrap();
+        client.write(stream_id, &[0; 1000]).unwrap();
+        drive(&mut client, &mut server, now);
+        assert!(events(&mut server).contains(&Event::StreamReadable(stream_id)));
+
+        client.reset_stream(stream_id, 1).unwrap();
+        drive(&mut client, &mut server, now);
+        assert_eq!(server.read(stream_id, &mut buf), Err(StreamError::Reset(1)));
+
+        now += Duration::from_millis(100);
+        client.handle_timeout(now);
+        server.handle_timeout(now);
+        drive(&mut client, &mut server, now);
+    }
+}
+
+#[test]
+fn test_connection_sends_max_streams_when_streams_close() {
+    let (mut client, mut server, now) = established();
+
+    let mut streams = Vec::new();
+    for _ in 0..4 {
+        let stream_id = client.open_stream(StreamDirection::Uni).unwrap();
+        client.write(stream_id, b"hello").unwrap();
+        client.finish(stream_id).unwrap();
+        streams.push(stream_id);
+    }
+    assert!(client.open_stream(StreamDirection::Uni).is_none());
+    drive(&mut client, &mut server, now);
+
+    // 对端的流全部关闭后, 仅有 MAX_STREAMS 帧待发送时也要发送数据包
+    let mut buf = [0u8; 16];
+    for stream_id in streams {
+        assert_eq!(server.read(stream_id, &mut buf).unwrap(), 5);
+    }
+    drive(&mut client, &mut server, now);
+    assert!(client.open_stream(StreamDirection::Uni).is_some());
+}
+
+/// 记录发送回调次数的拥塞控制算法, 其余行为委托给 NewReno
+struct CountingController {
+    inner: NewReno,
+    sent: Rc<Cell<usize>>,
+}
+
+impl CongestionController for CountingController {
+    fn on_packet_sent(&mut self, packet: &SentPacket, bytes_in_flight: usize) {
+        self.sent.set(self.sent.get() + 1);
+        self.inner.on_packet_sent(packet, bytes_in_flight);
+    }
+
+    fn on_ack(&mut self, now: Instant, packet: &SentPacket, rtt: &RttEstimator) {
+        self.inner.on_ack(now, packet, rtt);
+    }
+
+    fn on_congestion_event(&mut self, now: Instant, time_sent: Instant) {
+        self.inner.on_congestion_event(now, time_sent);
+    }
+
+    fn on_persistent_congestion(&mut self, now: Instant) {
+        self.inner.on_persistent_congestion(now);
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn get_window(&self) -> usize {
+        self.inner.get_window()
+    }
+}
+
+#[test]
+fn test_connection_uses_configured_congestion_controller() {
+    let sent = Rc::new(Cell::new(0));
+    let mut client_config = Config::new(params());
+    client_config.set_congestion_controller(Box::new(CountingController {
+        inner: NewReno::new(1200),
+        sent: sent.clone(),
+    }));
+    let (mut client, mut server) = pair_with_config(client_config, Config::new(params()));
+
+    let now = Instant::now();
+    drive(&mut client, &mut server, now);
+    assert!(client.is_established());
+    assert!(sent.get() > 0);
+}
+
+#[test]
+fn test_connection_zero_length_connection_id() {
+    // 客户端使用零长度的 Connection ID, 不提供新的 Connection ID
+    let mut client = Connection::connect(
+        server_addr(),
+        &[],
+        &[9; 8],
+        Config::new(params()),
+        Box::new(ScriptedSession::new(Side::Client, params())),
+    )
+    .unwrap();
+    let mut server = Connection::accept(
+        client_addr(),
+        &[2; 8],
+        &[9; 8],
+        Config::new(params()),
+        Box::new(ScriptedSession::new(Side::Server, params())),
+    )
+    .unwrap();
+    let now = Instant::now();
+    drive(&mut client, &mut server, now);
+    let now = now + Duration::from_millis(100);
+    client.handle_timeout(now);
+    server.handle_timeout(now);
+    drive(&mut client, &mut server, now);
+
+    assert!(client.is_established());
+    assert!(server.is_established());
+    assert_eq!(client.get_local_connection_ids(), vec![&[][..]]);
+    assert!(!server.rotate_connection_id());
+    assert_eq!(server.get_local_connection_ids().len(), 2);
+    assert!(client.rotate_connection_id());
+
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    drive(&mut client, &mut server, now);
+    server.write(stream_id, b"world").unwrap();
+    drive(&mut client, &mut server, now);
+    let mut buf = [0u8; 16];
+    assert_eq!(client.read(stream_id, &mut buf).unwrap(), 5);
+}
+
+#[test]
+fn test_connection_id_configuration_errors() {
+    let connect = |local_cid: &[u8], remote_cid: &[u8]| {
+        Connection::connect(
+            server_addr(),
+            local_cid,
+            remote_cid,
+            Config::new(params()),
+            Box::new(ScriptedSession::new(Side::Client, params())),
+        )
+    };
+    assert_eq!(
+        connect(&[1; 21], &[9; 8]).err(),
+        Some(ConnectionIdError::InvalidLength(21))
+    );
+    assert_eq!(
+        connect(&[1; 8], &[9; 21]).err(),
+        Some(ConnectionIdError::InvalidLength(21))
+    );
+
+    // 生成器的长度必须与本端的 Connection ID 一致, 且只能在握手完成之前替换
+    let (mut client, mut server) = pair();
+    assert_eq!(
+        client.set_connection_id_generator(Box::new(
+            RandomConnectionIdGenerator::new(4, Box::new(XorShiftRandom(3))).unwrap()
+        )),
+        Err(ConnectionIdError::LengthMismatch {
+            expected: 8,
+            actual: 4
+        })
+    );
+    assert!(client
+        .set_connection_id_generator(Box::new(
+            RandomConnectionIdGenerator::new(8, Box::new(XorShiftRandom(3))).unwrap()
+        ))
+        .is_ok());
+
+    drive(&mut client, &mut server, Instant::now());
+    assert!(client.is_established());
+    assert_eq!(
+        client.set_connection_id_generator(Box::new(
+            RandomConnectionIdGenerator::new(8, Box::new(XorShiftRandom(3))).unwrap()
+        )),
+        Err(ConnectionIdError::InvalidState)
+    );
+}
+
+#[test]
+fn test_connection_migration_resets_path_state() {
+    let (mut client, mut server, now) = established();
+    let original_cid = client.get_local_connection_ids()[0].to_vec();
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+
+    // 仅端口改变, 通常是 NAT 重新绑定, 保留 RTT 估算
+    client.write(stream_id, b"hello").unwrap();
+    let datagram = client.poll_transmit(now).unwrap();
+    server.handle_datagram(
+        now,
+        migrated_addr(),
+        datagram.get_ecn(),
+        datagram.get_contents(),
+    );
+    assert_eq!(server.get_remote(), migrated_addr());
+    assert!(server.get_rtt().has_sample());
+
+    // IP 地址改变后新路径的 RTT 未知, 恢复初始估算
+    let other_addr: SocketAddr = "127.0.0.2:4433".parse().unwrap();
+    client.write(stream_id, b"world").unwrap();
+    let datagram = client.poll_transmit(now).unwrap();
+    server.handle_datagram(now, other_addr, datagram.get_ecn(), datagram.get_contents());
+    assert_eq!(server.get_remote(), other_addr);
+    assert!(!server.get_rtt().has_sample());
+
+    // 向新地址发送时换用未使用过的 Connection ID, 并废弃之前使用的 Connection ID
+    while let Some(transmit) = server.poll_transmit(now) {
+        assert_eq!(transmit.get_destination(), other_addr);
+        client.handle_datagram(
+            now,
+            server_addr(),
+            transmit.get_ecn(),
+            transmit.get_contents(),
+        );
+    }
+    assert!(!client
+        .get_local_connection_ids()
+        .contains(&original_cid.as_slice()));
+}
+
+#[test]
+fn test_connection_stream_states() {
+    let (mut client, mut server, now) = established();
+    let stream_id = client.open_stream(StreamDirection::Bidi).unwrap();
+    assert_eq!(client.get_send_state(stream_id), Some(SendState::Ready));
+    assert_eq!(client.get_recv_state(stream_id), Some(RecvState::Recv));
+    assert_eq!(server.get_recv_state(stream_id), None);
+
+    // 发出首个 STREAM 帧后进入 Send
+    client.write(stream_id, b"hello").unwrap();
+    assert_eq!(client.get_send_state(stream_id), Some(SendState::Ready));
+    drive(&mut client, &mut server, now);
+    assert_eq!(client.get_send_state(stream_id), Some(SendState::Send));
+    assert_eq!(server.get_recv_state(stream_id), Some(RecvState::Recv));
+
+    // 携带 FIN 的 STREAM 帧发出后进入 DataSent, 被确认后进入 DataRecvd
+    client.finish(stream_id).unwrap();
+    let transmit = client.poll_transmit(now).unwrap();
+    assert_eq!(client.get_send_state(stream_id), Some(SendState::DataSent));
+    server.handle_datagram(
+        now,
+        client_addr(),
+        transmit.get_ecn(),
+        transmit.get_contents(),
+    );
+    assert_eq!(server.get_recv_state(stream_id), Some(RecvState::DataRecvd));
+    assert_eq!(server.get_send_state(stream_id), Some(SendState::Ready));
+    drive(&mut client, &mut server, now);
+    assert_eq!(client.get_send_state(stream_id), Some(SendState::DataRecvd));
+
+    // 应用层读完数据后接收部分进入终止状态
+    let mut buf = [0; 16];
+    assert_eq!(server.read(stream_id, &mut buf), Ok(5));
+    assert_eq!(server.read(stream_id, &mut buf), Ok(0));
+    assert_eq!(server.get_recv_state(stream_id), Some(RecvState::DataRead));
+
+    // 重置流后进入 ResetSent
+    let stream_id = client.open_stream(StreamDirection::Uni).unwrap();
+    client.write(stream_id, b"hello").unwrap();
+    client.reset_stream(stream_id, 7).unwrap();
+    assert_eq!(client.get_send_state(stream_id), Some(SendState::ResetSent));
+    assert_eq!(client.get_recv_state(stream_id), None);
+
+    // 重置被确认后流进入终止状态并被移除
+    drive(&mut client, &mut server, now);
+    let now = now + Duration::from_millis(100);
+    server.handle_timeout(now);
+    drive(&mut client, &mut server, now);
+    assert_eq!(client.get_send_state(stream_id), None);
+}
+
+#[test]
+fn test_connection_set_priority() {
+    let (mut client, mut server, now) = established();
+    let priority = Priority::new(1, true);
+
+    // 未打开的流与没有发送部分的流
+    assert_eq!(
+        client.set_priority(StreamId::new(0), priority),
+        Err(StreamError::UnknownStream)
+    );
+    let stream_id = server.open_stream(StreamDirection::Uni).unwrap();
+    server.write(stream_id, b"hello").unwrap();
+    drive(&mut client, &mut server, now);
+    assert_eq!(
+        client.set_priority(stream_id, priority),
+        Err(StreamError::UnknownStream)
+    );
+
+    // 流被重置并移除之后不能再设置优先级
+    let stream_id = client.open_stream(StreamDirection::Uni).unwrap();
+    assert_eq!(client.set_priority(stream_id, priority), Ok(()));
+    client.write(stream_id, b"hello").unwrap();
+    client.reset_stream(stream_id, 7).unwrap();
+    drive(&mut client, &mut server, now);
+    let now = now + Duration::from_millis(100);
+    server.handle_timeout(now);
+    drive(&mut client, &mut server, now);
+    assert_eq!(client.get_send_state(stream_id), None);
+    assert_eq!(
+        client.set_priority(stream_id, priority),
+        Err(StreamError::UnknownStream)
+    );
+}
+
+#[test]
+fn test_connection_reset_stream_at_respects_flow_control() {
+    let mut client_params = params();
+    client_params.set_reset_stream_at(true);
+    let mut server_params = params();
+    server_params.set_reset_stream_at(true);
+    server_params.set_initial_max_data(1000);
+    let (mut client, mut server) = pair_with(client_params, server_params);
+    let now = Instant::now();
+    drive(&mut client, &mut server, now);
+
+    // 可靠大小被限制在对端的 MAX_DATA 之内, 并预先消耗连接级信用
+    let stream_id = client.open_stream(StreamDirection::Uni).unwrap();
+    client.write(stream_id, &[7; 3000]).unwrap();
+    client.reset_stream_at(stream_id, 7, 3000).unwrap();
+    let other = client.open_stream(StreamDirection::Uni).unwrap();
+    client.write(other, b"hello").unwrap();
+    drive(&mut client, &mut server, now);
+    assert!(server.get_close_error().is_none());
+    assert_eq!(server.get_recv_state(other), None);
+
+    let mut buf = [0; 4000];
+    assert_eq!(server.read(stream_id, &mut buf), Ok(1000));
+    assert_eq!(server.read(stream_id, &mut buf), Err(StreamError::Reset(7)));
+}
@@ -0,0 +1,35 @@
+use std::net::SocketAddr;
+
+use crate::{attr::StreamId, error::ConnectionError};
+
+/// 连接事件
+///
+/// 由 `Connection::poll_event` 依次取出, 通知应用层连接与流的状态变化.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// 握手完成, 可以打开流
+    Connected,
+
+    /// 对端打开了一个新的流
+    StreamOpened(StreamId),
+
+    /// 流上有可读取的数据, 或流已结束、已被对端重置
+    StreamReadable(StreamId),
+
+    /// 对端以 STOP_SENDING 要求停止在流上发送
+    StreamStopped {
+        /// Stream 标识
+        stream_id: StreamId,
+        /// 应用错误码
+        error_code: u64,
+    },
+
+    /// 对端地址的路径验证成功
+    PathValidated(SocketAddr),
+
+    /// 对端地址的路径验证失败; 因对端地址变化而发起的验证失败后恢复使用之前的地址
+    PathValidationFailed(SocketAddr),
+
+    /// 连接已关闭, 携带关闭的原因
+    Closed(ConnectionError),
+}
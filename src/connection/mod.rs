@@ -0,0 +1,23 @@
+mod config;
+mod conn;
+mod event;
+mod path;
+mod space;
+mod transmit;
+
+pub(crate) use config::*;
+pub(crate) use conn::*;
+pub(crate) use event::*;
+pub(crate) use path::*;
+pub(crate) use space::*;
+pub(crate) use transmit::*;
+
+pub use config::Config;
+pub use conn::Connection;
+pub use event::Event;
+pub use transmit::Transmit;
+
+#[cfg(test)]
+mod conn_test;
+#[cfg(test)]
+mod path_test;
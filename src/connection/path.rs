@@ -0,0 +1,210 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use crate::crypto::Random;
+
+/// PATH_CHALLENGE 帧中 Data 的长度
+pub(crate) const PATH_CHALLENGE_LEN: usize = 8;
+
+/// 路径验证超时的下限, 即六倍的初始 RTT (RFC 9000 §8.2.4)
+const MIN_VALIDATION_TIMEOUT: Duration = Duration::from_millis(6 * 333);
+
+/// 路径验证超时, 以 PTO 计 (RFC 9000 §8.2.4)
+const VALIDATION_PTO_COUNT: u32 = 3;
+
+/// 路径验证器
+///
+/// 向对端地址发送携带不可预测数据的 PATH_CHALLENGE 帧, 在同一路径上收到携带相同数据的
+/// PATH_RESPONSE 帧即验证成功 (RFC 9000 §8.2). 未收到应答时按指数退避重发 PATH_CHALLENGE 帧,
+/// 超过验证时限后验证失败.
+pub(crate) struct PathValidator {
+    /// 被验证的对端地址
+    remote: SocketAddr,
+
+    /// 已发送的 PATH_CHALLENGE 帧中的数据, 应答其中任意一个即验证成功
+    challenges: Vec<[u8; PATH_CHALLENGE_LEN]>,
+
+    /// 是否需要发送新的 PATH_CHALLENGE 帧
+    challenge_pending: bool,
+
+    /// 重发 PATH_CHALLENGE 帧的间隔, 每次发送后翻倍
+    retry_interval: Duration,
+
+    /// 重发 PATH_CHALLENGE 帧的时刻
+    retry_at: Option<Instant>,
+
+    /// 验证失败的时刻
+    deadline: Instant,
+}
+
+/// 待发送的 PATH_RESPONSE 帧
+pub(crate) struct PendingPathResponse {
+    /// 收到 PATH_CHALLENGE 帧的路径, 应答在同一路径上发送
+    remote: SocketAddr,
+
+    /// PATH_CHALLENGE 帧中的数据
+    data: [u8; PATH_CHALLENGE_LEN],
+
+    /// 向未验证的路径最多可发送的字节数, 即携带 PATH_CHALLENGE 帧的数据报的三倍
+    budget: usize,
+}
+
+impl PathValidator {
+    /// 开始验证一条路径
+    ///
+    /// # Arguments
+    /// `remote` - 被验证的对端地址
+    /// `now` - 当前时刻
+    /// `pto` - 当前的 PTO 时长, 作为首次重发的间隔
+    /// # Returns
+    /// 返回路径验证器, 首个 PATH_CHALLENGE 帧等待发送
+    pub(crate) fn new(remote: SocketAddr, now: Instant, pto: Duration) -> Self {
+        Self {
+            remote,
+            challenges: Vec::new(),
+            challenge_pending: true,
+            retry_interval: pto,
+            retry_at: None,
+            deadline: now + (pto * VALIDATION_PTO_COUNT).max(MIN_VALIDATION_TIMEOUT),
+        }
+    }
+
+    /// 获取被验证的对端地址
+    ///
+    /// # Returns
+    /// 返回对端地址
+    #[inline(always)]
+    pub(crate) const fn get_remote(&self) -> SocketAddr {
+        self.remote
+    }
+
+    /// 判断是否需要发送新的 PATH_CHALLENGE 帧
+    ///
+    /// # Returns
+    /// 返回是否需要发送 PATH_CHALLENGE 帧
+    #[inline(always)]
+    pub(crate) const fn is_challenge_pending(&self) -> bool {
+        self.challenge_pending
+    }
+
+    /// 取出新的 PATH_CHALLENGE 帧的数据, 并设置重发定时器
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `random` - 生成数据的随机数生成器
+    /// # Returns
+    /// 返回随机生成的数据, 不需要发送 PATH_CHALLENGE 帧时返回 None
+    pub(crate) fn poll_challenge(
+        &mut self,
+        now: Instant,
+        random: &mut dyn Random,
+    ) -> Option<[u8; PATH_CHALLENGE_LEN]> {
+        if !self.challenge_pending {
+            return None;
+        }
+
+        let mut data = [0; PATH_CHALLENGE_LEN];
+        random.fill(&mut data);
+        self.challenges.push(data);
+        self.challenge_pending = false;
+        self.retry_at = Some(now + self.retry_interval);
+        self.retry_interval *= 2;
+        Some(data)
+    }
+
+    /// 处理 PATH_RESPONSE 帧
+    ///
+    /// # Arguments
+    /// `remote` - 收到 PATH_RESPONSE 帧的路径
+    /// `data` - PATH_RESPONSE 帧中的数据
+    /// # Returns
+    /// 在被验证的路径上应答了已发送的 PATH_CHALLENGE 帧时返回 true, 即验证成功
+    pub(crate) fn on_path_response(&self, remote: SocketAddr, data: &[u8]) -> bool {
+        remote == self.remote && self.challenges.iter().any(|challenge| challenge == data)
+    }
+
+    /// 获取下一次需要调用 `on_timeout` 的时刻
+    ///
+    /// # Returns
+    /// 返回重发与验证失败的时刻中较早的一个
+    pub(crate) fn get_timeout(&self) -> Instant {
+        self.retry_at
+            .map_or(self.deadline, |retry_at| retry_at.min(self.deadline))
+    }
+
+    /// 处理到期的定时器
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// # Returns
+    /// 超过验证时限时返回 true, 即验证失败
+    pub(crate) fn on_timeout(&mut self, now: Instant) -> bool {
+        if self.deadline <= now {
+            return true;
+        }
+        if self.retry_at.is_some_and(|retry_at| retry_at <= now) {
+            self.retry_at = None;
+            self.challenge_pending = true;
+        }
+        false
+    }
+}
+
+impl PendingPathResponse {
+    /// 构造一个待发送的 PATH_RESPONSE 帧
+    ///
+    /// # Arguments
+    /// `remote` - 收到 PATH_CHALLENGE 帧的路径
+    /// `data` - PATH_CHALLENGE 帧中的数据
+    /// `budget` - 向该路径最多可发送的字节数
+    /// # Returns
+    /// 返回待发送的 PATH_RESPONSE 帧
+    pub(crate) fn new(remote: SocketAddr, data: &[u8], budget: usize) -> Self {
+        let mut response = Self {
+            remote,
+            data: [0; PATH_CHALLENGE_LEN],
+            budget,
+        };
+        response.data.copy_from_slice(data);
+        response
+    }
+
+    /// 获取应答的路径
+    ///
+    /// # Returns
+    /// 返回对端地址
+    #[inline(always)]
+    pub(crate) const fn get_remote(&self) -> SocketAddr {
+        self.remote
+    }
+
+    /// 获取应答的数据
+    ///
+    /// # Returns
+    /// 返回 PATH_CHALLENGE 帧中的数据
+    #[inline(always)]
+    pub(crate) const fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 获取向该路径最多可发送的字节数
+    ///
+    /// # Returns
+    /// 返回字节数
+    #[inline(always)]
+    pub(crate) const fn get_budget(&self) -> usize {
+        self.budget
+    }
+
+    /// 以同一路径上更新的 PATH_CHALLENGE 帧替换应答的数据
+    ///
+    /// # Arguments
+    /// `data` - PATH_CHALLENGE 帧中的数据
+    /// `budget` - 向该路径最多可发送的字节数
+    pub(crate) fn update(&mut self, data: &[u8], budget: usize) {
+        self.data.copy_from_slice(data);
+        self.budget = budget;
+    }
+}
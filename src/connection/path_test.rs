@@ -0,0 +1,84 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use crate::crypto::Random;
+
+use super::PathValidator;
+
+const PTO: Duration = Duration::from_millis(100);
+
+/// 按序产生字节的随机数生成器, 保证每次的数据不同
+struct CountingRandom(u8);
+
+impl Random for CountingRandom {
+    fn fill(&mut self, buf: &mut [u8]) {
+        for b in buf {
+            self.0 = self.0.wrapping_add(1);
+            *b = self.0;
+        }
+    }
+}
+
+fn addr(port: u16) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], port))
+}
+
+#[test]
+fn test_path_validation_success() {
+    let now = Instant::now();
+    let mut validator = PathValidator::new(addr(1), now, PTO);
+    let mut random = CountingRandom(0);
+    assert!(validator.is_challenge_pending());
+
+    let first = validator.poll_challenge(now, &mut random).unwrap();
+    assert!(validator.poll_challenge(now, &mut random).is_none());
+
+    // 重发的 PATH_CHALLENGE 帧携带新的随机数据
+    validator.on_timeout(now + PTO);
+    let second = validator.poll_challenge(now + PTO, &mut random).unwrap();
+    assert_ne!(first, second);
+
+    // 只有在同一路径上应答已发送的数据才算验证成功
+    assert!(!validator.on_path_response(addr(2), &first));
+    assert!(!validator.on_path_response(addr(1), &[0; 8]));
+    assert!(validator.on_path_response(addr(1), &first));
+    assert!(validator.on_path_response(addr(1), &second));
+}
+
+#[test]
+fn test_path_validation_backoff() {
+    let start = Instant::now();
+    let mut validator = PathValidator::new(addr(1), start, PTO);
+    let mut random = CountingRandom(0);
+
+    // 重发间隔依次为 1、2、4 倍 PTO
+    let mut now = start;
+    let mut sent = Vec::new();
+    for _ in 0..3 {
+        validator.poll_challenge(now, &mut random).unwrap();
+        sent.push(now - start);
+        now = validator.get_timeout();
+        assert!(!validator.on_timeout(now));
+    }
+    assert_eq!(sent, vec![Duration::ZERO, PTO, PTO * 3]);
+}
+
+#[test]
+fn test_path_validation_timeout() {
+    let start = Instant::now();
+    let mut validator = PathValidator::new(addr(1), start, PTO);
+    let mut random = CountingRandom(0);
+
+    // 三倍 PTO 小于六倍初始 RTT 时使用后者作为验证时限
+    let mut now = start;
+    loop {
+        validator.poll_challenge(now, &mut random);
+        now = validator.get_timeout();
+        if validator.on_timeout(now) {
+            break;
+        }
+    }
+    assert_eq!(now - start, Duration::from_millis(6 * 333));
+}
@@ -0,0 +1,127 @@
+use crate::{
+    attr::PacketNumber,
+    crypto::KeyPair,
+    recovery::ReceivedPackets,
+    stream::{RecvBuffer, SendBuffer},
+};
+
+/// 数据包编号空间的连接状态
+///
+/// 每个编号空间拥有独立的密钥、数据包编号、CRYPTO 数据流与接收记录.
+/// Initial 与 Handshake 空间在握手推进后被丢弃, 之后不再收发该空间的数据包.
+pub(crate) struct PacketSpace {
+    /// 该空间的密钥, 尚未获得或已丢弃时为 None
+    keys: Option<KeyPair>,
+
+    /// 下一个发送的数据包编号
+    next_packet_number: PacketNumber,
+
+    /// 待发送的握手数据
+    crypto_send: SendBuffer,
+
+    /// 收到的握手数据
+    crypto_recv: RecvBuffer,
+
+    /// 收到的数据包记录
+    received: ReceivedPackets,
+}
+
+impl PacketSpace {
+    /// 构造一个尚无密钥的编号空间
+    ///
+    /// # Returns
+    /// 返回编号空间
+    pub(crate) fn new() -> Self {
+        Self {
+            keys: None,
+            next_packet_number: 0,
+            crypto_send: SendBuffer::new(),
+            crypto_recv: RecvBuffer::new(),
+            received: ReceivedPackets::new(),
+        }
+    }
+
+    /// 获取该空间的密钥
+    ///
+    /// # Returns
+    /// 返回密钥, 尚未获得或已丢弃时返回 None
+    #[inline(always)]
+    pub(crate) const fn get_keys(&self) -> Option<&KeyPair> {
+        self.keys.as_ref()
+    }
+
+    /// 设置该空间的密钥
+    ///
+    /// # Arguments
+    /// `keys` - 密钥
+    pub(crate) fn set_keys(&mut self, keys: KeyPair) {
+        self.keys = Some(keys);
+    }
+
+    /// 获取下一个发送的数据包编号
+    ///
+    /// # Returns
+    /// 返回数据包编号
+    #[inline(always)]
+    pub(crate) const fn get_next_packet_number(&self) -> PacketNumber {
+        self.next_packet_number
+    }
+
+    /// 获取待发送的握手数据
+    ///
+    /// # Returns
+    /// 返回发送缓冲区
+    #[inline(always)]
+    pub(crate) const fn get_crypto_send(&self) -> &SendBuffer {
+        &self.crypto_send
+    }
+
+    /// 获取待发送的握手数据
+    ///
+    /// # Returns
+    /// 返回发送缓冲区
+    #[inline(always)]
+    pub(crate) fn get_crypto_send_mut(&mut self) -> &mut SendBuffer {
+        &mut self.crypto_send
+    }
+
+    /// 获取收到的握手数据
+    ///
+    /// # Returns
+    /// 返回接收缓冲区
+    #[inline(always)]
+    pub(crate) fn get_crypto_recv_mut(&mut self) -> &mut RecvBuffer {
+        &mut self.crypto_recv
+    }
+
+    /// 获取收到的数据包记录
+    ///
+    /// # Returns
+    /// 返回数据包记录
+    #[inline(always)]
+    pub(crate) const fn get_received(&self) -> &ReceivedPackets {
+        &self.received
+    }
+
+    /// 获取收到的数据包记录
+    ///
+    /// # Returns
+    /// 返回数据包记录
+    #[inline(always)]
+    pub(crate) fn get_received_mut(&mut self) -> &mut ReceivedPackets {
+        &mut self.received
+    }
+
+    /// 记录已发送一个数据包, 递增数据包编号
+    pub(crate) fn on_packet_sent(&mut self) {
+        self.next_packet_number += 1;
+    }
+
+    /// 丢弃该空间的密钥与握手数据, 之后不再收发该空间的数据包
+    pub(crate) fn discard(&mut self) {
+        self.keys = None;
+        self.crypto_send = SendBuffer::new();
+        self.crypto_recv = RecvBuffer::new();
+        self.received = ReceivedPackets::new();
+    }
+}
@@ -0,0 +1,63 @@
+use std::net::SocketAddr;
+
+use crate::attr::EcnCodepoint;
+
+/// 待发送的 UDP 数据报
+///
+/// 由 `Connection::poll_transmit` 生成, 调用者负责将其写入套接字.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Transmit {
+    /// 目标地址
+    destination: SocketAddr,
+
+    /// IP 头部中需要设置的 ECN 码点
+    ecn: EcnCodepoint,
+
+    /// 数据报的内容, 可能由多个合并的数据包组成
+    contents: Vec<u8>,
+}
+
+impl Transmit {
+    /// 构造一个待发送的数据报
+    ///
+    /// # Arguments
+    /// `destination` - 目标地址
+    /// `ecn` - ECN 码点
+    /// `contents` - 数据报的内容
+    /// # Returns
+    /// 返回待发送的数据报
+    pub(crate) fn new(destination: SocketAddr, ecn: EcnCodepoint, contents: Vec<u8>) -> Self {
+        Self {
+            destination,
+            ecn,
+            contents,
+        }
+    }
+
+    /// 获取目标地址
+    ///
+    /// # Returns
+    /// 返回目标地址
+    #[inline(always)]
+    pub const fn get_destination(&self) -> SocketAddr {
+        self.destination
+    }
+
+    /// 获取 ECN 码点
+    ///
+    /// # Returns
+    /// 返回 ECN 码点
+    #[inline(always)]
+    pub const fn get_ecn(&self) -> EcnCodepoint {
+        self.ecn
+    }
+
+    /// 获取数据报的内容
+    ///
+    /// # Returns
+    /// 返回数据报的内容
+    #[inline(always)]
+    pub fn get_contents(&self) -> &[u8] {
+        &self.contents
+    }
+}
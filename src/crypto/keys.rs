@@ -0,0 +1,127 @@
+/// 数据包载荷保护
+///
+/// 以 AEAD 算法加密与解密数据包载荷, 包头作为附加认证数据.
+pub trait PacketKey {
+    /// 获取 AEAD 认证标签长度
+    ///
+    /// # Returns
+    /// 返回认证标签长度
+    fn tag_len(&self) -> usize;
+
+    /// 原地加密数据包载荷
+    ///
+    /// # Arguments
+    /// `packet_number` - 完整的数据包编号, 用于生成 nonce
+    /// `header` - 未施加头部保护的包头, 作为附加认证数据
+    /// `payload` - 明文载荷, 末尾预留了 `tag_len` 字节用于写入认证标签
+    fn seal(&self, packet_number: u64, header: &[u8], payload: &mut [u8]);
+
+    /// 原地解密数据包载荷
+    ///
+    /// # Arguments
+    /// `packet_number` - 完整的数据包编号, 用于生成 nonce
+    /// `header` - 已移除头部保护的包头, 作为附加认证数据
+    /// `payload` - 密文与认证标签
+    /// # Returns
+    /// 返回明文长度, 明文位于 `payload` 的前部; 认证失败时返回 None
+    fn open(&self, packet_number: u64, header: &[u8], payload: &mut [u8]) -> Option<usize>;
+}
+
+/// 头部保护
+///
+/// 由载荷的采样生成掩码, 保护包头首字节的低位与 Packet Number 字段.
+pub trait HeaderKey {
+    /// 获取采样长度
+    ///
+    /// # Returns
+    /// 返回采样长度
+    fn sample_len(&self) -> usize;
+
+    /// 由采样生成掩码
+    ///
+    /// # Arguments
+    /// `sample` - 从 Packet Number 起始处之后 4 字节开始的采样
+    /// # Returns
+    /// 返回 5 字节的掩码
+    fn mask(&self, sample: &[u8]) -> [u8; 5];
+}
+
+/// 一个方向上的密钥
+pub struct Keys {
+    /// 载荷保护密钥
+    packet: Box<dyn PacketKey>,
+
+    /// 头部保护密钥
+    header: Box<dyn HeaderKey>,
+}
+
+impl Keys {
+    /// 构造一个方向上的密钥
+    ///
+    /// # Arguments
+    /// `packet` - 载荷保护密钥
+    /// `header` - 头部保护密钥
+    /// # Returns
+    /// 返回密钥
+    pub fn new(packet: Box<dyn PacketKey>, header: Box<dyn HeaderKey>) -> Self {
+        Self { packet, header }
+    }
+
+    /// 获取载荷保护密钥
+    ///
+    /// # Returns
+    /// 返回载荷保护密钥
+    #[inline(always)]
+    pub fn get_packet(&self) -> &dyn PacketKey {
+        self.packet.as_ref()
+    }
+
+    /// 获取头部保护密钥
+    ///
+    /// # Returns
+    /// 返回头部保护密钥
+    #[inline(always)]
+    pub fn get_header(&self) -> &dyn HeaderKey {
+        self.header.as_ref()
+    }
+}
+
+/// 一个加密级别上双向的密钥
+pub struct KeyPair {
+    /// 本端发送数据包使用的密钥
+    local: Keys,
+
+    /// 解密对端数据包使用的密钥
+    remote: Keys,
+}
+
+impl KeyPair {
+    /// 构造一个加密级别上双向的密钥
+    ///
+    /// # Arguments
+    /// `local` - 本端发送数据包使用的密钥
+    /// `remote` - 解密对端数据包使用的密钥
+    /// # Returns
+    /// 返回双向的密钥
+    pub fn new(local: Keys, remote: Keys) -> Self {
+        Self { local, remote }
+    }
+
+    /// 获取本端发送数据包使用的密钥
+    ///
+    /// # Returns
+    /// 返回本端发送数据包使用的密钥
+    #[inline(always)]
+    pub fn get_local(&self) -> &Keys {
+        &self.local
+    }
+
+    /// 获取解密对端数据包使用的密钥
+    ///
+    /// # Returns
+    /// 返回解密对端数据包使用的密钥
+    #[inline(always)]
+    pub fn get_remote(&self) -> &Keys {
+        &self.remote
+    }
+}
@@ -0,0 +1,7 @@
+mod keys;
+mod random;
+mod session;
+
+pub use keys::{HeaderKey, KeyPair, Keys, PacketKey};
+pub use random::Random;
+pub use session::{EncryptionLevel, Session};
@@ -0,0 +1,11 @@
+/// 密码学安全的随机数生成器
+///
+/// 由加密库提供, 用于生成 Connection ID 与 PATH_CHALLENGE 数据等需要对观察者
+/// 不可预测的值 (RFC 9000 §5.1, §8.2.1).
+pub trait Random {
+    /// 以随机字节填充缓冲区
+    ///
+    /// # Arguments
+    /// `buf` - 待填充的缓冲区
+    fn fill(&mut self, buf: &mut [u8]);
+}
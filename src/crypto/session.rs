@@ -0,0 +1,101 @@
+use crate::attr::{PacketNumberSpace, Side, TransportParameters};
+
+use super::{keys::KeyPair, random::Random};
+
+/// 加密级别
+///
+/// 握手数据与数据包保护所使用的密钥按加密级别区分, 每个级别对应一个数据包编号空间.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EncryptionLevel {
+    /// Initial 数据包, 密钥由客户端选择的目标 Connection ID 派生
+    Initial,
+
+    /// Handshake 数据包
+    Handshake,
+
+    /// 1-RTT 数据包
+    OneRtt,
+}
+
+impl EncryptionLevel {
+    /// 由数据包编号空间得到加密级别, 0-RTT 与 1-RTT 同属应用数据空间
+    ///
+    /// # Arguments
+    /// `space` - 数据包编号空间
+    /// # Returns
+    /// 返回加密级别
+    pub(crate) const fn from_space(space: PacketNumberSpace) -> Self {
+        match space {
+            PacketNumberSpace::Initial => EncryptionLevel::Initial,
+            PacketNumberSpace::Handshake => EncryptionLevel::Handshake,
+            PacketNumberSpace::ApplicationData => EncryptionLevel::OneRtt,
+        }
+    }
+
+    /// 获取加密级别对应的数据包编号空间
+    ///
+    /// # Returns
+    /// 返回数据包编号空间
+    pub(crate) const fn get_space(&self) -> PacketNumberSpace {
+        match self {
+            EncryptionLevel::Initial => PacketNumberSpace::Initial,
+            EncryptionLevel::Handshake => PacketNumberSpace::Handshake,
+            EncryptionLevel::OneRtt => PacketNumberSpace::ApplicationData,
+        }
+    }
+}
+
+/// 加密握手会话
+///
+/// 由 TLS 1.3 等握手协议实现, 连接通过该特征驱动握手: 将收到的 CRYPTO 数据交给会话,
+/// 取出会话产生的握手数据与密钥. 本端的传输参数由实现者以 `TransportParameters::encode`
+/// 编码进握手消息中, 对端的传输参数以 `TransportParameters::decode` 解码.
+pub trait Session {
+    /// 由客户端选择的目标 Connection ID 派生 Initial 密钥
+    ///
+    /// # Arguments
+    /// `dst_cid` - 客户端首个 Initial 数据包的目标 Connection ID
+    /// `side` - 本端角色
+    /// # Returns
+    /// 返回 Initial 级别的密钥
+    fn initial_keys(&self, dst_cid: &[u8], side: Side) -> KeyPair;
+
+    /// 处理对端在某个加密级别上发送的握手数据
+    ///
+    /// # Arguments
+    /// `level` - 握手数据所在的加密级别
+    /// `data` - 按序到达的握手数据
+    /// # Returns
+    /// 握手失败时返回 TLS alert
+    fn read_handshake(&mut self, level: EncryptionLevel, data: &[u8]) -> Result<(), u8>;
+
+    /// 取出待发送的握手数据
+    ///
+    /// # Returns
+    /// 返回握手数据及其加密级别; 没有待发送的数据时返回 None
+    fn poll_handshake(&mut self) -> Option<(EncryptionLevel, Vec<u8>)>;
+
+    /// 取出新可用的密钥
+    ///
+    /// # Returns
+    /// 返回新的密钥及其加密级别; 没有新的密钥时返回 None
+    fn poll_keys(&mut self) -> Option<(EncryptionLevel, KeyPair)>;
+
+    /// 判断握手是否仍在进行
+    ///
+    /// # Returns
+    /// 握手尚未完成时返回 true
+    fn is_handshaking(&self) -> bool;
+
+    /// 获取对端通告的传输参数
+    ///
+    /// # Returns
+    /// 返回对端的传输参数; 尚未收到时返回 None
+    fn get_peer_transport_parameters(&self) -> Option<TransportParameters>;
+
+    /// 获取加密库的密码学安全随机数生成器
+    ///
+    /// # Returns
+    /// 返回随机数生成器, 连接以其生成 Connection ID 与 PATH_CHALLENGE 数据
+    fn random(&self) -> Box<dyn Random>;
+}
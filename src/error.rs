@@ -0,0 +1,367 @@
+use std::fmt;
+
+/// 传输层错误码
+///
+/// RFC 9000 §20.1 中定义的错误码, 与 CONNECTION_CLOSE (0x1c) 帧中的 Error Code 字段相互转换.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TransportErrorCode {
+    /// 没有错误, 正常关闭连接
+    NoError,
+
+    /// 终端遇到了内部错误
+    InternalError,
+
+    /// 服务端拒绝接受新的连接
+    ConnectionRefused,
+
+    /// 收到的数据超过了通告的流量控制限制
+    FlowControlError,
+
+    /// 收到的流标识超过了通告的流数量限制
+    StreamLimitError,
+
+    /// 收到的帧与流的状态不符
+    StreamStateError,
+
+    /// 流的最终大小错误
+    FinalSizeError,
+
+    /// 帧的格式错误
+    FrameEncodingError,
+
+    /// 传输参数错误
+    TransportParameterError,
+
+    /// 对端提供的 Connection ID 超过了通告的 active_connection_id_limit
+    ConnectionIdLimitError,
+
+    /// 不符合协议的行为
+    ProtocolViolation,
+
+    /// 收到的 Retry 或 NEW_TOKEN 中的 Token 无效
+    InvalidToken,
+
+    /// 应用层错误, 用于在 Initial 或 Handshake 数据包中代替应用层的关闭
+    ApplicationError,
+
+    /// 收到的 CRYPTO 数据超过了可以缓存的数量
+    CryptoBufferExceeded,
+
+    /// 密钥更新错误
+    KeyUpdateError,
+
+    /// 达到了 AEAD 的使用限制
+    AeadLimitReached,
+
+    /// 没有可用的网络路径
+    NoViablePath,
+
+    /// 加密握手失败, 携带 TLS alert, 对应 0x0100..=0x01ff
+    Crypto(u8),
+
+    /// 未定义的错误码
+    Unknown(u64),
+}
+
+impl From<u64> for TransportErrorCode {
+    fn from(code: u64) -> Self {
+        match code {
+            0x00 => Self::NoError,
+            0x01 => Self::InternalError,
+            0x02 => Self::ConnectionRefused,
+            0x03 => Self::FlowControlError,
+            0x04 => Self::StreamLimitError,
+            0x05 => Self::StreamStateError,
+            0x06 => Self::FinalSizeError,
+            0x07 => Self::FrameEncodingError,
+            0x08 => Self::TransportParameterError,
+            0x09 => Self::ConnectionIdLimitError,
+            0x0a => Self::ProtocolViolation,
+            0x0b => Self::InvalidToken,
+            0x0c => Self::ApplicationError,
+            0x0d => Self::CryptoBufferExceeded,
+            0x0e => Self::KeyUpdateError,
+            0x0f => Self::AeadLimitReached,
+            0x10 => Self::NoViablePath,
+            0x0100..=0x01ff => Self::Crypto((code - 0x0100) as u8),
+            _ => Self::Unknown(code),
+        }
+    }
+}
+
+impl From<TransportErrorCode> for u64 {
+    fn from(code: TransportErrorCode) -> Self {
+        match code {
+            TransportErrorCode::NoError => 0x00,
+            TransportErrorCode::InternalError => 0x01,
+            TransportErrorCode::ConnectionRefused => 0x02,
+            TransportErrorCode::FlowControlError => 0x03,
+            TransportErrorCode::StreamLimitError => 0x04,
+            TransportErrorCode::StreamStateError => 0x05,
+            TransportErrorCode::FinalSizeError => 0x06,
+            TransportErrorCode::FrameEncodingError => 0x07,
+            TransportErrorCode::TransportParameterError => 0x08,
+            TransportErrorCode::ConnectionIdLimitError => 0x09,
+            TransportErrorCode::ProtocolViolation => 0x0a,
+            TransportErrorCode::InvalidToken => 0x0b,
+            TransportErrorCode::ApplicationError => 0x0c,
+            TransportErrorCode::CryptoBufferExceeded => 0x0d,
+            TransportErrorCode::KeyUpdateError => 0x0e,
+            TransportErrorCode::AeadLimitReached => 0x0f,
+            TransportErrorCode::NoViablePath => 0x10,
+            TransportErrorCode::Crypto(alert) => 0x0100 + alert as u64,
+            TransportErrorCode::Unknown(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for TransportErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Crypto(alert) => write!(f, "CRYPTO_ERROR (alert {})", alert),
+            Self::Unknown(code) => write!(f, "unknown error 0x{:x}", code),
+            code => write!(f, "{:?} (0x{:x})", code, u64::from(*code)),
+        }
+    }
+}
+
+/// 传输层错误
+///
+/// 携带 RFC 9000 §20.1 中定义的错误码, 用于以 CONNECTION_CLOSE (0x1c) 帧关闭连接.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TransportError {
+    /// 错误码
+    code: TransportErrorCode,
+
+    /// 触发错误的帧类型, 未知时为 0
+    frame_type: u64,
+
+    /// 错误原因
+    reason: &'static str,
+}
+
+impl TransportError {
+    /// 构造一个传输层错误
+    ///
+    /// # Arguments
+    /// `code` - 错误码
+    /// `frame_type` - 触发错误的帧类型
+    /// `reason` - 错误原因
+    ///
+    /// # Returns
+    /// 返回传输层错误
+    pub(crate) const fn new(
+        code: TransportErrorCode,
+        frame_type: u64,
+        reason: &'static str,
+    ) -> Self {
+        Self {
+            code,
+            frame_type,
+            reason,
+        }
+    }
+
+    /// 获取错误码
+    ///
+    /// # Returns
+    /// 返回传输层错误码
+    #[inline(always)]
+    pub const fn get_code(&self) -> TransportErrorCode {
+        self.code
+    }
+
+    /// 获取触发错误的帧类型
+    ///
+    /// # Returns
+    /// 返回触发错误的帧类型
+    #[inline(always)]
+    pub const fn get_frame_type(&self) -> u64 {
+        self.frame_type
+    }
+
+    /// 获取错误原因
+    ///
+    /// # Returns
+    /// 返回错误原因
+    #[inline(always)]
+    pub const fn get_reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transport error {} (frame 0x{:x}): {}",
+            self.code, self.frame_type, self.reason
+        )
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+/// 连接错误
+///
+/// 连接关闭的原因, 区分传输层关闭 (CONNECTION_CLOSE 0x1c)、应用层关闭 (0x1d)
+/// 与不发送 CONNECTION_CLOSE 帧的空闲超时.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ConnectionError {
+    /// 传输层错误
+    Transport {
+        /// 错误码
+        code: TransportErrorCode,
+        /// 触发错误的帧类型, 未知时为 0
+        frame_type: u64,
+        /// 错误原因
+        reason: String,
+    },
+
+    /// 应用层错误
+    Application {
+        /// 应用错误码
+        error_code: u64,
+        /// 错误原因
+        reason: String,
+    },
+
+    /// 空闲超时, 连接被静默关闭 (RFC 9000 §10.1)
+    TimedOut,
+}
+
+impl ConnectionError {
+    /// 构造一个应用层错误
+    ///
+    /// # Arguments
+    /// `error_code` - 应用错误码
+    /// `reason` - 错误原因
+    ///
+    /// # Returns
+    /// 返回应用层错误
+    pub fn application(error_code: u64, reason: &str) -> Self {
+        Self::Application {
+            error_code,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// 判断是否为应用层错误
+    ///
+    /// # Returns
+    /// 返回是否为应用层错误
+    #[inline(always)]
+    pub const fn is_application(&self) -> bool {
+        matches!(self, Self::Application { .. })
+    }
+
+    /// 获取错误原因
+    ///
+    /// # Returns
+    /// 返回错误原因, 空闲超时时为空
+    pub fn get_reason(&self) -> &str {
+        match self {
+            Self::Transport { reason, .. } | Self::Application { reason, .. } => reason,
+            Self::TimedOut => "",
+        }
+    }
+}
+
+impl From<TransportError> for ConnectionError {
+    fn from(error: TransportError) -> Self {
+        Self::Transport {
+            code: error.get_code(),
+            frame_type: error.get_frame_type(),
+            reason: error.get_reason().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport {
+                code,
+                frame_type,
+                reason,
+            } => write!(f, "{} (frame 0x{:x}): {}", code, frame_type, reason),
+            Self::Application { error_code, reason } => {
+                write!(f, "application error 0x{:x}: {}", error_code, reason)
+            }
+            Self::TimedOut => write!(f, "idle timeout"),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {}
+
+/// 流操作错误
+///
+/// 应用层读写流时返回, 与关闭连接的传输层错误不同, 仅影响单个流.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StreamError {
+    /// 对端以 RESET_STREAM 重置了流, 携带应用错误码
+    Reset(u64),
+
+    /// 对端以 STOP_SENDING 要求停止发送, 携带应用错误码
+    Stopped(u64),
+
+    /// 流已结束或已被本端重置, 不能再写入
+    Closed,
+
+    /// 流不存在或不支持该操作
+    UnknownStream,
+
+    /// 对端不支持该操作, 例如未协商 RESET_STREAM_AT 扩展
+    Unsupported,
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reset(code) => write!(f, "stream reset by peer (0x{:x})", code),
+            Self::Stopped(code) => write!(f, "stream stopped by peer (0x{:x})", code),
+            Self::Closed => write!(f, "stream closed"),
+            Self::UnknownStream => write!(f, "unknown stream"),
+            Self::Unsupported => write!(f, "operation not supported by peer"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// Connection ID 配置错误
+///
+/// 构造连接或 Connection ID 生成器、替换 Connection ID 生成器时返回.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConnectionIdError {
+    /// Connection ID 的长度超出允许的范围, 携带该长度
+    InvalidLength(usize),
+
+    /// 生成器产生的 Connection ID 长度与本端的 Connection ID 不一致
+    LengthMismatch {
+        /// 本端 Connection ID 的长度
+        expected: usize,
+        /// 生成器产生的 Connection ID 的长度
+        actual: usize,
+    },
+
+    /// 握手已经完成, 不能再替换 Connection ID 生成器
+    InvalidState,
+}
+
+impl fmt::Display for ConnectionIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => write!(f, "invalid connection id length {}", len),
+            Self::LengthMismatch { expected, actual } => write!(
+                f,
+                "connection id length mismatch (expected {}, got {})",
+                expected, actual
+            ),
+            Self::InvalidState => write!(f, "handshake already completed"),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionIdError {}
@@ -0,0 +1,51 @@
+use super::error::{TransportError, TransportErrorCode};
+
+#[test]
+fn test_transport_error_code_round_trip() {
+    for code in 0x00..=0x10u64 {
+        let error_code = TransportErrorCode::from(code);
+        assert!(!matches!(error_code, TransportErrorCode::Unknown(_)));
+        assert_eq!(u64::from(error_code), code);
+    }
+    assert_eq!(
+        TransportErrorCode::from(0x0a),
+        TransportErrorCode::ProtocolViolation
+    );
+    assert_eq!(u64::from(TransportErrorCode::NoViablePath), 0x10);
+}
+
+#[test]
+fn test_transport_error_code_crypto_range() {
+    // 0x0100..=0x01ff 携带 TLS alert
+    for code in 0x0100..=0x01ffu64 {
+        let error_code = TransportErrorCode::from(code);
+        assert_eq!(
+            error_code,
+            TransportErrorCode::Crypto((code - 0x0100) as u8)
+        );
+        assert_eq!(u64::from(error_code), code);
+    }
+    assert_eq!(u64::from(TransportErrorCode::Crypto(0x28)), 0x0128);
+
+    for code in [0x11, 0xff, 0x0200, (1 << 62) - 1] {
+        assert_eq!(
+            TransportErrorCode::from(code),
+            TransportErrorCode::Unknown(code)
+        );
+        assert_eq!(u64::from(TransportErrorCode::from(code)), code);
+    }
+}
+
+#[test]
+fn test_transport_error_display() {
+    let error = TransportError::new(TransportErrorCode::FlowControlError, 0x08, "limit exceeded");
+    assert_eq!(error.get_code(), TransportErrorCode::FlowControlError);
+    assert_eq!(
+        error.to_string(),
+        "transport error FlowControlError (0x3) (frame 0x8): limit exceeded"
+    );
+    assert_eq!(
+        TransportErrorCode::Crypto(0x28).to_string(),
+        "CRYPTO_ERROR (alert 40)"
+    );
+}
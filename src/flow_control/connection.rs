@@ -0,0 +1,176 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    error::{TransportError, TransportErrorCode},
+    frame::{DataBlockedFrame, FrameType, MaxDataFrame},
+};
+
+use super::window::{RecvWindow, SendWindow};
+
+/// 连接级流量控制
+///
+/// 所有流的数据共享连接级的信用. 发送方向由对端的 MAX_DATA 限制,
+/// 受限时发送 DATA_BLOCKED; 接收方向在应用层读取后发送 MAX_DATA.
+pub(crate) struct ConnectionFlowControl {
+    /// 发送方向的信用
+    send: SendWindow,
+
+    /// 接收方向的窗口
+    recv: RecvWindow,
+}
+
+impl ConnectionFlowControl {
+    /// 构造一个连接级流量控制
+    ///
+    /// # Arguments
+    /// `initial_max_data` - 本端通告的 initial_max_data
+    /// `max_window` - 接收窗口自动调整的上限
+    ///
+    /// # Returns
+    /// 返回连接级流量控制
+    pub(crate) fn new(initial_max_data: usize, max_window: usize) -> Self {
+        Self {
+            send: SendWindow::new(0),
+            recv: RecvWindow::new(initial_max_data, max_window),
+        }
+    }
+
+    /// 获取发送方向的信用
+    ///
+    /// # Returns
+    /// 返回发送方向的信用
+    #[inline(always)]
+    pub(crate) const fn get_send(&self) -> &SendWindow {
+        &self.send
+    }
+
+    /// 获取接收方向的窗口
+    ///
+    /// # Returns
+    /// 返回接收方向的窗口
+    #[inline(always)]
+    pub(crate) const fn get_recv(&self) -> &RecvWindow {
+        &self.recv
+    }
+
+    /// 获取当前可发送的数据量
+    ///
+    /// # Returns
+    /// 返回可发送的数据量
+    #[inline(always)]
+    pub(crate) const fn get_available(&self) -> usize {
+        self.send.get_available()
+    }
+
+    /// 设置对端通告的 initial_max_data
+    ///
+    /// # Arguments
+    /// `max_data` - 对端的 initial_max_data
+    pub(crate) fn set_peer_initial_max_data(&mut self, max_data: usize) {
+        self.send.update(max_data);
+    }
+
+    /// 记录新发送的流数据 (不包含重传)
+    ///
+    /// # Arguments
+    /// `size` - 新发送的数据量
+    pub(crate) fn on_data_sent(&mut self, size: usize) {
+        self.send.consume(size);
+    }
+
+    /// 处理对端的 MAX_DATA 帧
+    ///
+    /// # Arguments
+    /// `frame` - MAX_DATA 帧
+    pub(crate) fn on_max_data_frame(&mut self, frame: &MaxDataFrame) {
+        self.send.update(frame.get_maximum_data());
+    }
+
+    /// 生成 DATA_BLOCKED 帧
+    ///
+    /// # Returns
+    /// 发送受连接级流量控制限制时返回 DATA_BLOCKED 帧
+    pub(crate) fn poll_data_blocked_frame(&mut self) -> Option<DataBlockedFrame> {
+        self.send.poll_blocked().map(|max_data| {
+            let mut frame = DataBlockedFrame::new();
+            frame.set_maximum_data(max_data);
+            frame
+        })
+    }
+
+    /// DATA_BLOCKED 帧丢失
+    ///
+    /// # Arguments
+    /// `max_data` - 丢失帧中的限制
+    pub(crate) fn on_data_blocked_lost(&mut self, max_data: usize) {
+        self.send.on_blocked_lost(max_data);
+    }
+
+    /// 记录流上新接收的数据
+    ///
+    /// # Arguments
+    /// `size` - 流的最大偏移量增加的字节数
+    /// `frame_type` - 携带数据的帧类型
+    ///
+    /// # Returns
+    /// 超过本端通告的限制时返回 FLOW_CONTROL_ERROR
+    pub(crate) fn on_data_received(
+        &mut self,
+        size: usize,
+        frame_type: FrameType,
+    ) -> Result<(), TransportError> {
+        if self.recv.on_received(size) {
+            Ok(())
+        } else {
+            Err(TransportError::new(
+                TransportErrorCode::FlowControlError,
+                u8::from(frame_type) as u64,
+                "connection flow control limit exceeded",
+            ))
+        }
+    }
+
+    /// 记录应用层读取的数据
+    ///
+    /// # Arguments
+    /// `size` - 读取的数据量
+    pub(crate) fn on_data_consumed(&mut self, size: usize) {
+        self.recv.on_consumed(size);
+    }
+
+    /// 生成 MAX_DATA 帧
+    ///
+    /// # Arguments
+    /// `now` - 当前时间
+    /// `smoothed_rtt` - 平滑 RTT, 用于接收窗口自动调整
+    ///
+    /// # Returns
+    /// 需要扩大限制时返回 MAX_DATA 帧
+    pub(crate) fn poll_max_data_frame(
+        &mut self,
+        now: Instant,
+        smoothed_rtt: Duration,
+    ) -> Option<MaxDataFrame> {
+        self.recv
+            .poll_update(now, smoothed_rtt)
+            .map(Self::max_data_frame)
+    }
+
+    /// MAX_DATA 帧丢失
+    ///
+    /// # Arguments
+    /// `max_data` - 丢失帧中的限制
+    ///
+    /// # Returns
+    /// 丢失的限制仍是最新值时返回需要重传的 MAX_DATA 帧
+    pub(crate) fn on_max_data_lost(&self, max_data: usize) -> Option<MaxDataFrame> {
+        self.recv.on_update_lost(max_data).map(Self::max_data_frame)
+    }
+
+    /// 构造 MAX_DATA 帧
+    fn max_data_frame(max_data: usize) -> MaxDataFrame {
+        let mut frame = MaxDataFrame::new();
+        frame.set_maximum_data(max_data);
+        frame
+    }
+}
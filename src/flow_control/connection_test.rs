@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    error::TransportErrorCode,
+    frame::{FrameType, MaxDataFrame},
+};
+
+use super::ConnectionFlowControl;
+
+const STREAM: FrameType = FrameType::Stream {
+    off_flag: true,
+    len_flag: true,
+    fin_flag: false,
+};
+
+#[test]
+fn test_connection_send_credit() {
+    let mut fc = ConnectionFlowControl::new(1000, 4000);
+    fc.set_peer_initial_max_data(100);
+
+    fc.on_data_sent(60);
+    assert_eq!(fc.get_available(), 40);
+    assert!(fc.poll_data_blocked_frame().is_none());
+
+    fc.on_data_sent(40);
+    let blocked = fc.poll_data_blocked_frame().unwrap();
+    assert_eq!(blocked.get_maximum_data(), 100);
+    assert!(fc.poll_data_blocked_frame().is_none());
+    fc.on_data_blocked_lost(100);
+    assert!(fc.poll_data_blocked_frame().is_some());
+
+    // 限制只增不减
+    let mut max_data = MaxDataFrame::new();
+    max_data.set_maximum_data(300);
+    fc.on_max_data_frame(&max_data);
+    max_data.set_maximum_data(200);
+    fc.on_max_data_frame(&max_data);
+    assert_eq!(fc.get_available(), 200);
+}
+
+#[test]
+fn test_connection_recv_window() {
+    let start = Instant::now();
+    let rtt = Duration::from_millis(100);
+    let mut fc = ConnectionFlowControl::new(1000, 4000);
+
+    fc.on_data_received(400, STREAM).unwrap();
+    fc.on_data_consumed(400);
+    assert!(fc.poll_max_data_frame(start, rtt).is_none());
+
+    fc.on_data_received(200, STREAM).unwrap();
+    fc.on_data_consumed(200);
+    let frame = fc.poll_max_data_frame(start, rtt).unwrap();
+    assert_eq!(frame.get_maximum_data(), 1600);
+    assert_eq!(fc.get_recv().get_window(), 1000);
+
+    // 短于 2 个 RTT 内再次扩大限制, 窗口翻倍
+    fc.on_data_received(600, STREAM).unwrap();
+    fc.on_data_consumed(600);
+    let frame = fc
+        .poll_max_data_frame(start + Duration::from_millis(50), rtt)
+        .unwrap();
+    assert_eq!(fc.get_recv().get_window(), 2000);
+    assert_eq!(frame.get_maximum_data(), 3200);
+    assert_eq!(fc.on_max_data_lost(3200).unwrap().get_maximum_data(), 3200);
+    assert!(fc.on_max_data_lost(1600).is_none());
+
+    let err = fc.on_data_received(2001, STREAM).unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::FlowControlError);
+}
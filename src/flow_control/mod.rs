@@ -0,0 +1,12 @@
+mod connection;
+mod stream;
+mod window;
+
+pub(crate) use connection::*;
+pub(crate) use stream::*;
+pub(crate) use window::*;
+
+#[cfg(test)]
+mod connection_test;
+#[cfg(test)]
+mod stream_test;
@@ -0,0 +1,214 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    attr::{Side, StreamDirection, StreamIDSetter, StreamId, TransportParameters},
+    error::{TransportError, TransportErrorCode},
+    frame::{FrameType, MaxStreamDataFrame, StreamDataBlockedFrame},
+};
+
+use super::window::{RecvWindow, SendWindow};
+
+/// 流级流量控制
+///
+/// 发送方向由对端的 MAX_STREAM_DATA 限制, 受限时发送 STREAM_DATA_BLOCKED;
+/// 接收方向在应用层读取后发送 MAX_STREAM_DATA, 并自动扩大窗口.
+pub(crate) struct StreamFlowControl {
+    /// 流标识
+    stream_id: StreamId,
+
+    /// 发送方向的信用
+    send: SendWindow,
+
+    /// 接收方向的窗口
+    recv: RecvWindow,
+}
+
+impl StreamFlowControl {
+    /// 构造一个流级流量控制, 初始限制按流的发起方与方向从双方的传输参数中选取
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `local` - 本端角色
+    /// `local_params` - 本端通告的传输参数
+    /// `peer_params` - 对端通告的传输参数
+    /// `max_window` - 接收窗口自动调整的上限
+    ///
+    /// # Returns
+    /// 返回流级流量控制
+    pub(crate) fn new(
+        stream_id: StreamId,
+        local: Side,
+        local_params: &TransportParameters,
+        peer_params: &TransportParameters,
+        max_window: usize,
+    ) -> Self {
+        let (send_limit, recv_limit) = match (stream_id.get_direction(), stream_id.is_local(local))
+        {
+            (StreamDirection::Bidi, true) => (
+                peer_params.get_initial_max_stream_data_bidi_remote(),
+                local_params.get_initial_max_stream_data_bidi_local(),
+            ),
+            (StreamDirection::Bidi, false) => (
+                peer_params.get_initial_max_stream_data_bidi_local(),
+                local_params.get_initial_max_stream_data_bidi_remote(),
+            ),
+            (StreamDirection::Uni, true) => (peer_params.get_initial_max_stream_data_uni(), 0),
+            (StreamDirection::Uni, false) => (0, local_params.get_initial_max_stream_data_uni()),
+        };
+
+        Self {
+            stream_id,
+            send: SendWindow::new(send_limit),
+            recv: RecvWindow::new(recv_limit, max_window),
+        }
+    }
+
+    /// 获取流标识
+    ///
+    /// # Returns
+    /// 返回流标识
+    #[inline(always)]
+    pub(crate) const fn get_stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// 获取发送方向的信用
+    ///
+    /// # Returns
+    /// 返回发送方向的信用
+    #[inline(always)]
+    pub(crate) const fn get_send(&self) -> &SendWindow {
+        &self.send
+    }
+
+    /// 获取接收方向的窗口
+    ///
+    /// # Returns
+    /// 返回接收方向的窗口
+    #[inline(always)]
+    pub(crate) const fn get_recv(&self) -> &RecvWindow {
+        &self.recv
+    }
+
+    /// 获取对端允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 返回允许发送的最大偏移量
+    #[inline(always)]
+    pub(crate) const fn get_max_send_offset(&self) -> usize {
+        self.send.get_max_data()
+    }
+
+    /// 记录新发送的流数据 (不包含重传)
+    ///
+    /// # Arguments
+    /// `size` - 新发送的数据量
+    pub(crate) fn on_data_sent(&mut self, size: usize) {
+        self.send.consume(size);
+    }
+
+    /// 处理对端的 MAX_STREAM_DATA 帧
+    ///
+    /// # Arguments
+    /// `frame` - MAX_STREAM_DATA 帧
+    ///
+    /// # Returns
+    /// 返回限制是否增加
+    pub(crate) fn on_max_stream_data_frame(&mut self, frame: &MaxStreamDataFrame) -> bool {
+        self.send.update(frame.get_maximum_data())
+    }
+
+    /// 生成 STREAM_DATA_BLOCKED 帧
+    ///
+    /// # Returns
+    /// 发送受流级流量控制限制时返回 STREAM_DATA_BLOCKED 帧
+    pub(crate) fn poll_stream_data_blocked_frame(&mut self) -> Option<StreamDataBlockedFrame> {
+        self.send.poll_blocked().map(|max_data| {
+            let mut frame = StreamDataBlockedFrame::new();
+            frame.set_stream_id(self.stream_id);
+            frame.set_maximum_data(max_data);
+            frame
+        })
+    }
+
+    /// STREAM_DATA_BLOCKED 帧丢失
+    ///
+    /// # Arguments
+    /// `max_data` - 丢失帧中的限制
+    pub(crate) fn on_stream_data_blocked_lost(&mut self, max_data: usize) {
+        self.send.on_blocked_lost(max_data);
+    }
+
+    /// 记录流上接收数据的最大偏移量
+    ///
+    /// # Arguments
+    /// `max_offset` - 流上已接收数据的最大偏移量
+    /// `frame_type` - 携带数据的帧类型
+    ///
+    /// # Returns
+    /// 返回最大偏移量增加的字节数, 用于连接级流量控制;
+    /// 超过本端通告的限制时返回 FLOW_CONTROL_ERROR
+    pub(crate) fn on_data_received(
+        &mut self,
+        max_offset: usize,
+        frame_type: FrameType,
+    ) -> Result<usize, TransportError> {
+        let size = max_offset.saturating_sub(self.recv.get_received());
+        if self.recv.on_received(size) {
+            Ok(size)
+        } else {
+            Err(TransportError::new(
+                TransportErrorCode::FlowControlError,
+                u8::from(frame_type) as u64,
+                "stream flow control limit exceeded",
+            ))
+        }
+    }
+
+    /// 记录应用层读取的数据
+    ///
+    /// # Arguments
+    /// `size` - 读取的数据量
+    pub(crate) fn on_data_consumed(&mut self, size: usize) {
+        self.recv.on_consumed(size);
+    }
+
+    /// 生成 MAX_STREAM_DATA 帧
+    ///
+    /// # Arguments
+    /// `now` - 当前时间
+    /// `smoothed_rtt` - 平滑 RTT, 用于接收窗口自动调整
+    ///
+    /// # Returns
+    /// 需要扩大限制时返回 MAX_STREAM_DATA 帧
+    pub(crate) fn poll_max_stream_data_frame(
+        &mut self,
+        now: Instant,
+        smoothed_rtt: Duration,
+    ) -> Option<MaxStreamDataFrame> {
+        self.recv
+            .poll_update(now, smoothed_rtt)
+            .map(|max_data| self.max_stream_data_frame(max_data))
+    }
+
+    /// MAX_STREAM_DATA 帧丢失
+    ///
+    /// # Arguments
+    /// `max_data` - 丢失帧中的限制
+    ///
+    /// # Returns
+    /// 丢失的限制仍是最新值时返回需要重传的 MAX_STREAM_DATA 帧
+    pub(crate) fn on_max_stream_data_lost(&self, max_data: usize) -> Option<MaxStreamDataFrame> {
+        self.recv
+            .on_update_lost(max_data)
+            .map(|max_data| self.max_stream_data_frame(max_data))
+    }
+
+    /// 构造 MAX_STREAM_DATA 帧
+    fn max_stream_data_frame(&self, max_data: usize) -> MaxStreamDataFrame {
+        let mut frame = MaxStreamDataFrame::new();
+        frame.set_stream_id(self.stream_id);
+        frame.set_maximum_data(max_data);
+        frame
+    }
+}
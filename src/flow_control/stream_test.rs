@@ -0,0 +1,73 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    attr::{Side, StreamDirection, StreamIDGetter, StreamIDSetter, StreamId, TransportParameters},
+    error::TransportErrorCode,
+    frame::{FrameType, MaxStreamDataFrame},
+};
+
+use super::StreamFlowControl;
+
+const STREAM: FrameType = FrameType::Stream {
+    off_flag: true,
+    len_flag: true,
+    fin_flag: false,
+};
+
+fn params(bidi_local: usize, bidi_remote: usize, uni: usize) -> TransportParameters {
+    let mut params = TransportParameters::new();
+    params.set_initial_max_stream_data_bidi_local(bidi_local);
+    params.set_initial_max_stream_data_bidi_remote(bidi_remote);
+    params.set_initial_max_stream_data_uni(uni);
+    params
+}
+
+#[test]
+fn test_stream_initial_limits() {
+    let local = params(10, 20, 30);
+    let peer = params(100, 200, 300);
+
+    let cases = [
+        (Side::Client, StreamDirection::Bidi, 200, 10),
+        (Side::Server, StreamDirection::Bidi, 100, 20),
+        (Side::Client, StreamDirection::Uni, 300, 0),
+        (Side::Server, StreamDirection::Uni, 0, 30),
+    ];
+    for (initiator, direction, send, recv) in cases {
+        let id = StreamId::nth(initiator, direction, 0);
+        let fc = StreamFlowControl::new(id, Side::Client, &local, &peer, 1000);
+        assert_eq!(fc.get_max_send_offset(), send);
+        assert_eq!(fc.get_recv().get_max_data(), recv);
+    }
+}
+
+#[test]
+fn test_stream_credit() {
+    let start = Instant::now();
+    let rtt = Duration::from_millis(100);
+    let id = StreamId::nth(Side::Client, StreamDirection::Bidi, 1);
+    let mut fc =
+        StreamFlowControl::new(id, Side::Client, &params(100, 0, 0), &params(0, 50, 0), 400);
+
+    fc.on_data_sent(50);
+    let blocked = fc.poll_stream_data_blocked_frame().unwrap();
+    assert_eq!(blocked.get_stream_id(), id);
+    assert_eq!(blocked.get_maximum_data(), 50);
+
+    let mut max = MaxStreamDataFrame::new();
+    max.set_stream_id(id);
+    max.set_maximum_data(80);
+    assert!(fc.on_max_stream_data_frame(&max));
+    assert!(fc.poll_stream_data_blocked_frame().is_none());
+
+    // 重复接收的数据不计入
+    assert_eq!(fc.on_data_received(60, STREAM).unwrap(), 60);
+    assert_eq!(fc.on_data_received(40, STREAM).unwrap(), 0);
+    fc.on_data_consumed(60);
+    let update = fc.poll_max_stream_data_frame(start, rtt).unwrap();
+    assert_eq!(update.get_stream_id(), id);
+    assert_eq!(update.get_maximum_data(), 160);
+
+    let err = fc.on_data_received(161, STREAM).unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::FlowControlError);
+}
@@ -0,0 +1,251 @@
+use std::time::{Duration, Instant};
+
+/// 发送方向的流量控制信用
+///
+/// 记录对端通告的限制与已消耗的信用, 并判断何时需要发送 *_BLOCKED 帧.
+pub(crate) struct SendWindow {
+    /// 对端允许发送的最大数据量
+    max_data: usize,
+
+    /// 已消耗的信用
+    used: usize,
+
+    /// 最近一次发送 *_BLOCKED 帧时的限制
+    blocked_at: Option<usize>,
+}
+
+impl SendWindow {
+    /// 构造一个发送方向的流量控制信用
+    ///
+    /// # Arguments
+    /// `max_data` - 对端通告的初始限制
+    ///
+    /// # Returns
+    /// 返回发送方向的流量控制信用
+    pub(crate) fn new(max_data: usize) -> Self {
+        Self {
+            max_data,
+            used: 0,
+            blocked_at: None,
+        }
+    }
+
+    /// 获取对端允许发送的最大数据量
+    ///
+    /// # Returns
+    /// 返回对端允许发送的最大数据量
+    #[inline(always)]
+    pub(crate) const fn get_max_data(&self) -> usize {
+        self.max_data
+    }
+
+    /// 获取已消耗的信用
+    ///
+    /// # Returns
+    /// 返回已消耗的信用
+    #[inline(always)]
+    pub(crate) const fn get_used(&self) -> usize {
+        self.used
+    }
+
+    /// 获取剩余可用的信用
+    ///
+    /// # Returns
+    /// 返回剩余可用的信用
+    #[inline(always)]
+    pub(crate) const fn get_available(&self) -> usize {
+        self.max_data.saturating_sub(self.used)
+    }
+
+    /// 消耗信用
+    ///
+    /// # Arguments
+    /// `size` - 新发送的数据量
+    pub(crate) fn consume(&mut self, size: usize) {
+        self.used += size;
+    }
+
+    /// 更新对端通告的限制, 限制只增不减
+    ///
+    /// # Arguments
+    /// `max_data` - 对端通告的限制
+    ///
+    /// # Returns
+    /// 返回限制是否增加
+    pub(crate) fn update(&mut self, max_data: usize) -> bool {
+        if max_data <= self.max_data {
+            return false;
+        }
+
+        self.max_data = max_data;
+        true
+    }
+
+    /// 判断是否需要发送 *_BLOCKED 帧, 每个限制值仅发送一次
+    ///
+    /// # Returns
+    /// 返回需要通告的限制
+    pub(crate) fn poll_blocked(&mut self) -> Option<usize> {
+        if self.get_available() != 0 || self.blocked_at == Some(self.max_data) {
+            return None;
+        }
+
+        self.blocked_at = Some(self.max_data);
+        Some(self.max_data)
+    }
+
+    /// *_BLOCKED 帧丢失后允许再次发送
+    ///
+    /// # Arguments
+    /// `max_data` - 丢失帧中通告的限制
+    pub(crate) fn on_blocked_lost(&mut self, max_data: usize) {
+        if self.blocked_at == Some(max_data) {
+            self.blocked_at = None;
+        }
+    }
+}
+
+/// 接收方向的流量控制窗口
+///
+/// 在应用层读取数据后向对端扩大限制, 当扩大限制的间隔短于 2 个 RTT 时
+/// 认为窗口限制了吞吐, 将窗口翻倍直至上限.
+pub(crate) struct RecvWindow {
+    /// 已通告给对端的限制
+    max_data: usize,
+
+    /// 已接收的数据量
+    received: usize,
+
+    /// 应用层已读取的数据量
+    consumed: usize,
+
+    /// 当前窗口大小
+    window: usize,
+
+    /// 窗口大小上限
+    max_window: usize,
+
+    /// 最近一次扩大限制的时间
+    last_update: Option<Instant>,
+}
+
+impl RecvWindow {
+    /// 构造一个接收方向的流量控制窗口
+    ///
+    /// # Arguments
+    /// `window` - 初始窗口大小, 即通告给对端的初始限制
+    /// `max_window` - 窗口大小上限
+    ///
+    /// # Returns
+    /// 返回接收方向的流量控制窗口
+    pub(crate) fn new(window: usize, max_window: usize) -> Self {
+        Self {
+            max_data: window,
+            received: 0,
+            consumed: 0,
+            window,
+            max_window: max_window.max(window),
+            last_update: None,
+        }
+    }
+
+    /// 获取已通告给对端的限制
+    ///
+    /// # Returns
+    /// 返回已通告的限制
+    #[inline(always)]
+    pub(crate) const fn get_max_data(&self) -> usize {
+        self.max_data
+    }
+
+    /// 获取已接收的数据量
+    ///
+    /// # Returns
+    /// 返回已接收的数据量
+    #[inline(always)]
+    pub(crate) const fn get_received(&self) -> usize {
+        self.received
+    }
+
+    /// 获取应用层已读取的数据量
+    ///
+    /// # Returns
+    /// 返回已读取的数据量
+    #[inline(always)]
+    pub(crate) const fn get_consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// 获取当前窗口大小
+    ///
+    /// # Returns
+    /// 返回当前窗口大小
+    #[inline(always)]
+    pub(crate) const fn get_window(&self) -> usize {
+        self.window
+    }
+
+    /// 记录新接收的数据
+    ///
+    /// # Arguments
+    /// `size` - 新接收的数据量
+    ///
+    /// # Returns
+    /// 超过已通告的限制时返回 false
+    pub(crate) fn on_received(&mut self, size: usize) -> bool {
+        self.received += size;
+        self.received <= self.max_data
+    }
+
+    /// 记录应用层读取的数据
+    ///
+    /// # Arguments
+    /// `size` - 读取的数据量
+    pub(crate) fn on_consumed(&mut self, size: usize) {
+        self.consumed += size;
+    }
+
+    /// 判断是否需要扩大限制, 剩余信用不足半个窗口时需要扩大
+    ///
+    /// # Returns
+    /// 返回是否需要扩大限制
+    pub(crate) fn should_update(&self) -> bool {
+        self.max_data - self.consumed.min(self.max_data) < self.window / 2
+    }
+
+    /// 扩大限制
+    ///
+    /// # Arguments
+    /// `now` - 当前时间
+    /// `smoothed_rtt` - 平滑 RTT
+    ///
+    /// # Returns
+    /// 需要扩大限制时返回新的限制
+    pub(crate) fn poll_update(&mut self, now: Instant, smoothed_rtt: Duration) -> Option<usize> {
+        if !self.should_update() {
+            return None;
+        }
+
+        if self
+            .last_update
+            .is_some_and(|last| now.saturating_duration_since(last) < smoothed_rtt * 2)
+        {
+            self.window = (self.window * 2).min(self.max_window);
+        }
+        self.last_update = Some(now);
+
+        self.max_data = self.consumed + self.window;
+        Some(self.max_data)
+    }
+
+    /// 更新限制的帧丢失后, 重新通告当前限制
+    ///
+    /// # Arguments
+    /// `max_data` - 丢失帧中通告的限制
+    ///
+    /// # Returns
+    /// 丢失的限制仍是最新值时返回需要重传的限制
+    pub(crate) fn on_update_lost(&self, max_data: usize) -> Option<usize> {
+        (max_data == self.max_data).then_some(self.max_data)
+    }
+}
@@ -1,3 +1,5 @@
+use std::{io, ops::RangeInclusive};
+
 use crate::{
     attr::{Deserializer, PacketNumber, Serializer},
     util,
@@ -82,13 +84,85 @@ pub(crate) struct ACKRange {
 ///     ECT1 Count (i),
 ///     ECN-CE Count (i),
 /// }
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub(crate) struct ECNCounts {
     ect0: u64,
     ect1: u64,
     ecn_ce: u64,
 }
 
+impl ECNCounts {
+    /// 构造一个 ECN Counts
+    ///
+    /// # Arguments
+    /// `ect0` - 收到的 ECT(0) 数据包数量
+    /// `ect1` - 收到的 ECT(1) 数据包数量
+    /// `ecn_ce` - 收到的 ECN-CE 数据包数量
+    /// # Returns
+    /// 返回一个 ECN Counts
+    pub(crate) const fn new(ect0: u64, ect1: u64, ecn_ce: u64) -> Self {
+        Self { ect0, ect1, ecn_ce }
+    }
+
+    /// 获取收到的 ECT(0) 数据包数量
+    ///
+    /// # Returns
+    /// 返回 ECT(0) 数据包数量
+    #[inline(always)]
+    pub(crate) const fn get_ect0(&self) -> u64 {
+        self.ect0
+    }
+
+    /// 获取收到的 ECT(1) 数据包数量
+    ///
+    /// # Returns
+    /// 返回 ECT(1) 数据包数量
+    #[inline(always)]
+    pub(crate) const fn get_ect1(&self) -> u64 {
+        self.ect1
+    }
+
+    /// 获取收到的 ECN-CE 数据包数量
+    ///
+    /// # Returns
+    /// 返回 ECN-CE 数据包数量
+    #[inline(always)]
+    pub(crate) const fn get_ecn_ce(&self) -> u64 {
+        self.ecn_ce
+    }
+}
+
+impl ACKRange {
+    /// 构造一个 ACK 范围
+    ///
+    /// # Arguments
+    /// `gap` - 与前一个 ACK 范围之间未被确认的连续数据包数量减 1
+    /// `length` - 本范围内被确认的连续数据包数量减 1
+    /// # Returns
+    /// 返回一个 ACK 范围
+    pub(crate) const fn new(gap: usize, length: usize) -> Self {
+        Self { gap, length }
+    }
+
+    /// 获取 Gap
+    ///
+    /// # Returns
+    /// 返回 Gap
+    #[inline(always)]
+    pub(crate) const fn get_gap(&self) -> usize {
+        self.gap
+    }
+
+    /// 获取 ACK 范围长度
+    ///
+    /// # Returns
+    /// 返回 ACK 范围长度
+    #[inline(always)]
+    pub(crate) const fn get_length(&self) -> usize {
+        self.length
+    }
+}
+
 impl ACKFrame {
     /// 构造一个 ACK 帧
     ///
@@ -103,11 +177,7 @@ impl ACKFrame {
             first_range: 0,
             ranges: Vec::new(),
             ecn: if with_ecn {
-                Some(ECNCounts {
-                    ect0: 0,
-                    ect1: 0,
-                    ecn_ce: 0,
-                })
+                Some(ECNCounts::default())
             } else {
                 None
             },
@@ -203,6 +273,35 @@ impl ACKFrame {
     pub(crate) fn set_ranges(&mut self, ranges: &[ACKRange]) {
         self.ranges.extend_from_slice(ranges)
     }
+
+    /// 将 First ACK Range 与各 ACK 范围还原为被确认的数据包编号区间
+    ///
+    /// # Returns
+    /// 按数据包编号递减排列的被确认区间;
+    /// 若某个范围的数据包编号小于 0, 则返回 io::Error.
+    pub(crate) fn get_acked_ranges(&self) -> Result<Vec<RangeInclusive<PacketNumber>>, io::Error> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid ack range");
+
+        let mut ret = Vec::with_capacity(self.ranges.len() + 1);
+
+        let mut smallest = self
+            .largest
+            .checked_sub(self.first_range as u64)
+            .ok_or_else(invalid)?;
+        ret.push(smallest..=self.largest);
+
+        for range in self.ranges.iter() {
+            let largest = smallest
+                .checked_sub(range.gap as u64 + 2)
+                .ok_or_else(invalid)?;
+            smallest = largest
+                .checked_sub(range.length as u64)
+                .ok_or_else(invalid)?;
+            ret.push(smallest..=largest);
+        }
+
+        Ok(ret)
+    }
 }
 
 impl Serializer for ACKFrame {
@@ -248,7 +347,7 @@ impl Deserializer for ACKFrame {
 
         let range_count = util::read_varint(r)?;
         payload_size += range_count.size;
-        let range_count = range_count.value as usize - 1;
+        let range_count = range_count.value as usize;
 
         let first_range = util::read_varint(r)?;
         self.first_range = first_range.value as usize;
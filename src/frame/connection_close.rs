@@ -1,5 +1,6 @@
 use crate::{
-    attr::{Deserializer, Serializer},
+    attr::{Deserializer, PacketNumberSpace, Serializer},
+    error::{ConnectionError, TransportErrorCode},
     util,
 };
 
@@ -47,6 +48,70 @@ impl ConnectionCloseFrame {
         }
     }
 
+    /// 由连接错误构造在指定编号空间中发送的 CONNECTION_CLOSE 帧
+    ///
+    /// 应用层关闭不能出现在 Initial 与 Handshake 数据包中 (RFC 9000 §10.2.3),
+    /// 此时转换为错误码为 APPLICATION_ERROR 的传输层关闭, 并清空错误原因.
+    ///
+    /// # Arguments
+    /// `error` - 连接错误
+    /// `space` - 承载该帧的数据包编号空间
+    ///
+    /// # Returns
+    /// 返回 CONNECTION_CLOSE 帧
+    pub(crate) fn from_error(error: &ConnectionError, space: PacketNumberSpace) -> Self {
+        match error {
+            ConnectionError::Transport {
+                code,
+                frame_type,
+                reason,
+            } => {
+                let mut frame = Self::new(true);
+                frame.set_error_code((*code).into());
+                frame.set_frame_type(*frame_type);
+                frame.set_reason(reason);
+                frame
+            }
+            ConnectionError::Application { error_code, reason } => {
+                if space == PacketNumberSpace::ApplicationData {
+                    let mut frame = Self::new(false);
+                    frame.set_error_code(*error_code);
+                    frame.set_reason(reason);
+                    frame
+                } else {
+                    let mut frame = Self::new(true);
+                    frame.set_error_code(TransportErrorCode::ApplicationError.into());
+                    frame
+                }
+            }
+            // 空闲超时不发送 CONNECTION_CLOSE 帧, 仅为完整起见映射为 NO_ERROR
+            ConnectionError::TimedOut => {
+                let mut frame = Self::new(true);
+                frame.set_error_code(TransportErrorCode::NoError.into());
+                frame
+            }
+        }
+    }
+
+    /// 获取帧所表示的连接错误
+    ///
+    /// # Returns
+    /// 返回连接错误
+    pub(crate) fn get_error(&self) -> ConnectionError {
+        if self.sys_err {
+            ConnectionError::Transport {
+                code: TransportErrorCode::from(self.error_code),
+                frame_type: self.frame_type,
+                reason: self.reason.clone(),
+            }
+        } else {
+            ConnectionError::Application {
+                error_code: self.error_code,
+                reason: self.reason.clone(),
+            }
+        }
+    }
+
     /// 获取是否是系统级（QUIC 层级）报错
     ///
     /// # Returns
@@ -159,11 +224,9 @@ impl Deserializer for ConnectionCloseFrame {
         }
 
         let len = util::read_varint(r)?;
-        let mut reason = Vec::<u8>::with_capacity(len.value as usize);
-        reason.resize(len.value as usize, 0);
         payload_size += len.size;
 
-        r.read_exact(&mut reason)?;
+        let reason = util::read_bytes(r, len.value)?;
         self.reason = String::from_utf8(reason).or(Err(std::io::Error::new(
             std::io::ErrorKind::InvalidInput,
             "invalid input",
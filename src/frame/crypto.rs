@@ -82,10 +82,9 @@ impl Deserializer for CryptoFrame {
         payload_size += offset.size;
 
         let len = util::read_varint(r)?;
-        self.data.resize(len.value as usize, 0);
         payload_size += len.size;
 
-        r.read_exact(&mut self.data)?;
+        self.data = util::read_bytes(r, len.value)?;
         payload_size += self.data.len();
 
         Ok(payload_size)
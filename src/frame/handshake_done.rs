@@ -0,0 +1,37 @@
+use crate::attr::{Deserializer, Serializer};
+
+use super::types::FrameType;
+
+/// HANDSHAKE_DONE 帧
+///
+/// 用于服务端向客户端发出握手确认信号, 只能由服务端发送.
+///
+/// 帧结构如下:
+/// HANDSHAKE_DONE Frame {
+///     Type (i) = 0x1e,
+/// }
+pub(crate) struct HandshakeDoneFrame;
+
+impl HandshakeDoneFrame {
+    /// 构造一个 HANDSHAKE_DONE 帧
+    ///
+    /// # Returns
+    /// 返回一个 HANDSHAKE_DONE 帧
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Serializer for HandshakeDoneFrame {
+    fn write(&self, w: &mut dyn std::io::Write) -> Result<usize, std::io::Error> {
+        w.write_all(&[FrameType::HandshakeDone.into()])?;
+
+        Ok(1)
+    }
+}
+
+impl Deserializer for HandshakeDoneFrame {
+    fn read(&mut self, _r: &mut dyn std::io::Read) -> Result<usize, std::io::Error> {
+        Ok(0)
+    }
+}
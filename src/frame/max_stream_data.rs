@@ -1,5 +1,6 @@
 use crate::{
-    attr::{Deserializer, Serializer, StreamID, StreamIDGetter, StreamIDSetter},
+    attr::{Deserializer, Serializer, Side, StreamIDGetter, StreamIDSetter, StreamId},
+    error::TransportError,
     util,
 };
 
@@ -21,7 +22,7 @@ use super::types::FrameType;
 /// }
 pub(crate) struct MaxStreamDataFrame {
     /// 流标识
-    stream_id: StreamID,
+    stream_id: StreamId,
 
     /// 限定流上发送的最大数据量
     maximum_data: usize,
@@ -34,7 +35,7 @@ impl MaxStreamDataFrame {
     /// 返回一个 MAX_STREAM_DATA 帧
     pub(crate) fn new() -> Self {
         Self {
-            stream_id: 0,
+            stream_id: StreamId::new(0),
             maximum_data: 0,
         }
     }
@@ -56,16 +57,26 @@ impl MaxStreamDataFrame {
     pub(crate) fn set_maximum_data(&mut self, maximum_data: usize) {
         self.maximum_data = maximum_data
     }
+
+    /// 检查流标识的方向是否允许本端接收该帧,
+    /// 本端仅接收的单向流上收到 MAX_STREAM_DATA 帧时返回 STREAM_STATE_ERROR.
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    pub(crate) fn check_direction(&self, local: Side) -> Result<(), TransportError> {
+        self.stream_id
+            .check_sendable(local, u8::from(FrameType::MaxStreamData) as u64)
+    }
 }
 
 impl StreamIDGetter for MaxStreamDataFrame {
-    fn get_stream_id(&self) -> StreamID {
+    fn get_stream_id(&self) -> StreamId {
         self.stream_id
     }
 }
 
 impl StreamIDSetter for MaxStreamDataFrame {
-    fn set_stream_id(&mut self, stream_id: StreamID) {
+    fn set_stream_id(&mut self, stream_id: StreamId) {
         self.stream_id = stream_id
     }
 }
@@ -76,7 +87,7 @@ impl Serializer for MaxStreamDataFrame {
 
         w.write_all(&[FrameType::MaxStreamData.into()])?;
 
-        payload_size += util::write_varint(self.stream_id, w)?;
+        payload_size += util::write_varint(self.stream_id.get_value(), w)?;
         payload_size += util::write_varint(self.maximum_data as u64, w)?;
 
         Ok(payload_size)
@@ -88,7 +99,7 @@ impl Deserializer for MaxStreamDataFrame {
         let mut payload_size = 0;
 
         let stream_id = util::read_varint(r)?;
-        self.stream_id = stream_id.value;
+        self.stream_id = StreamId::new(stream_id.value);
         payload_size += stream_id.size;
 
         let maximum_data = util::read_varint(r)?;
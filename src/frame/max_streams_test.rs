@@ -0,0 +1,41 @@
+use crate::attr::{Deserializer, Serializer};
+
+use super::MaxStreamsFrame;
+
+#[test]
+fn test_max_streams_frame() {
+    for (bidi_flag, type_byte) in [(true, 0x12), (false, 0x13)] {
+        let mut frame = MaxStreamsFrame::new(bidi_flag);
+        frame.set_maximum_streams(1000);
+
+        let mut buf = Vec::new();
+        let size = frame.write(&mut buf).unwrap();
+        assert_eq!(size, buf.len());
+        assert_eq!(buf, [type_byte, 0x43, 0xe8]);
+
+        let mut decoded = MaxStreamsFrame::new(bidi_flag);
+        assert_eq!(decoded.read(&mut &buf[1..]).unwrap(), size - 1);
+        assert_eq!(decoded.is_bidi(), bidi_flag);
+        assert_eq!(decoded.get_maximum_streams(), 1000);
+    }
+}
+
+#[test]
+fn test_max_streams_frame_above_limit() {
+    // 2^60 + 1, 由 StreamCounts 负责以 FRAME_ENCODING_ERROR 拒绝
+    let buf = [0xd0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+    let mut frame = MaxStreamsFrame::new(true);
+    assert_eq!(frame.read(&mut &buf[..]).unwrap(), 8);
+    assert_eq!(frame.get_maximum_streams(), (1 << 60) + 1);
+
+    let mut encoded = Vec::new();
+    frame.write(&mut encoded).unwrap();
+    assert_eq!(encoded[1..], buf);
+}
+
+#[test]
+fn test_max_streams_frame_truncated() {
+    let buf = [0x80, 0x00];
+    let mut frame = MaxStreamsFrame::new(false);
+    assert!(frame.read(&mut &buf[..]).is_err());
+}
@@ -4,15 +4,51 @@ mod ack;
 mod connection_close;
 mod crypto;
 mod data_blocked;
+mod handshake_done;
 mod max_data;
 mod max_stream_data;
+mod max_streams;
 mod new_connection_id;
 mod new_token;
+mod parser;
 mod path_challenge;
 mod path_response;
+mod ping;
 mod reset_stream;
+mod reset_stream_at;
 mod retire_connection_id;
 mod stop_sending;
 mod stream;
 mod stream_data_blocked;
 mod streams_blocked;
+
+pub(crate) use types::FrameType;
+
+pub(crate) use ack::*;
+pub(crate) use connection_close::*;
+pub(crate) use crypto::*;
+pub(crate) use data_blocked::*;
+pub(crate) use handshake_done::*;
+pub(crate) use max_data::*;
+pub(crate) use max_stream_data::*;
+pub(crate) use max_streams::*;
+pub(crate) use new_connection_id::*;
+pub(crate) use new_token::*;
+pub(crate) use parser::*;
+pub(crate) use path_challenge::*;
+pub(crate) use path_response::*;
+pub(crate) use ping::*;
+pub(crate) use reset_stream::*;
+pub(crate) use reset_stream_at::*;
+pub(crate) use retire_connection_id::*;
+pub(crate) use stop_sending::*;
+pub(crate) use stream::*;
+pub(crate) use stream_data_blocked::*;
+pub(crate) use streams_blocked::*;
+
+#[cfg(test)]
+mod max_streams_test;
+#[cfg(test)]
+mod parser_test;
+#[cfg(test)]
+mod reset_stream_at_test;
@@ -171,9 +171,14 @@ impl Deserializer for NewConnectionIDFrame {
         r.read_exact(&mut len_bytes)?;
         let len = util::from_bigendian_bytes::<1>(&len_bytes) as usize;
         payload_size += len_bytes.len();
+        if !(1..=20).contains(&len) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "invalid connection id length",
+            ));
+        }
 
-        let mut conn_id = Vec::with_capacity(len);
-        conn_id.resize(len, 0);
+        let mut conn_id = vec![0u8; len];
         r.read_exact(&mut conn_id)?;
         self.connection_id.set_id(&conn_id);
         payload_size += conn_id.len();
@@ -73,8 +73,7 @@ impl Deserializer for NewTokenFrame {
         let token_len = util::read_varint(r)?;
         payload_size += token_len.size;
 
-        self.token.resize(token_len.value as usize, 0);
-        r.read_exact(&mut self.token)?;
+        self.token = util::read_bytes(r, token_len.value)?;
         payload_size += self.token.len();
 
         Ok(payload_size)
@@ -0,0 +1,223 @@
+use crate::{
+    attr::Deserializer,
+    error::{TransportError, TransportErrorCode},
+    util,
+};
+
+use super::{
+    ack::ACKFrame, connection_close::ConnectionCloseFrame, crypto::CryptoFrame,
+    data_blocked::DataBlockedFrame, max_data::MaxDataFrame, max_stream_data::MaxStreamDataFrame,
+    max_streams::MaxStreamsFrame, new_connection_id::NewConnectionIDFrame,
+    new_token::NewTokenFrame, path_challenge::PathChallengeFrame, path_response::PathResponseFrame,
+    reset_stream::ResetStreamFrame, reset_stream_at::ResetStreamAtFrame,
+    retire_connection_id::RetireConnectionIDFrame, stop_sending::StopSendingFrame,
+    stream::StreamFrame, stream_data_blocked::StreamDataBlockedFrame,
+    streams_blocked::StreamsBlockedFrame, types::FrameType,
+};
+
+/// 从数据包载荷中解析出的帧
+pub(crate) enum Frame {
+    /// 一个或多个连续的 PADDING 帧
+    Padding,
+    Ping,
+    Ack(ACKFrame),
+    ResetStream(ResetStreamFrame),
+    StopSending(StopSendingFrame),
+    Crypto(CryptoFrame),
+    NewToken(NewTokenFrame),
+    Stream(StreamFrame),
+    MaxData(MaxDataFrame),
+    MaxStreamData(MaxStreamDataFrame),
+    MaxStreams(MaxStreamsFrame),
+    DataBlocked(DataBlockedFrame),
+    StreamDataBlocked(StreamDataBlockedFrame),
+    StreamsBlocked(StreamsBlockedFrame),
+    NewConnectionID(NewConnectionIDFrame),
+    RetireConnectionID(RetireConnectionIDFrame),
+    PathChallenge(PathChallengeFrame),
+    PathResponse(PathResponseFrame),
+    ConnectionClose(ConnectionCloseFrame),
+    HandshakeDone,
+    ResetStreamAt(ResetStreamAtFrame),
+}
+
+impl Frame {
+    /// 判断该帧是否会引发对方发送 ACK
+    ///
+    /// # Returns
+    /// 除 ACK、PADDING 与 CONNECTION_CLOSE 之外的帧均返回 true
+    pub(crate) const fn is_ack_eliciting(&self) -> bool {
+        !matches!(
+            self,
+            Frame::Padding | Frame::Ack(_) | Frame::ConnectionClose(_)
+        )
+    }
+
+    /// 判断该帧是否允许出现在 Initial 与 Handshake 数据包中
+    ///
+    /// # Returns
+    /// PADDING、PING、ACK、CRYPTO 与传输层的 CONNECTION_CLOSE 帧返回 true
+    pub(crate) fn is_allowed_in_handshake(&self) -> bool {
+        match self {
+            Frame::Padding | Frame::Ping | Frame::Ack(_) | Frame::Crypto(_) => true,
+            Frame::ConnectionClose(frame) => frame.get_sys_err(),
+            _ => false,
+        }
+    }
+
+    /// 判断该帧是否为探测帧, 仅含探测帧的数据包不会引起连接迁移 (RFC 9000 §9.1)
+    ///
+    /// # Returns
+    /// PADDING、PATH_CHALLENGE、PATH_RESPONSE 与 NEW_CONNECTION_ID 帧返回 true
+    pub(crate) const fn is_probing(&self) -> bool {
+        matches!(
+            self,
+            Frame::Padding
+                | Frame::PathChallenge(_)
+                | Frame::PathResponse(_)
+                | Frame::NewConnectionID(_)
+        )
+    }
+}
+
+/// 帧解析器
+///
+/// 依次解析数据包载荷中的帧, 以迭代器的形式返回帧类型字节与帧.
+/// 遇到未知的帧类型或格式错误的帧时返回 FRAME_ENCODING_ERROR, 之后不再继续解析.
+pub(crate) struct FrameParser<'a> {
+    /// 尚未解析的载荷
+    buf: &'a [u8],
+}
+
+impl<'a> FrameParser<'a> {
+    /// 构造一个帧解析器
+    ///
+    /// # Arguments
+    /// `payload` - 解密后的数据包载荷
+    ///
+    /// # Returns
+    /// 返回帧解析器
+    pub(crate) fn new(payload: &'a [u8]) -> Self {
+        Self { buf: payload }
+    }
+
+    /// 解析一个帧
+    ///
+    /// # Returns
+    /// 返回帧类型字节与帧; 帧类型未知或帧格式错误时返回 FRAME_ENCODING_ERROR
+    fn parse(&mut self) -> Result<(u8, Frame), TransportError> {
+        let r = &mut self.buf;
+        let frame_type = util::read_varint(r).map_err(|_| encoding_error(0))?.value;
+        if frame_type > u8::MAX as u64 {
+            return Err(encoding_error(frame_type));
+        }
+        let type_byte = frame_type as u8;
+
+        let frame = match FrameType::from(type_byte) {
+            FrameType::Padding => {
+                // 连续的 PADDING 帧合并为一个
+                let len = r.iter().take_while(|&&b| b == 0).count();
+                *r = &r[len..];
+                Frame::Padding
+            }
+            FrameType::Ping => Frame::Ping,
+            FrameType::Ack { with_ecm } => Frame::Ack(read(r, ACKFrame::new(with_ecm), type_byte)?),
+            FrameType::ResetStream => {
+                Frame::ResetStream(read(r, ResetStreamFrame::new(), type_byte)?)
+            }
+            FrameType::StopSending => {
+                Frame::StopSending(read(r, StopSendingFrame::new(), type_byte)?)
+            }
+            FrameType::Crypto => Frame::Crypto(read(r, CryptoFrame::new(), type_byte)?),
+            FrameType::NewToken => Frame::NewToken(read(r, NewTokenFrame::new(), type_byte)?),
+            FrameType::Stream {
+                off_flag,
+                len_flag,
+                fin_flag,
+            } => Frame::Stream(read(
+                r,
+                StreamFrame::new(off_flag, len_flag, fin_flag),
+                type_byte,
+            )?),
+            FrameType::MaxData => Frame::MaxData(read(r, MaxDataFrame::new(), type_byte)?),
+            FrameType::MaxStreamData => {
+                Frame::MaxStreamData(read(r, MaxStreamDataFrame::new(), type_byte)?)
+            }
+            FrameType::MaxStreams { bidi_flag } => {
+                Frame::MaxStreams(read(r, MaxStreamsFrame::new(bidi_flag), type_byte)?)
+            }
+            FrameType::DataBlocked => {
+                Frame::DataBlocked(read(r, DataBlockedFrame::new(), type_byte)?)
+            }
+            FrameType::StreamDataBlocked => {
+                Frame::StreamDataBlocked(read(r, StreamDataBlockedFrame::new(), type_byte)?)
+            }
+            FrameType::StreamsBlocked { bidi_flag } => {
+                Frame::StreamsBlocked(read(r, StreamsBlockedFrame::new(bidi_flag), type_byte)?)
+            }
+            FrameType::NewConnectionID => {
+                Frame::NewConnectionID(read(r, NewConnectionIDFrame::new(), type_byte)?)
+            }
+            FrameType::RetireConnectionID => {
+                Frame::RetireConnectionID(read(r, RetireConnectionIDFrame::new(), type_byte)?)
+            }
+            FrameType::PathChallenge => {
+                Frame::PathChallenge(read(r, PathChallengeFrame::new(), type_byte)?)
+            }
+            FrameType::PathResponse => {
+                Frame::PathResponse(read(r, PathResponseFrame::new(), type_byte)?)
+            }
+            FrameType::ConnectionClose { sys_err } => {
+                Frame::ConnectionClose(read(r, ConnectionCloseFrame::new(sys_err), type_byte)?)
+            }
+            FrameType::HandshakeDone => Frame::HandshakeDone,
+            FrameType::ResetStreamAt => {
+                Frame::ResetStreamAt(read(r, ResetStreamAtFrame::new(), type_byte)?)
+            }
+            FrameType::Extension { .. } => return Err(encoding_error(frame_type)),
+        };
+
+        Ok((type_byte, frame))
+    }
+}
+
+impl Iterator for FrameParser<'_> {
+    type Item = Result<(u8, Frame), TransportError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+
+        let result = self.parse();
+        if result.is_err() {
+            self.buf = &[];
+        }
+        Some(result)
+    }
+}
+
+/// 从载荷中读出帧的内容
+///
+/// # Arguments
+/// `r` - 尚未解析的载荷
+/// `frame` - 待填充的帧
+/// `type_byte` - 帧类型字节
+///
+/// # Returns
+/// 返回读出的帧; 帧格式错误时返回 FRAME_ENCODING_ERROR
+fn read<T: Deserializer>(r: &mut &[u8], mut frame: T, type_byte: u8) -> Result<T, TransportError> {
+    frame
+        .read(r)
+        .map_err(|_| encoding_error(type_byte as u64))?;
+    Ok(frame)
+}
+
+/// 构造 FRAME_ENCODING_ERROR
+fn encoding_error(frame_type: u64) -> TransportError {
+    TransportError::new(
+        TransportErrorCode::FrameEncodingError,
+        frame_type,
+        "malformed frame",
+    )
+}
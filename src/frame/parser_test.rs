@@ -0,0 +1,69 @@
+use crate::{
+    attr::{StreamDataGetter, StreamIDGetter},
+    error::{TransportError, TransportErrorCode},
+};
+
+use super::{Frame, FrameParser};
+
+/// 取值为 2^62 - 1 的长度字段
+const HUGE_LEN: [u8; 8] = [0xff; 8];
+
+/// 解析载荷中的第一个帧, 期望解析失败
+fn parse_error(payload: &[u8]) -> TransportError {
+    match FrameParser::new(payload).next().unwrap() {
+        Ok(_) => panic!("malformed frame parsed"),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn test_parser_stream_huge_length() {
+    // STREAM 帧, 携带 Length 字段
+    let mut payload = vec![0x0a, 0x04];
+    payload.extend_from_slice(&HUGE_LEN);
+    payload.extend_from_slice(&[1, 2, 3]);
+    let err = parse_error(&payload);
+    assert_eq!(err.get_code(), TransportErrorCode::FrameEncodingError);
+    assert_eq!(err.get_frame_type(), 0x0a);
+
+    // 长度与剩余的载荷一致时正常解析
+    let payload = [0x0a, 0x04, 0x03, 1, 2, 3];
+    match FrameParser::new(&payload).next().unwrap() {
+        Ok((_, Frame::Stream(frame))) => {
+            assert_eq!(frame.get_stream_id().get_value(), 4);
+            assert_eq!(frame.get_data(), (0, &[1, 2, 3][..]));
+        }
+        _ => panic!("STREAM frame expected"),
+    }
+}
+
+#[test]
+fn test_parser_crypto_huge_length() {
+    let mut payload = vec![0x06, 0x00];
+    payload.extend_from_slice(&HUGE_LEN);
+    payload.extend_from_slice(&[1, 2, 3]);
+    let err = parse_error(&payload);
+    assert_eq!(err.get_code(), TransportErrorCode::FrameEncodingError);
+    assert_eq!(err.get_frame_type(), 0x06);
+}
+
+#[test]
+fn test_parser_new_token_huge_length() {
+    let mut payload = vec![0x07];
+    payload.extend_from_slice(&HUGE_LEN);
+    payload.extend_from_slice(&[1, 2, 3]);
+    let err = parse_error(&payload);
+    assert_eq!(err.get_code(), TransportErrorCode::FrameEncodingError);
+    assert_eq!(err.get_frame_type(), 0x07);
+}
+
+#[test]
+fn test_parser_connection_close_huge_length() {
+    // 传输层 CONNECTION_CLOSE 帧: Error Code、Frame Type 与 Reason Phrase Length
+    let mut payload = vec![0x1c, 0x0a, 0x00];
+    payload.extend_from_slice(&HUGE_LEN);
+    payload.extend_from_slice(b"bye");
+    let err = parse_error(&payload);
+    assert_eq!(err.get_code(), TransportErrorCode::FrameEncodingError);
+    assert_eq!(err.get_frame_type(), 0x1c);
+}
@@ -46,7 +46,7 @@ impl Serializer for PathChallengeFrame {
     fn write(&self, w: &mut dyn std::io::Write) -> Result<usize, std::io::Error> {
         let mut payload_size = 1;
 
-        w.write_all(&[FrameType::PathResponse.into()])?;
+        w.write_all(&[FrameType::PathChallenge.into()])?;
 
         w.write_all(&self.data)?;
         payload_size += self.data.len();
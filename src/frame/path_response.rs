@@ -8,7 +8,7 @@ use super::types::FrameType;
 ///
 /// 帧结构如下:
 /// PATH_RESPONSE Frame {
-///     Type (i) = 0x1a,
+///     Type (i) = 0x1b,
 ///     Data (64),
 /// }
 pub(crate) struct PathResponseFrame {
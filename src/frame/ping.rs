@@ -0,0 +1,37 @@
+use crate::attr::{Deserializer, Serializer};
+
+use super::types::FrameType;
+
+/// PING 帧
+///
+/// 用于检查对方是否仍然存活, 或使数据包成为 ack-eliciting 的数据包.
+///
+/// 帧结构如下:
+/// PING Frame {
+///     Type (i) = 0x01,
+/// }
+pub(crate) struct PingFrame;
+
+impl PingFrame {
+    /// 构造一个 PING 帧
+    ///
+    /// # Returns
+    /// 返回一个 PING 帧
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Serializer for PingFrame {
+    fn write(&self, w: &mut dyn std::io::Write) -> Result<usize, std::io::Error> {
+        w.write_all(&[FrameType::Ping.into()])?;
+
+        Ok(1)
+    }
+}
+
+impl Deserializer for PingFrame {
+    fn read(&mut self, _r: &mut dyn std::io::Read) -> Result<usize, std::io::Error> {
+        Ok(0)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
-    attr::{Deserializer, Serializer, StreamID, StreamIDGetter, StreamIDSetter},
+    attr::{Deserializer, Serializer, Side, StreamIDGetter, StreamIDSetter, StreamId},
+    error::TransportError,
     util,
 };
 
@@ -21,7 +22,7 @@ use super::types::FrameType;
 /// }
 pub(crate) struct ResetStreamFrame {
     /// Stream 标识
-    stream_id: StreamID,
+    stream_id: StreamId,
 
     /// 应用错误码
     ///
@@ -42,7 +43,7 @@ impl ResetStreamFrame {
     /// RESET_STREAM 帧
     pub(crate) fn new() -> Self {
         Self {
-            stream_id: 0,
+            stream_id: StreamId::new(0),
             error_code: 0,
             final_size: 0,
         }
@@ -82,16 +83,26 @@ impl ResetStreamFrame {
     pub(crate) fn set_error_code(&mut self, error_code: u64) {
         self.error_code = error_code
     }
+
+    /// 检查流标识的方向是否允许本端接收该帧,
+    /// 本端仅发送的单向流上收到 RESET_STREAM 帧时返回 STREAM_STATE_ERROR.
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    pub(crate) fn check_direction(&self, local: Side) -> Result<(), TransportError> {
+        self.stream_id
+            .check_receivable(local, u8::from(FrameType::ResetStream) as u64)
+    }
 }
 
 impl StreamIDGetter for ResetStreamFrame {
-    fn get_stream_id(&self) -> StreamID {
+    fn get_stream_id(&self) -> StreamId {
         self.stream_id
     }
 }
 
 impl StreamIDSetter for ResetStreamFrame {
-    fn set_stream_id(&mut self, stream_id: StreamID) {
+    fn set_stream_id(&mut self, stream_id: StreamId) {
         self.stream_id = stream_id
     }
 }
@@ -102,7 +113,7 @@ impl Serializer for ResetStreamFrame {
 
         w.write_all(&[FrameType::ResetStream.into()])?;
 
-        payload_size += util::write_varint(self.stream_id, w)?;
+        payload_size += util::write_varint(self.stream_id.get_value(), w)?;
         payload_size += util::write_varint(self.error_code, w)?;
         payload_size += util::write_varint(self.final_size as u64, w)?;
 
@@ -115,7 +126,7 @@ impl Deserializer for ResetStreamFrame {
         let mut payload_size = 0;
 
         let stream_id = util::read_varint(r)?;
-        self.stream_id = stream_id.value;
+        self.stream_id = StreamId::new(stream_id.value);
         payload_size += stream_id.size;
 
         let error_code = util::read_varint(r)?;
@@ -0,0 +1,167 @@
+use crate::{
+    attr::{Deserializer, Serializer, Side, StreamIDGetter, StreamIDSetter, StreamId},
+    error::TransportError,
+    util,
+};
+
+use super::types::FrameType;
+
+/// RESET_STREAM_AT 帧
+///
+/// 用于重置流的发送部分, 但要求接收方在流被重置之前, 仍能收到可靠大小之前的全部数据.
+/// 例如 WebTransport 需要保证流头部在重置后仍被交付.
+///
+/// 可靠大小不能超过最终大小, 否则接收方必须以 FRAME_ENCODING_ERROR 关闭连接.
+///
+/// 帧结构如下:
+/// RESET_STREAM_AT Frame {
+///     Type (i) = 0x24,
+///     Stream ID (i),
+///     Application Protocol Error Code (i),
+///     Final Size (i),
+///     Reliable Size (i),
+/// }
+pub(crate) struct ResetStreamAtFrame {
+    /// Stream 标识
+    stream_id: StreamId,
+
+    /// 应用错误码
+    ///
+    /// 由应用协议自行管理
+    error_code: u64,
+
+    /// QUIC 协议中 Stream 的最终大小.
+    final_size: usize,
+
+    /// 可靠大小, 在此之前的数据必须交付给接收方
+    reliable_size: usize,
+}
+
+impl ResetStreamAtFrame {
+    /// 构造一个 RESET_STREAM_AT 帧.
+    ///
+    /// # Returns
+    /// RESET_STREAM_AT 帧
+    pub(crate) fn new() -> Self {
+        Self {
+            stream_id: StreamId::new(0),
+            error_code: 0,
+            final_size: 0,
+            reliable_size: 0,
+        }
+    }
+
+    /// 获取 Stream 的最终大小.
+    ///
+    /// # Returns
+    /// 返回 Stream 的最终大小
+    #[inline(always)]
+    pub(crate) const fn get_final_size(&self) -> usize {
+        self.final_size
+    }
+
+    /// 设置 Stream 的最终大小.
+    ///
+    /// # Arguments
+    /// `final_size` - Stream 的最终大小
+    #[inline(always)]
+    pub(crate) fn set_final_size(&mut self, final_size: usize) {
+        self.final_size = final_size
+    }
+
+    /// 获取可靠大小.
+    ///
+    /// # Returns
+    /// 返回可靠大小
+    #[inline(always)]
+    pub(crate) const fn get_reliable_size(&self) -> usize {
+        self.reliable_size
+    }
+
+    /// 设置可靠大小.
+    ///
+    /// # Arguments
+    /// `reliable_size` - 可靠大小
+    #[inline(always)]
+    pub(crate) fn set_reliable_size(&mut self, reliable_size: usize) {
+        self.reliable_size = reliable_size
+    }
+
+    /// 获取应用错误码
+    ///
+    /// # Returns
+    /// 返回应用错误码
+    #[inline(always)]
+    pub(crate) const fn get_error_code(&self) -> u64 {
+        self.error_code
+    }
+
+    /// 设置应用错误码
+    ///
+    /// # Arguments
+    /// `error_code` - 应用错误码
+    pub(crate) fn set_error_code(&mut self, error_code: u64) {
+        self.error_code = error_code
+    }
+
+    /// 检查流标识的方向是否允许本端接收该帧,
+    /// 本端仅发送的单向流上收到 RESET_STREAM_AT 帧时返回 STREAM_STATE_ERROR.
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    pub(crate) fn check_direction(&self, local: Side) -> Result<(), TransportError> {
+        self.stream_id
+            .check_receivable(local, u8::from(FrameType::ResetStreamAt) as u64)
+    }
+}
+
+impl StreamIDGetter for ResetStreamAtFrame {
+    fn get_stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+}
+
+impl StreamIDSetter for ResetStreamAtFrame {
+    fn set_stream_id(&mut self, stream_id: StreamId) {
+        self.stream_id = stream_id
+    }
+}
+
+impl Serializer for ResetStreamAtFrame {
+    fn write(&self, w: &mut dyn std::io::Write) -> Result<usize, std::io::Error> {
+        let mut payload_size = 1;
+
+        w.write_all(&[FrameType::ResetStreamAt.into()])?;
+
+        payload_size += util::write_varint(self.stream_id.get_value(), w)?;
+        payload_size += util::write_varint(self.error_code, w)?;
+        payload_size += util::write_varint(self.final_size as u64, w)?;
+        payload_size += util::write_varint(self.reliable_size as u64, w)?;
+
+        Ok(payload_size)
+    }
+}
+
+impl Deserializer for ResetStreamAtFrame {
+    fn read(&mut self, r: &mut dyn std::io::Read) -> Result<usize, std::io::Error> {
+        let mut payload_size = 0;
+
+        let stream_id = util::read_varint(r)?;
+        self.stream_id = StreamId::new(stream_id.value);
+        payload_size += stream_id.size;
+
+        let error_code = util::read_varint(r)?;
+        self.error_code = error_code.value;
+        payload_size += error_code.size;
+
+        let final_size = util::read_varint(r)?;
+        self.final_size = final_size.value as usize;
+        payload_size += final_size.size;
+
+        let reliable_size = util::read_varint(r)?;
+        self.reliable_size = reliable_size.value as usize;
+        payload_size += reliable_size.size;
+
+        Ok(payload_size)
+    }
+}
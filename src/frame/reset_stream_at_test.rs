@@ -0,0 +1,32 @@
+use crate::attr::{Deserializer, Serializer, StreamIDGetter, StreamIDSetter, StreamId};
+
+use super::ResetStreamAtFrame;
+
+#[test]
+fn test_reset_stream_at_frame() {
+    let mut frame = ResetStreamAtFrame::new();
+    frame.set_stream_id(StreamId::new(6));
+    frame.set_error_code(0x1234);
+    frame.set_final_size(100_000);
+    frame.set_reliable_size(16);
+
+    let mut buf = Vec::new();
+    let size = frame.write(&mut buf).unwrap();
+    assert_eq!(size, buf.len());
+    assert_eq!(buf, [0x24, 0x06, 0x52, 0x34, 0x80, 0x01, 0x86, 0xa0, 0x10]);
+
+    let mut decoded = ResetStreamAtFrame::new();
+    assert_eq!(decoded.read(&mut &buf[1..]).unwrap(), size - 1);
+    assert_eq!(decoded.get_stream_id(), StreamId::new(6));
+    assert_eq!(decoded.get_error_code(), 0x1234);
+    assert_eq!(decoded.get_final_size(), 100_000);
+    assert_eq!(decoded.get_reliable_size(), 16);
+}
+
+#[test]
+fn test_reset_stream_at_frame_truncated() {
+    // 缺少 Reliable Size 字段
+    let buf = [0x06, 0x00, 0x05];
+    let mut frame = ResetStreamAtFrame::new();
+    assert!(frame.read(&mut &buf[..]).is_err());
+}
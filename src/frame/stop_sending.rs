@@ -1,5 +1,6 @@
 use crate::{
-    attr::{Deserializer, Serializer, StreamID, StreamIDGetter, StreamIDSetter},
+    attr::{Deserializer, Serializer, Side, StreamIDGetter, StreamIDSetter, StreamId},
+    error::TransportError,
     util,
 };
 
@@ -26,7 +27,7 @@ use super::types::FrameType;
 /// }
 pub(crate) struct StopSendingFrame {
     /// Stream 标识
-    stream_id: StreamID,
+    stream_id: StreamId,
 
     /// 应用错误码
     ///
@@ -41,7 +42,7 @@ impl StopSendingFrame {
     /// STOP_SENDING 帧
     pub(crate) fn new() -> Self {
         Self {
-            stream_id: 0,
+            stream_id: StreamId::new(0),
             error_code: 0,
         }
     }
@@ -62,16 +63,26 @@ impl StopSendingFrame {
     pub(crate) fn set_error_code(&mut self, error_code: u64) {
         self.error_code = error_code
     }
+
+    /// 检查流标识的方向是否允许本端接收该帧,
+    /// 本端仅接收的单向流上收到 STOP_SENDING 帧时返回 STREAM_STATE_ERROR.
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    pub(crate) fn check_direction(&self, local: Side) -> Result<(), TransportError> {
+        self.stream_id
+            .check_sendable(local, u8::from(FrameType::StopSending) as u64)
+    }
 }
 
 impl StreamIDGetter for StopSendingFrame {
-    fn get_stream_id(&self) -> StreamID {
+    fn get_stream_id(&self) -> StreamId {
         self.stream_id
     }
 }
 
 impl StreamIDSetter for StopSendingFrame {
-    fn set_stream_id(&mut self, stream_id: StreamID) {
+    fn set_stream_id(&mut self, stream_id: StreamId) {
         self.stream_id = stream_id
     }
 }
@@ -82,7 +93,7 @@ impl Serializer for StopSendingFrame {
 
         w.write_all(&[FrameType::StopSending.into()])?;
 
-        payload_size += util::write_varint(self.stream_id, w)?;
+        payload_size += util::write_varint(self.stream_id.get_value(), w)?;
         payload_size += util::write_varint(self.error_code, w)?;
 
         Ok(payload_size)
@@ -94,7 +105,7 @@ impl Deserializer for StopSendingFrame {
         let mut payload_size = 0;
 
         let stream_id = util::read_varint(r)?;
-        self.stream_id = stream_id.value;
+        self.stream_id = StreamId::new(stream_id.value);
         payload_size += stream_id.size;
 
         let error_code = util::read_varint(r)?;
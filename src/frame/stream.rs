@@ -1,8 +1,9 @@
 use crate::{
     attr::{
-        Deserializer, Serializer, StreamDataGetter, StreamDataSetter, StreamID, StreamIDGetter,
-        StreamIDSetter,
+        Deserializer, Serializer, Side, StreamDataGetter, StreamDataSetter, StreamIDGetter,
+        StreamIDSetter, StreamId,
     },
+    error::TransportError,
     util,
 };
 
@@ -25,7 +26,7 @@ pub struct StreamFrame {
     len_flag: bool,
     fin_flag: bool,
 
-    stream_id: StreamID,
+    stream_id: StreamId,
     offset: usize,
     data: Vec<u8>,
 }
@@ -46,7 +47,7 @@ impl StreamFrame {
             len_flag,
             fin_flag,
 
-            stream_id: 0,
+            stream_id: StreamId::new(0),
             offset: 0,
             data: Vec::new(),
         }
@@ -99,16 +100,32 @@ impl StreamFrame {
     pub(crate) fn set_off_flag(&mut self, off_flag: bool) {
         self.off_flag = off_flag
     }
+
+    /// 检查流标识的方向是否允许本端接收该帧,
+    /// 本端仅发送的单向流上收到 STREAM 帧时返回 STREAM_STATE_ERROR.
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    pub(crate) fn check_direction(&self, local: Side) -> Result<(), TransportError> {
+        self.stream_id.check_receivable(
+            local,
+            u8::from(FrameType::Stream {
+                off_flag: self.off_flag,
+                len_flag: self.len_flag,
+                fin_flag: self.fin_flag,
+            }) as u64,
+        )
+    }
 }
 
 impl StreamIDGetter for StreamFrame {
-    fn get_stream_id(&self) -> StreamID {
+    fn get_stream_id(&self) -> StreamId {
         self.stream_id
     }
 }
 
 impl StreamIDSetter for StreamFrame {
-    fn set_stream_id(&mut self, stream_id: StreamID) {
+    fn set_stream_id(&mut self, stream_id: StreamId) {
         self.stream_id = stream_id
     }
 }
@@ -137,7 +154,7 @@ impl Serializer for StreamFrame {
         }
         .into()])?;
 
-        payload_size += util::write_varint(self.stream_id, w)?;
+        payload_size += util::write_varint(self.stream_id.get_value(), w)?;
         if self.off_flag {
             payload_size += util::write_varint(self.offset as u64, w)?;
         }
@@ -157,7 +174,7 @@ impl Deserializer for StreamFrame {
         let mut payload_size = 0;
 
         let stream_id = util::read_varint(r)?;
-        self.stream_id = stream_id.value;
+        self.stream_id = StreamId::new(stream_id.value);
         payload_size += stream_id.size;
 
         if self.off_flag {
@@ -169,10 +186,9 @@ impl Deserializer for StreamFrame {
         // 如果 `len_flag` == false, 则该 stream 帧的 data 部分应该读取到数据包的结尾.
         if self.len_flag {
             let data_len = util::read_varint(r)?;
-            self.data.resize(data_len.value as usize, 0);
             payload_size += data_len.size;
 
-            r.read(&mut self.data)?;
+            self.data = util::read_bytes(r, data_len.value)?;
             payload_size += self.data.len();
         } else {
             payload_size += r.read_to_end(&mut self.data)?;
@@ -1,5 +1,6 @@
 use crate::{
-    attr::{Deserializer, Serializer, StreamID, StreamIDGetter, StreamIDSetter},
+    attr::{Deserializer, Serializer, Side, StreamIDGetter, StreamIDSetter, StreamId},
+    error::TransportError,
     util,
 };
 
@@ -18,7 +19,7 @@ use super::types::FrameType;
 /// }
 pub(crate) struct StreamDataBlockedFrame {
     /// 数据流标识
-    stream_id: StreamID,
+    stream_id: StreamId,
 
     /// 数据流流量控制限制
     maximum_data: usize,
@@ -31,7 +32,7 @@ impl StreamDataBlockedFrame {
     /// 返回一个 STREAM_DATA_BLOCKED 帧
     pub(crate) fn new() -> Self {
         Self {
-            stream_id: 0,
+            stream_id: StreamId::new(0),
             maximum_data: 0,
         }
     }
@@ -53,18 +54,28 @@ impl StreamDataBlockedFrame {
     pub(crate) fn set_maximum_data(&mut self, maximum_data: usize) {
         self.maximum_data = maximum_data
     }
+
+    /// 检查流标识的方向是否允许本端接收该帧,
+    /// 本端仅发送的单向流上收到 STREAM_DATA_BLOCKED 帧时返回 STREAM_STATE_ERROR.
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    pub(crate) fn check_direction(&self, local: Side) -> Result<(), TransportError> {
+        self.stream_id
+            .check_receivable(local, u8::from(FrameType::StreamDataBlocked) as u64)
+    }
 }
 
 impl StreamIDGetter for StreamDataBlockedFrame {
     #[inline(always)]
-    fn get_stream_id(&self) -> StreamID {
+    fn get_stream_id(&self) -> StreamId {
         self.stream_id
     }
 }
 
 impl StreamIDSetter for StreamDataBlockedFrame {
     #[inline(always)]
-    fn set_stream_id(&mut self, stream_id: StreamID) {
+    fn set_stream_id(&mut self, stream_id: StreamId) {
         self.stream_id = stream_id;
     }
 }
@@ -75,7 +86,7 @@ impl Serializer for StreamDataBlockedFrame {
 
         w.write_all(&[FrameType::StreamDataBlocked.into()])?;
 
-        payload_size += util::write_varint(self.stream_id, w)?;
+        payload_size += util::write_varint(self.stream_id.get_value(), w)?;
         payload_size += util::write_varint(self.maximum_data as u64, w)?;
 
         Ok(payload_size)
@@ -87,7 +98,7 @@ impl Deserializer for StreamDataBlockedFrame {
         let mut payload_size = 0;
 
         let stream_id = util::read_varint(r)?;
-        self.stream_id = stream_id.value;
+        self.stream_id = StreamId::new(stream_id.value);
         payload_size += stream_id.size;
 
         let maximum_data = util::read_varint(r)?;
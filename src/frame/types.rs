@@ -268,6 +268,21 @@ pub enum FrameType {
     /// }
     HandshakeDone,
 
+    /// RESET_STREAM_AT 帧
+    ///
+    /// 用于重置流, 但保证可靠大小之前的数据仍被交付给对方.
+    /// 仅在双方都通告了 reset_stream_at 传输参数时使用.
+    ///
+    /// 帧结构如下:
+    /// RESET_STREAM_AT Frame {
+    ///     Type (i) = 0x24,
+    ///     Stream ID (i),
+    ///     Application Protocol Error Code (i),
+    ///     Final Size (i),
+    ///     Reliable Size (i),
+    /// }
+    ResetStreamAt,
+
     /// QUIC 扩展帧
     Extension { type_byte: u8 },
 }
@@ -307,6 +322,7 @@ impl From<u8> for FrameType {
                 sys_err: byte == 0x1c,
             },
             0x1e => Self::HandshakeDone,
+            0x24 => Self::ResetStreamAt,
             _ => Self::Extension { type_byte: byte },
         }
     }
@@ -349,6 +365,7 @@ impl From<FrameType> for u8 {
             FrameType::ConnectionClose { sys_err: true } => 0x1c,
             FrameType::ConnectionClose { sys_err: false } => 0x1d,
             FrameType::HandshakeDone => 0x1e,
+            FrameType::ResetStreamAt => 0x24,
             FrameType::Extension { type_byte } => type_byte,
         }
     }
@@ -1,8 +1,37 @@
 #[allow(dead_code)]
 mod attr;
+#[allow(dead_code, unused_imports)]
+mod cid;
+#[allow(dead_code, unused_imports)]
+mod congestion;
+#[allow(dead_code, unused_imports)]
+mod connection;
+mod crypto;
 #[allow(dead_code)]
+mod error;
+#[allow(dead_code, unused_imports)]
+mod flow_control;
+#[allow(dead_code, unused_imports)]
 mod frame;
-#[allow(dead_code)]
+#[allow(dead_code, unused_imports)]
 mod packet;
+#[allow(dead_code, unused_imports)]
+mod recovery;
+#[allow(dead_code, unused_imports)]
+mod stream;
 #[allow(dead_code)]
 mod util;
+
+pub use attr::{EcnCodepoint, Side, StreamDirection, StreamId, TransportParameters};
+pub use cid::{ConnectionIdGenerator, RandomConnectionIdGenerator};
+pub use congestion::{Bbr, BbrState, CongestionController, Cubic, NewReno};
+pub use connection::{Config, Connection, Event, Transmit};
+pub use crypto::{EncryptionLevel, HeaderKey, KeyPair, Keys, PacketKey, Random, Session};
+pub use error::{
+    ConnectionError, ConnectionIdError, StreamError, TransportError, TransportErrorCode,
+};
+pub use recovery::{RateSample, RttEstimator, SentPacket};
+pub use stream::{Priority, RecvState, SendState};
+
+#[cfg(test)]
+mod error_test;
@@ -0,0 +1,376 @@
+use std::io;
+
+use crate::{
+    attr::{PacketNumber, PacketNumberSpace, Serializer, StreamDataGetter, StreamIDGetter},
+    frame::{ACKFrame, StreamFrame},
+    recovery::SentFrame,
+    stream::{SendBuffer, SendStream},
+    util,
+};
+
+use super::header::PacketHeader;
+
+/// 携带 Initial 数据包的 UDP 数据报需要填充到的最小字节数
+pub(crate) const MIN_INITIAL_SIZE: usize = 1200;
+
+/// 头部保护从 Packet Number 起始处之后 4 字节开始采样,
+/// 因此 Packet Number 与载荷合计至少需要 4 字节
+const MIN_SAMPLE_OFFSET: usize = 4;
+
+/// 数据包组装器
+///
+/// 在给定的数据报预算内, 按调用顺序 (通常为 ACK、CRYPTO、控制帧、STREAM 帧)
+/// 贪心地填充帧: 最后一个 CRYPTO 或 STREAM 帧会被切分为恰好填满剩余空间,
+/// 位于末尾的 STREAM 帧省略 LEN 字段. 组装结束时自动计算并写入 Length 字段,
+/// 需要时在载荷前部填充 PADDING.
+///
+/// 包头的 Packet Number 及其编码长度需要在构造组装器之前设置好.
+pub(crate) struct PacketBuilder {
+    header: PacketHeader,
+
+    /// 数据包最多可占用的字节数
+    max_size: usize,
+
+    /// 数据包至少需要占用的字节数, 不足时填充 PADDING
+    min_size: usize,
+
+    /// AEAD 认证标签长度
+    tag_len: usize,
+
+    /// 包头与认证标签的开销, Length 字段按最大可能的编码长度计算
+    overhead: usize,
+
+    /// 已写入的帧
+    payload: Vec<u8>,
+
+    /// 最近加入的 STREAM 帧及其编码长度.
+    /// 组装结束时若它仍是最后一个帧, 则省略其 LEN 字段
+    last_stream: Option<(StreamFrame, usize)>,
+
+    /// 数据包中承载的帧, 用于丢包检测与重传
+    frames: Vec<SentFrame>,
+}
+
+/// 组装完成的数据包
+///
+/// 缓冲区中包含明文包头、明文载荷以及预留给 AEAD 认证标签的空间,
+/// 由调用方原地加密载荷并施加头部保护.
+pub(crate) struct AssembledPacket {
+    space: PacketNumberSpace,
+    packet_number: PacketNumber,
+    packet_number_len: usize,
+
+    /// 包头长度, 即载荷的起始偏移量
+    header_len: usize,
+
+    buf: Vec<u8>,
+    frames: Vec<SentFrame>,
+}
+
+impl PacketBuilder {
+    /// 构造一个数据包组装器, Initial 数据包会被填充到 `MIN_INITIAL_SIZE` 字节
+    ///
+    /// # Arguments
+    /// `header` - 已设置 Packet Number 的包头
+    /// `max_size` - 数据包最多可占用的字节数
+    /// `tag_len` - AEAD 认证标签长度
+    /// # Returns
+    /// 返回数据包组装器, 包头无法序列化时返回 io::Error
+    pub(crate) fn new(
+        header: PacketHeader,
+        max_size: usize,
+        tag_len: usize,
+    ) -> Result<Self, io::Error> {
+        let mut buf = Vec::new();
+        let mut overhead = header.write(&mut buf)? + tag_len;
+        if header.is_long() {
+            // 序列化时 Length 字段为 0, 仅占 1 字节
+            overhead += util::varint_len(max_size as u64) - 1;
+        }
+
+        let min_size = match header {
+            PacketHeader::Initial(_) => MIN_INITIAL_SIZE.min(max_size),
+            _ => 0,
+        };
+
+        Ok(Self {
+            header,
+            max_size,
+            min_size,
+            tag_len,
+            overhead,
+            payload: Vec::new(),
+            last_stream: None,
+            frames: Vec::new(),
+        })
+    }
+
+    /// 设置数据包至少需要占用的字节数, 不能超过最大字节数
+    ///
+    /// # Arguments
+    /// `min_size` - 最小字节数
+    #[inline(always)]
+    pub(crate) fn set_min_size(&mut self, min_size: usize) {
+        self.min_size = min_size.min(self.max_size)
+    }
+
+    /// 判断是否尚未写入任何帧
+    ///
+    /// # Returns
+    /// 返回是否为空
+    #[inline(always)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// 判断是否已写入 ack-eliciting 的帧
+    ///
+    /// # Returns
+    /// 返回数据包是否为 ack-eliciting 的数据包
+    pub(crate) fn is_ack_eliciting(&self) -> bool {
+        self.frames.iter().any(SentFrame::is_ack_eliciting)
+    }
+
+    /// 获取载荷剩余可用的字节数
+    ///
+    /// # Returns
+    /// 返回剩余可用的字节数
+    pub(crate) fn remaining(&self) -> usize {
+        let stream_len = self.last_stream.as_ref().map_or(0, |(_, len)| *len);
+        self.max_size
+            .saturating_sub(self.overhead + self.payload.len() + stream_len)
+    }
+
+    /// 写入一个完整的帧, 剩余空间不足时不写入
+    ///
+    /// # Arguments
+    /// `frame` - 帧
+    /// `sent` - 帧的发送记录
+    /// # Returns
+    /// 返回是否写入成功
+    pub(crate) fn push_frame(&mut self, frame: &dyn Serializer, sent: SentFrame) -> bool {
+        let mut buf = Vec::new();
+        if frame.write(&mut buf).is_err() || buf.len() > self.remaining() {
+            return false;
+        }
+
+        self.flush_stream();
+        self.payload.extend_from_slice(&buf);
+        self.frames.push(sent);
+        true
+    }
+
+    /// 写入 ACK 帧
+    ///
+    /// # Arguments
+    /// `frame` - ACK 帧
+    /// # Returns
+    /// 返回是否写入成功
+    pub(crate) fn push_ack(&mut self, frame: &ACKFrame) -> bool {
+        let sent = SentFrame::Ack {
+            largest: frame.get_largest(),
+        };
+        self.push_frame(frame, sent)
+    }
+
+    /// 从握手数据发送缓冲区中取出数据写入 CRYPTO 帧, 数据过多时切分以恰好填满剩余空间
+    ///
+    /// # Arguments
+    /// `buffer` - 握手数据发送缓冲区
+    /// # Returns
+    /// 返回是否写入了 CRYPTO 帧
+    pub(crate) fn push_crypto(&mut self, buffer: &mut SendBuffer) -> bool {
+        let Some(frame) = buffer.poll_crypto_frame(self.remaining()) else {
+            return false;
+        };
+
+        let (offset, data) = frame.get_data();
+        let sent = SentFrame::Crypto {
+            offset,
+            len: data.len(),
+        };
+        self.push_frame(&frame, sent)
+    }
+
+    /// 从发送流中取出数据写入 STREAM 帧, 数据过多时切分以恰好填满剩余空间
+    ///
+    /// # Arguments
+    /// `stream` - 发送流
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    /// # Returns
+    /// 返回是否写入了 STREAM 帧
+    pub(crate) fn push_stream(&mut self, stream: &mut SendStream, max_offset: usize) -> bool {
+        let Some(frame) = stream.poll_stream_frame(self.remaining(), max_offset) else {
+            return false;
+        };
+
+        let mut buf = Vec::new();
+        if frame.write(&mut buf).is_err() {
+            return false;
+        }
+
+        let (offset, data) = frame.get_data();
+        let sent = SentFrame::Stream {
+            stream_id: frame.get_stream_id(),
+            offset,
+            len: data.len(),
+            fin: frame.get_fin_flag(),
+        };
+
+        self.flush_stream();
+        self.last_stream = Some((frame, buf.len()));
+        self.frames.push(sent);
+        true
+    }
+
+    /// 完成组装: 省略末尾 STREAM 帧的 LEN 字段, 按需填充 PADDING, 并写入 Length 字段
+    ///
+    /// # Returns
+    /// 返回组装完成的数据包, 包头无法序列化时返回 io::Error
+    pub(crate) fn finish(mut self) -> Result<AssembledPacket, io::Error> {
+        let mut stream = Vec::new();
+        if let Some((mut frame, _)) = self.last_stream.take() {
+            frame.set_len_flag(false);
+            frame.write(&mut stream)?;
+        }
+
+        let packet_number_len = self.header.get_packet_number_len();
+        let content = self.payload.len() + stream.len();
+
+        // PADDING 放在载荷前部, 以便末尾的 STREAM 帧可以省略 LEN 字段
+        let mut padding = MIN_SAMPLE_OFFSET.saturating_sub(packet_number_len + content);
+        let mut padding_added = false;
+        let mut trimmed = false;
+        let mut buf = Vec::with_capacity(self.max_size);
+        loop {
+            self.header
+                .set_length(packet_number_len + content + padding + self.tag_len);
+
+            buf.clear();
+            self.header.write(&mut buf)?;
+
+            let size = buf.len() + content + padding + self.tag_len;
+            if size >= self.min_size {
+                // 填充使 Length 字段变长时会超出最小字节数, 从填充中扣除多出的字节
+                let excess = size - self.min_size;
+                if excess != 0 && excess < padding && padding_added && !trimmed {
+                    padding -= excess;
+                    trimmed = true;
+                    continue;
+                }
+                break;
+            }
+            padding += self.min_size - size;
+            padding_added = true;
+        }
+
+        let header_len = buf.len();
+        buf.resize(header_len + padding, 0);
+        buf.extend_from_slice(&self.payload);
+        buf.extend_from_slice(&stream);
+        buf.resize(buf.len() + self.tag_len, 0);
+
+        if padding != 0 {
+            self.frames.push(SentFrame::Padding);
+        }
+
+        Ok(AssembledPacket {
+            space: self.header.get_space(),
+            packet_number: self.header.get_packet_number(),
+            packet_number_len,
+            header_len,
+            buf,
+            frames: self.frames,
+        })
+    }
+
+    /// 将最近加入的 STREAM 帧带 LEN 字段写入载荷, 在其后追加其他帧之前调用
+    fn flush_stream(&mut self) {
+        if let Some((frame, _)) = self.last_stream.take() {
+            // 写入 Vec 不会失败
+            let _ = frame.write(&mut self.payload);
+        }
+    }
+}
+
+impl AssembledPacket {
+    /// 获取数据包所属的编号空间
+    ///
+    /// # Returns
+    /// 返回数据包编号空间
+    #[inline(always)]
+    pub(crate) const fn get_space(&self) -> PacketNumberSpace {
+        self.space
+    }
+
+    /// 获取 Packet Number
+    ///
+    /// # Returns
+    /// 返回 Packet Number
+    #[inline(always)]
+    pub(crate) const fn get_packet_number(&self) -> PacketNumber {
+        self.packet_number
+    }
+
+    /// 获取 Packet Number 在数据包中的偏移量, 用于头部保护
+    ///
+    /// # Returns
+    /// 返回 Packet Number 的偏移量
+    #[inline(always)]
+    pub(crate) const fn get_packet_number_offset(&self) -> usize {
+        self.header_len - self.packet_number_len
+    }
+
+    /// 获取包头长度, 即载荷的起始偏移量
+    ///
+    /// # Returns
+    /// 返回包头长度
+    #[inline(always)]
+    pub(crate) const fn get_header_len(&self) -> usize {
+        self.header_len
+    }
+
+    /// 获取数据包所占字节数, 包括包头与 AEAD 认证标签
+    ///
+    /// # Returns
+    /// 返回数据包所占字节数
+    #[inline(always)]
+    pub(crate) fn get_size(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// 获取数据包的字节
+    ///
+    /// # Returns
+    /// 返回数据包的字节
+    #[inline(always)]
+    pub(crate) fn get_buf(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// 获取可修改的数据包字节, 用于原地加密
+    ///
+    /// # Returns
+    /// 返回可修改的数据包字节
+    #[inline(always)]
+    pub(crate) fn get_buf_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+
+    /// 获取数据包中承载的帧
+    ///
+    /// # Returns
+    /// 返回数据包中承载的帧
+    #[inline(always)]
+    pub(crate) fn get_frames(&self) -> &[SentFrame] {
+        &self.frames
+    }
+
+    /// 拆分为数据包字节与承载的帧
+    ///
+    /// # Returns
+    /// 返回数据包字节与承载的帧
+    pub(crate) fn into_parts(self) -> (Vec<u8>, Vec<SentFrame>) {
+        (self.buf, self.frames)
+    }
+}
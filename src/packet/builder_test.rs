@@ -0,0 +1,142 @@
+use crate::{
+    attr::{ConnectionID, Deserializer, Serializer, Side, StreamDirection, StreamId},
+    frame::ACKFrame,
+    recovery::SentFrame,
+    stream::{SendBuffer, SendStream},
+};
+
+use super::{
+    HandshakeHeader, InitialHeader, PacketBuilder, PacketHeader, ShortHeader, MIN_INITIAL_SIZE,
+};
+
+fn conn_id(id: &[u8]) -> ConnectionID {
+    let mut conn_id = ConnectionID::new();
+    conn_id.set_id(id);
+    conn_id
+}
+
+#[test]
+fn test_builder_initial_padding() {
+    let mut header = InitialHeader::new(2);
+    header.get_header_mut().set_version(1);
+    header.get_header_mut().set_dst(conn_id(&[1; 8]));
+    header.get_header_mut().set_src(conn_id(&[2; 8]));
+    header.set_packet_number(0, 2);
+
+    let mut crypto = SendBuffer::new();
+    crypto.write(&[7; 300]);
+
+    let mut builder = PacketBuilder::new(PacketHeader::Initial(header), 1452, 16).unwrap();
+    assert!(builder.push_crypto(&mut crypto));
+    assert!(!builder.push_crypto(&mut crypto));
+
+    let packet = builder.finish().unwrap();
+    assert_eq!(packet.get_size(), MIN_INITIAL_SIZE);
+    assert_eq!(
+        packet.get_frames(),
+        &[
+            SentFrame::Crypto {
+                offset: 0,
+                len: 300
+            },
+            SentFrame::Padding
+        ]
+    );
+
+    // Length 字段覆盖 Packet Number、载荷与认证标签
+    let buf = packet.get_buf();
+    assert_eq!(buf[0], 0xc1);
+    let mut header = InitialHeader::new(2);
+    let header_len = 1 + header.read(&mut &buf[1..]).unwrap();
+    assert_eq!(header_len, packet.get_header_len());
+    assert_eq!(
+        header.get_length(),
+        buf.len() - packet.get_packet_number_offset()
+    );
+
+    // PADDING 位于载荷前部
+    assert!(buf[header_len..buf.len() - 300 - 16 - 4]
+        .iter()
+        .all(|&b| b == 0));
+}
+
+#[test]
+fn test_builder_split_crypto() {
+    let mut crypto = SendBuffer::new();
+    crypto.write(&[7; 5000]);
+
+    let mut offset = 0;
+    for pn in 0..2 {
+        let mut header = HandshakeHeader::new(1);
+        header.get_header_mut().set_dst(conn_id(&[1; 8]));
+        header.set_packet_number(pn, 1);
+
+        let mut builder = PacketBuilder::new(PacketHeader::Handshake(header), 1200, 16).unwrap();
+        assert!(builder.push_crypto(&mut crypto));
+        assert_eq!(builder.remaining(), 0);
+
+        // 最后一个 CRYPTO 帧被切分为恰好填满数据报
+        let packet = builder.finish().unwrap();
+        assert_eq!(packet.get_size(), 1200);
+        match packet.get_frames() {
+            [SentFrame::Crypto { offset: o, len }] => {
+                assert_eq!(*o, offset);
+                offset += len;
+            }
+            frames => panic!("unexpected frames {:?}", frames),
+        }
+    }
+    assert_eq!(crypto.get_sent_offset(), offset);
+}
+
+#[test]
+fn test_builder_stream_len_flag() {
+    let first = StreamId::nth(Side::Client, StreamDirection::Bidi, 0);
+    let second = StreamId::nth(Side::Client, StreamDirection::Bidi, 1);
+    let mut streams = [
+        SendStream::new(first, Side::Client),
+        SendStream::new(second, Side::Client),
+    ];
+    for stream in streams.iter_mut() {
+        stream.write(&[3; 100]).unwrap();
+        stream.finish().unwrap();
+    }
+
+    let mut header = ShortHeader::new(0, 1);
+    header.set_dst(conn_id(&[1; 8]));
+    header.set_packet_number(5, 1);
+
+    let mut ack = ACKFrame::new(false);
+    ack.set_largest(3);
+    let mut ack_buf = Vec::new();
+    let ack_len = ack.write(&mut ack_buf).unwrap();
+
+    let mut builder = PacketBuilder::new(PacketHeader::Short(header), 1200, 16).unwrap();
+    assert!(builder.push_ack(&ack));
+    for stream in streams.iter_mut() {
+        assert!(builder.push_stream(stream, usize::MAX));
+    }
+
+    let packet = builder.finish().unwrap();
+    let buf = packet.get_buf();
+    let header_len = packet.get_header_len();
+    assert_eq!(header_len, 1 + 8 + 1);
+    assert_eq!(&buf[header_len..header_len + ack_len], &ack_buf[..]);
+
+    // 第一个 STREAM 帧携带 LEN, 末尾的 STREAM 帧省略 LEN
+    let first_frame = header_len + ack_len;
+    assert_eq!(buf[first_frame], 0x0b);
+    let second_frame = first_frame + 1 + 1 + 2 + 100;
+    assert_eq!(buf[second_frame], 0x09);
+    assert_eq!(packet.get_size(), second_frame + 1 + 1 + 100 + 16);
+
+    assert_eq!(
+        packet.get_frames()[2],
+        SentFrame::Stream {
+            stream_id: second,
+            offset: 0,
+            len: 100,
+            fin: true
+        }
+    );
+}
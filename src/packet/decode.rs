@@ -0,0 +1,252 @@
+use std::io;
+
+use crate::{
+    attr::{
+        decode_packet_number, ConnectionID, Deserializer, PacketNumber, PacketNumberSpace, Version,
+    },
+    crypto::Keys,
+    util,
+};
+
+use super::{long_header::LongHeader, protection::unprotect_header};
+
+/// 收到的数据包类型
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum PacketType {
+    Initial,
+    ZeroRTT,
+    Handshake,
+    Retry,
+    VersionNegotiation,
+    Short,
+}
+
+/// 尚未解密的数据包
+///
+/// 仅解析不受头部保护的包头字段, 用于确定数据包在数据报中的边界与所需的密钥.
+/// 一个 UDP 数据报中可以合并多个长包头数据包, 短包头数据包总是延伸到数据报的末尾.
+pub(crate) struct PartialPacket {
+    packet_type: PacketType,
+    version: Version,
+    dst: ConnectionID,
+    src: ConnectionID,
+
+    /// Initial 数据包携带的 Token
+    token: Vec<u8>,
+
+    /// Packet Number 的偏移量
+    packet_number_offset: usize,
+
+    /// 数据包的长度
+    len: usize,
+}
+
+/// 解密后的数据包
+pub(crate) struct DecryptedPacket {
+    packet_number: PacketNumber,
+
+    /// 明文载荷
+    payload: Vec<u8>,
+}
+
+impl PartialPacket {
+    /// 解析数据报中的第一个数据包
+    ///
+    /// # Arguments
+    /// `buf` - 数据报中尚未处理的部分
+    /// `local_cid_len` - 本端 Connection ID 的长度, 用于解析短包头
+    /// # Returns
+    /// 返回尚未解密的数据包, 包头格式错误或长度不足时返回 io::Error
+    pub(crate) fn parse(buf: &[u8], local_cid_len: usize) -> Result<Self, io::Error> {
+        let first = *buf.first().ok_or_else(|| invalid("empty packet"))?;
+
+        if first & 0x80 == 0 {
+            let packet_number_offset = 1 + local_cid_len;
+            if buf.len() < packet_number_offset {
+                return Err(invalid("short header too short"));
+            }
+
+            let mut dst = ConnectionID::new();
+            if local_cid_len != 0 {
+                dst.set_id(&buf[1..packet_number_offset]);
+            }
+            return Ok(Self {
+                packet_type: PacketType::Short,
+                version: 0,
+                dst,
+                src: ConnectionID::new(),
+                token: Vec::new(),
+                packet_number_offset,
+                len: buf.len(),
+            });
+        }
+
+        let mut r = &buf[1..];
+        let mut header = LongHeader::new();
+        header.read(&mut r)?;
+
+        let packet_type = match (header.get_version(), (first >> 4) & 0x03) {
+            (0, _) => PacketType::VersionNegotiation,
+            (_, 0x00) => PacketType::Initial,
+            (_, 0x01) => PacketType::ZeroRTT,
+            (_, 0x02) => PacketType::Handshake,
+            _ => PacketType::Retry,
+        };
+
+        let mut token = Vec::new();
+        let len = match packet_type {
+            PacketType::VersionNegotiation | PacketType::Retry => buf.len(),
+            _ => {
+                if packet_type == PacketType::Initial {
+                    let token_len = util::read_varint(&mut r)?.value as usize;
+                    if token_len > r.len() {
+                        return Err(invalid("token too long"));
+                    }
+                    token = r[..token_len].to_vec();
+                    r = &r[token_len..];
+                }
+
+                let length = util::read_varint(&mut r)?.value as usize;
+                if length > r.len() {
+                    return Err(invalid("length exceeds datagram"));
+                }
+                buf.len() - r.len() + length
+            }
+        };
+
+        Ok(Self {
+            packet_type,
+            version: header.get_version(),
+            dst: *header.get_dst(),
+            src: *header.get_src(),
+            token,
+            packet_number_offset: buf.len() - r.len(),
+            len,
+        })
+    }
+
+    /// 获取数据包类型
+    ///
+    /// # Returns
+    /// 返回数据包类型
+    #[inline(always)]
+    pub(crate) const fn get_type(&self) -> PacketType {
+        self.packet_type
+    }
+
+    /// 获取数据包所属的编号空间
+    ///
+    /// # Returns
+    /// 返回数据包编号空间; 版本协商与 Retry 数据包没有编号空间, 返回 None
+    pub(crate) const fn get_space(&self) -> Option<PacketNumberSpace> {
+        match self.packet_type {
+            PacketType::Initial => Some(PacketNumberSpace::Initial),
+            PacketType::Handshake => Some(PacketNumberSpace::Handshake),
+            PacketType::ZeroRTT | PacketType::Short => Some(PacketNumberSpace::ApplicationData),
+            PacketType::Retry | PacketType::VersionNegotiation => None,
+        }
+    }
+
+    /// 获取版本号, 短包头数据包返回 0
+    ///
+    /// # Returns
+    /// 返回版本号
+    #[inline(always)]
+    pub(crate) const fn get_version(&self) -> Version {
+        self.version
+    }
+
+    /// 获取目标 Connection ID
+    ///
+    /// # Returns
+    /// 返回目标 Connection ID
+    #[inline(always)]
+    pub(crate) const fn get_dst(&self) -> &ConnectionID {
+        &self.dst
+    }
+
+    /// 获取源 Connection ID, 短包头数据包返回空的 Connection ID
+    ///
+    /// # Returns
+    /// 返回源 Connection ID
+    #[inline(always)]
+    pub(crate) const fn get_src(&self) -> &ConnectionID {
+        &self.src
+    }
+
+    /// 获取 Initial 数据包携带的 Token
+    ///
+    /// # Returns
+    /// 返回 Token
+    #[inline(always)]
+    pub(crate) fn get_token(&self) -> &[u8] {
+        &self.token
+    }
+
+    /// 获取数据包的长度, 即下一个合并数据包的起始偏移量
+    ///
+    /// # Returns
+    /// 返回数据包的长度
+    #[inline(always)]
+    pub(crate) const fn get_len(&self) -> usize {
+        self.len
+    }
+
+    /// 移除头部保护并解密载荷
+    ///
+    /// # Arguments
+    /// `packet` - 数据包的字节, 长度为 `get_len()`, 解密时被原地修改
+    /// `keys` - 对端方向的密钥
+    /// `largest` - 该编号空间中成功处理的最大数据包编号
+    /// # Returns
+    /// 返回解密后的数据包; 数据包过短或认证失败时返回 None
+    pub(crate) fn decrypt(
+        &self,
+        packet: &mut [u8],
+        keys: &Keys,
+        largest: Option<PacketNumber>,
+    ) -> Option<DecryptedPacket> {
+        let offset = self.packet_number_offset;
+        let packet_number_len = unprotect_header(keys.get_header(), packet, offset)?;
+
+        let header_len = offset + packet_number_len;
+        let truncated = packet
+            .get(offset..header_len)?
+            .iter()
+            .fold(0, |acc, &b| (acc << 8) | b as PacketNumber);
+        let packet_number = decode_packet_number(truncated, packet_number_len, largest);
+
+        let (header, payload) = packet.split_at_mut(header_len);
+        let len = keys.get_packet().open(packet_number, header, payload)?;
+
+        Some(DecryptedPacket {
+            packet_number,
+            payload: payload[..len].to_vec(),
+        })
+    }
+}
+
+impl DecryptedPacket {
+    /// 获取数据包编号
+    ///
+    /// # Returns
+    /// 返回数据包编号
+    #[inline(always)]
+    pub(crate) const fn get_packet_number(&self) -> PacketNumber {
+        self.packet_number
+    }
+
+    /// 获取明文载荷
+    ///
+    /// # Returns
+    /// 返回明文载荷
+    #[inline(always)]
+    pub(crate) fn get_payload(&self) -> &[u8] {
+        &self.payload
+    }
+}
+
+/// 构造包头格式错误
+fn invalid(reason: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
@@ -0,0 +1,98 @@
+use std::io;
+
+use crate::attr::{PacketNumber, PacketNumberSpace, Serializer};
+
+use super::{
+    handshake_header::HandshakeHeader, initial_header::InitialHeader, short_header::ShortHeader,
+    zero_rtt_header::ZeroRTTHeader,
+};
+
+/// 待发送数据包的包头
+///
+/// 统一长数据包头与短数据包头, 供数据包组装时设置 Length 与 Packet Number 字段.
+pub(crate) enum PacketHeader {
+    /// Initial 数据包
+    Initial(InitialHeader),
+
+    /// 0-RTT 数据包
+    ZeroRTT(ZeroRTTHeader),
+
+    /// Handshake 数据包
+    Handshake(HandshakeHeader),
+
+    /// 1-RTT 数据包
+    Short(ShortHeader),
+}
+
+impl PacketHeader {
+    /// 判断是否为长数据包头
+    ///
+    /// # Returns
+    /// 长数据包头返回 true, 短数据包头返回 false
+    #[inline(always)]
+    pub(crate) const fn is_long(&self) -> bool {
+        !matches!(self, PacketHeader::Short(_))
+    }
+
+    /// 获取数据包所属的编号空间
+    ///
+    /// # Returns
+    /// 返回数据包编号空间
+    pub(crate) const fn get_space(&self) -> PacketNumberSpace {
+        match self {
+            PacketHeader::Initial(_) => PacketNumberSpace::Initial,
+            PacketHeader::Handshake(_) => PacketNumberSpace::Handshake,
+            PacketHeader::ZeroRTT(_) | PacketHeader::Short(_) => PacketNumberSpace::ApplicationData,
+        }
+    }
+
+    /// 获取 Packet Number
+    ///
+    /// # Returns
+    /// 返回 Packet Number
+    pub(crate) const fn get_packet_number(&self) -> PacketNumber {
+        match self {
+            PacketHeader::Initial(header) => header.get_packet_number(),
+            PacketHeader::ZeroRTT(header) => header.get_packet_number(),
+            PacketHeader::Handshake(header) => header.get_packet_number(),
+            PacketHeader::Short(header) => header.get_packet_number(),
+        }
+    }
+
+    /// 获取 Packet Number 的编码长度
+    ///
+    /// # Returns
+    /// 返回 Packet Number 的编码长度
+    pub(crate) const fn get_packet_number_len(&self) -> usize {
+        match self {
+            PacketHeader::Initial(header) => header.get_packet_number_len(),
+            PacketHeader::ZeroRTT(header) => header.get_packet_number_len(),
+            PacketHeader::Handshake(header) => header.get_packet_number_len(),
+            PacketHeader::Short(header) => header.get_packet_number_len(),
+        }
+    }
+
+    /// 设置 Length 字段, 短数据包头没有该字段, 设置无效
+    ///
+    /// # Arguments
+    /// `length` - Packet Number 与载荷 (含 AEAD 认证标签) 的总长度
+    pub(crate) fn set_length(&mut self, length: usize) {
+        match self {
+            PacketHeader::Initial(header) => header.set_length(length),
+            PacketHeader::ZeroRTT(header) => header.set_length(length),
+            PacketHeader::Handshake(header) => header.set_length(length),
+            PacketHeader::Short(_) => {}
+        }
+    }
+}
+
+impl Serializer for PacketHeader {
+    fn write(&self, w: &mut dyn io::Write) -> Result<usize, io::Error> {
+        match self {
+            PacketHeader::Initial(header) => header.write(w),
+            PacketHeader::ZeroRTT(header) => header.write(w),
+            PacketHeader::Handshake(header) => header.write(w),
+            PacketHeader::Short(header) => header.write(w),
+        }
+    }
+}
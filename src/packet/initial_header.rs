@@ -1,10 +1,7 @@
 use std::io;
 
 use crate::{
-    attr::{
-        Deserializer, FixedDeserializer, FixedSerializer, PacketNumber, PacketNumberAttr,
-        Serializer,
-    },
+    attr::{Deserializer, FixedDeserializer, FixedSerializer, PacketNumber, Serializer},
     util,
 };
 
@@ -43,13 +40,98 @@ impl InitialHeader {
             packet_number_len,
         }
     }
+
+    /// 获取长数据包头的公共部分
+    ///
+    /// # Returns
+    /// 返回长数据包头
+    #[inline(always)]
+    pub(crate) const fn get_header(&self) -> &LongHeader {
+        &self.header
+    }
+
+    /// 获取可修改的长数据包头的公共部分
+    ///
+    /// # Returns
+    /// 返回可修改的长数据包头
+    #[inline(always)]
+    pub(crate) fn get_header_mut(&mut self) -> &mut LongHeader {
+        &mut self.header
+    }
+
+    /// 获取 Length 字段, 即 Packet Number 与载荷 (含 AEAD 认证标签) 的总长度
+    ///
+    /// # Returns
+    /// 返回 Length 字段
+    #[inline(always)]
+    pub(crate) const fn get_length(&self) -> usize {
+        self.length
+    }
+
+    /// 设置 Length 字段
+    ///
+    /// # Arguments
+    /// `length` - Packet Number 与载荷 (含 AEAD 认证标签) 的总长度
+    #[inline(always)]
+    pub(crate) fn set_length(&mut self, length: usize) {
+        self.length = length
+    }
+
+    /// 获取 Packet Number
+    ///
+    /// # Returns
+    /// 返回 Packet Number
+    #[inline(always)]
+    pub(crate) const fn get_packet_number(&self) -> PacketNumber {
+        self.packet_number
+    }
+
+    /// 获取 Packet Number 的编码长度
+    ///
+    /// # Returns
+    /// 返回 Packet Number 的编码长度
+    #[inline(always)]
+    pub(crate) const fn get_packet_number_len(&self) -> usize {
+        self.packet_number_len
+    }
+
+    /// 设置 Packet Number 及其编码长度, 序列化时只写入低 `packet_number_len` 字节
+    ///
+    /// # Arguments
+    /// `packet_number` - Packet Number
+    /// `packet_number_len` - 编码长度, 取值为 1 到 4
+    pub(crate) fn set_packet_number(
+        &mut self,
+        packet_number: PacketNumber,
+        packet_number_len: usize,
+    ) {
+        self.packet_number = packet_number;
+        self.packet_number_len = packet_number_len;
+    }
+
+    /// 获取 Token
+    ///
+    /// # Returns
+    /// 返回 Token
+    #[inline(always)]
+    pub(crate) fn get_token(&self) -> &[u8] {
+        &self.token
+    }
+
+    /// 设置 Token
+    ///
+    /// # Arguments
+    /// `token` - 来自 Retry 数据包或 NEW_TOKEN 帧的 Token
+    pub(crate) fn set_token(&mut self, token: &[u8]) {
+        self.token = token.to_vec()
+    }
 }
 
 impl Serializer for InitialHeader {
     fn write(&self, w: &mut dyn std::io::Write) -> Result<usize, std::io::Error> {
         let mut payload_size = 1;
 
-        let packet_number_len = self.packet_number.serialize_len()?;
+        let packet_number_len = self.packet_number_len;
         w.write_all(&[0xc0 | (packet_number_len as u8 - 1)])?;
 
         payload_size += self.header.write(w)?;
@@ -74,10 +156,8 @@ impl Deserializer for InitialHeader {
 
         let token_length = util::read_varint(r)?;
         payload_size += token_length.size;
-        self.token.resize(token_length.value as usize, 0);
-
-        r.read_exact(&mut self.token)?;
-        payload_size += token_length.value as usize;
+        self.token = util::read_bytes(r, token_length.value)?;
+        payload_size += self.token.len();
 
         let length = util::read_varint(r)?;
         payload_size += length.size;
@@ -0,0 +1,46 @@
+use crate::attr::{ConnectionID, Deserializer, Serializer};
+
+use super::InitialHeader;
+
+fn conn_id(id: &[u8]) -> ConnectionID {
+    let mut conn_id = ConnectionID::new();
+    conn_id.set_id(id);
+    conn_id
+}
+
+fn header(token: &[u8]) -> Vec<u8> {
+    let mut header = InitialHeader::new(1);
+    header.get_header_mut().set_version(1);
+    header.get_header_mut().set_dst(conn_id(&[1; 8]));
+    header.get_header_mut().set_src(conn_id(&[2; 8]));
+    header.set_token(token);
+    header.set_length(17);
+    header.set_packet_number(0, 1);
+
+    let mut buf = Vec::new();
+    header.write(&mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn test_initial_header_token() {
+    let buf = header(&[7; 4]);
+    let mut header = InitialHeader::new(1);
+    assert_eq!(header.read(&mut &buf[1..]).unwrap(), buf.len() - 1);
+    assert_eq!(header.get_token(), &[7; 4]);
+    assert_eq!(header.get_length(), 17);
+}
+
+#[test]
+fn test_initial_header_huge_token_length() {
+    // 将 Token Length 字段替换为 2^62 - 1
+    let buf = header(&[]);
+    let token_len_offset = 1 + 4 + 1 + 8 + 1 + 8;
+    assert_eq!(buf[token_len_offset], 0);
+    let mut malformed = buf[..token_len_offset].to_vec();
+    malformed.extend_from_slice(&[0xff; 8]);
+    malformed.extend_from_slice(&buf[token_len_offset + 1..]);
+
+    let mut header = InitialHeader::new(1);
+    assert!(header.read(&mut &malformed[1..]).is_err());
+}
@@ -29,6 +29,60 @@ impl LongHeader {
             src: ConnectionID::new(),
         }
     }
+
+    /// 获取版本号
+    ///
+    /// # Returns
+    /// 返回版本号
+    #[inline(always)]
+    pub(crate) const fn get_version(&self) -> Version {
+        self.version
+    }
+
+    /// 设置版本号
+    ///
+    /// # Arguments
+    /// `version` - 版本号
+    #[inline(always)]
+    pub(crate) fn set_version(&mut self, version: Version) {
+        self.version = version
+    }
+
+    /// 获取目标 Connection ID
+    ///
+    /// # Returns
+    /// 返回目标 Connection ID
+    #[inline(always)]
+    pub(crate) const fn get_dst(&self) -> &ConnectionID {
+        &self.dst
+    }
+
+    /// 设置目标 Connection ID
+    ///
+    /// # Arguments
+    /// `dst` - 目标 Connection ID
+    #[inline(always)]
+    pub(crate) fn set_dst(&mut self, dst: ConnectionID) {
+        self.dst = dst
+    }
+
+    /// 获取源 Connection ID
+    ///
+    /// # Returns
+    /// 返回源 Connection ID
+    #[inline(always)]
+    pub(crate) const fn get_src(&self) -> &ConnectionID {
+        &self.src
+    }
+
+    /// 设置源 Connection ID
+    ///
+    /// # Arguments
+    /// `src` - 源 Connection ID
+    #[inline(always)]
+    pub(crate) fn set_src(&mut self, src: ConnectionID) {
+        self.src = src
+    }
 }
 
 impl Serializer for LongHeader {
@@ -100,13 +154,21 @@ fn read_packet_header_connid(r: &mut dyn io::Read) -> Result<(usize, ConnectionI
     r.read_exact(&mut len_bytes)?;
     let len = util::from_bigendian_bytes::<1>(&len_bytes) as usize;
     payload_size += 1;
+    if len > 20 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "connection id too long",
+        ));
+    }
 
-    let mut conn_id = Vec::with_capacity(len);
-    conn_id.resize(len, 0);
+    let mut conn_id = vec![0u8; len];
     r.read_exact(&mut conn_id)?;
     payload_size += len;
 
-    ret.set_id(&conn_id);
+    // 长度为 0 的 Connection ID 保持为空
+    if len != 0 {
+        ret.set_id(&conn_id);
+    }
 
     Ok((payload_size, ret))
 }
@@ -1,4 +1,24 @@
+mod builder;
+mod decode;
 mod handshake_header;
+mod header;
 mod initial_header;
 mod long_header;
+mod protection;
+mod short_header;
 mod zero_rtt_header;
+
+pub(crate) use builder::*;
+pub(crate) use decode::*;
+pub(crate) use handshake_header::*;
+pub(crate) use header::*;
+pub(crate) use initial_header::*;
+pub(crate) use long_header::*;
+pub(crate) use protection::*;
+pub(crate) use short_header::*;
+pub(crate) use zero_rtt_header::*;
+
+#[cfg(test)]
+mod builder_test;
+#[cfg(test)]
+mod initial_header_test;
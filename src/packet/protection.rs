@@ -0,0 +1,63 @@
+use crate::crypto::HeaderKey;
+
+/// 头部保护采样相对 Packet Number 起始处的偏移量
+const SAMPLE_OFFSET: usize = 4;
+
+/// 施加头部保护
+///
+/// 以载荷采样生成的掩码保护包头首字节的低位 (长包头 4 位, 短包头 5 位)
+/// 与 Packet Number 字段.
+///
+/// # Arguments
+/// `key` - 头部保护密钥
+/// `packet` - 已加密载荷的数据包
+/// `packet_number_offset` - Packet Number 的偏移量
+/// `packet_number_len` - Packet Number 的编码长度
+pub(crate) fn protect_header(
+    key: &dyn HeaderKey,
+    packet: &mut [u8],
+    packet_number_offset: usize,
+    packet_number_len: usize,
+) {
+    let sample = packet_number_offset + SAMPLE_OFFSET;
+    let mask = key.mask(&packet[sample..sample + key.sample_len()]);
+
+    packet[0] ^= mask[0] & first_byte_mask(packet[0]);
+    for i in 0..packet_number_len {
+        packet[packet_number_offset + i] ^= mask[1 + i];
+    }
+}
+
+/// 移除头部保护
+///
+/// # Arguments
+/// `key` - 头部保护密钥
+/// `packet` - 收到的数据包
+/// `packet_number_offset` - Packet Number 的偏移量
+/// # Returns
+/// 返回 Packet Number 的编码长度; 数据包过短无法采样时返回 None
+pub(crate) fn unprotect_header(
+    key: &dyn HeaderKey,
+    packet: &mut [u8],
+    packet_number_offset: usize,
+) -> Option<usize> {
+    let sample = packet_number_offset + SAMPLE_OFFSET;
+    let mask = key.mask(packet.get(sample..sample + key.sample_len())?);
+
+    packet[0] ^= mask[0] & first_byte_mask(packet[0]);
+    let packet_number_len = (packet[0] & 0x03) as usize + 1;
+    for i in 0..packet_number_len {
+        packet[packet_number_offset + i] ^= mask[1 + i];
+    }
+
+    Some(packet_number_len)
+}
+
+/// 首字节中受头部保护的位
+fn first_byte_mask(first: u8) -> u8 {
+    if first & 0x80 != 0 {
+        0x0f
+    } else {
+        0x1f
+    }
+}
@@ -0,0 +1,173 @@
+use std::io;
+
+use crate::attr::{
+    ConnectionID, Deserializer, FixedDeserializer, FixedSerializer, PacketNumber, Serializer,
+};
+
+/// 1-RTT Packet Header (短数据包头)
+///
+/// 在 1-RTT 密钥可用后使用. 短数据包头不携带版本号、源 Connection ID 与 Length 字段,
+/// 目标 Connection ID 的长度由接收方在本地记录, 载荷延伸到 UDP 数据报的末尾.
+pub(crate) struct ShortHeader {
+    /// 延迟探测位
+    spin: bool,
+
+    /// 密钥阶段
+    key_phase: bool,
+
+    /// 目标 Connection ID
+    dst: ConnectionID,
+
+    packet_number: PacketNumber,
+
+    dst_len: usize,
+    packet_number_len: usize,
+}
+
+impl ShortHeader {
+    /// 构造 1-RTT Packet Header
+    ///
+    /// # Arguments
+    /// `dst_len` - 目标 Connection ID 长度，用于反序列化时读取 Connection ID
+    /// `packet_number_len` - Packet Number 长度，用于反序列化时读取 Packet Number
+    /// # Returns
+    /// 返回 1-RTT Packet Header
+    pub(crate) fn new(dst_len: usize, packet_number_len: usize) -> Self {
+        Self {
+            spin: false,
+            key_phase: false,
+            dst: ConnectionID::new(),
+            packet_number: 0,
+
+            dst_len,
+            packet_number_len,
+        }
+    }
+
+    /// 获取延迟探测位
+    ///
+    /// # Returns
+    /// 返回延迟探测位
+    #[inline(always)]
+    pub(crate) const fn get_spin(&self) -> bool {
+        self.spin
+    }
+
+    /// 设置延迟探测位
+    ///
+    /// # Arguments
+    /// `spin` - 延迟探测位
+    #[inline(always)]
+    pub(crate) fn set_spin(&mut self, spin: bool) {
+        self.spin = spin
+    }
+
+    /// 获取密钥阶段
+    ///
+    /// # Returns
+    /// 返回密钥阶段
+    #[inline(always)]
+    pub(crate) const fn get_key_phase(&self) -> bool {
+        self.key_phase
+    }
+
+    /// 设置密钥阶段
+    ///
+    /// # Arguments
+    /// `key_phase` - 密钥阶段
+    #[inline(always)]
+    pub(crate) fn set_key_phase(&mut self, key_phase: bool) {
+        self.key_phase = key_phase
+    }
+
+    /// 获取目标 Connection ID
+    ///
+    /// # Returns
+    /// 返回目标 Connection ID
+    #[inline(always)]
+    pub(crate) const fn get_dst(&self) -> &ConnectionID {
+        &self.dst
+    }
+
+    /// 设置目标 Connection ID
+    ///
+    /// # Arguments
+    /// `dst` - 目标 Connection ID
+    pub(crate) fn set_dst(&mut self, dst: ConnectionID) {
+        self.dst_len = dst.get_id().len();
+        self.dst = dst;
+    }
+
+    /// 获取 Packet Number
+    ///
+    /// # Returns
+    /// 返回 Packet Number
+    #[inline(always)]
+    pub(crate) const fn get_packet_number(&self) -> PacketNumber {
+        self.packet_number
+    }
+
+    /// 获取 Packet Number 的编码长度
+    ///
+    /// # Returns
+    /// 返回 Packet Number 的编码长度
+    #[inline(always)]
+    pub(crate) const fn get_packet_number_len(&self) -> usize {
+        self.packet_number_len
+    }
+
+    /// 设置 Packet Number 及其编码长度, 序列化时只写入低 `packet_number_len` 字节
+    ///
+    /// # Arguments
+    /// `packet_number` - Packet Number
+    /// `packet_number_len` - 编码长度, 取值为 1 到 4
+    pub(crate) fn set_packet_number(
+        &mut self,
+        packet_number: PacketNumber,
+        packet_number_len: usize,
+    ) {
+        self.packet_number = packet_number;
+        self.packet_number_len = packet_number_len;
+    }
+}
+
+impl Serializer for ShortHeader {
+    fn write(&self, w: &mut dyn io::Write) -> Result<usize, io::Error> {
+        let mut payload_size = 1;
+
+        let mut first_byte = 0x40 | (self.packet_number_len as u8 - 1);
+        if self.spin {
+            first_byte |= 0x20;
+        }
+        if self.key_phase {
+            first_byte |= 0x04;
+        }
+        w.write_all(&[first_byte])?;
+
+        w.write_all(self.dst.get_id())?;
+        payload_size += self.dst.get_id().len();
+
+        self.packet_number.write_fixed(self.packet_number_len, w)?;
+        payload_size += self.packet_number_len;
+
+        Ok(payload_size)
+    }
+}
+
+impl Deserializer for ShortHeader {
+    fn read(&mut self, r: &mut dyn io::Read) -> Result<usize, io::Error> {
+        let mut payload_size = 0;
+
+        let mut conn_id = vec![0u8; self.dst_len];
+        r.read_exact(&mut conn_id)?;
+        if !conn_id.is_empty() {
+            self.dst.set_id(&conn_id);
+        }
+        payload_size += self.dst_len;
+
+        self.packet_number.read_fixed(self.packet_number_len, r)?;
+        payload_size += self.packet_number_len;
+
+        Ok(payload_size)
+    }
+}
@@ -0,0 +1,247 @@
+use std::time::{Duration, Instant};
+
+use super::sent_packet::SentPacket;
+
+/// 数据包发送时记录的连接交付状态
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DeliveryState {
+    /// 发送该数据包时连接已交付的字节数
+    delivered: usize,
+
+    /// 发送该数据包时最近一次交付的时刻
+    delivered_time: Instant,
+
+    /// 发送该数据包时当前发送区间的开始时刻
+    first_sent_time: Instant,
+
+    /// 发送该数据包时连接是否受应用限制
+    is_app_limited: bool,
+}
+
+/// 交付速率样本
+///
+/// 由一次 ACK 所确认的数据包中最近发送的那个数据包计算得出,
+/// 表示从该数据包发送到被确认期间连接的平均交付速率.
+#[derive(Clone, Copy, Debug)]
+pub struct RateSample {
+    /// 交付速率, 以 字节/秒 为单位
+    delivery_rate: u64,
+
+    /// 采样区间内交付的字节数
+    delivered: usize,
+
+    /// 采样数据包发送时连接已交付的字节数
+    prior_delivered: usize,
+
+    /// 连接当前已交付的字节数
+    total_delivered: usize,
+
+    /// 采样区间时长
+    interval: Duration,
+
+    /// 采样数据包发送时连接是否受应用限制
+    is_app_limited: bool,
+
+    /// 处理本次 ACK 之后的在途字节数
+    bytes_in_flight: usize,
+}
+
+impl RateSample {
+    /// 获取交付速率
+    ///
+    /// # Returns
+    /// 返回以 字节/秒 为单位的交付速率
+    #[inline(always)]
+    pub const fn get_delivery_rate(&self) -> u64 {
+        self.delivery_rate
+    }
+
+    /// 获取采样区间内交付的字节数
+    ///
+    /// # Returns
+    /// 返回交付的字节数
+    #[inline(always)]
+    pub const fn get_delivered(&self) -> usize {
+        self.delivered
+    }
+
+    /// 获取采样数据包发送时连接已交付的字节数
+    ///
+    /// # Returns
+    /// 返回已交付的字节数
+    #[inline(always)]
+    pub const fn get_prior_delivered(&self) -> usize {
+        self.prior_delivered
+    }
+
+    /// 获取连接当前已交付的字节数
+    ///
+    /// # Returns
+    /// 返回已交付的字节数
+    #[inline(always)]
+    pub const fn get_total_delivered(&self) -> usize {
+        self.total_delivered
+    }
+
+    /// 获取采样区间时长
+    ///
+    /// # Returns
+    /// 返回采样区间时长
+    #[inline(always)]
+    pub const fn get_interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 获取采样是否受应用限制
+    ///
+    /// 受应用限制的样本可能低估链路带宽.
+    ///
+    /// # Returns
+    /// 返回采样是否受应用限制
+    #[inline(always)]
+    pub const fn is_app_limited(&self) -> bool {
+        self.is_app_limited
+    }
+
+    /// 获取处理本次 ACK 之后的在途字节数
+    ///
+    /// # Returns
+    /// 返回在途字节数
+    #[inline(always)]
+    pub const fn get_bytes_in_flight(&self) -> usize {
+        self.bytes_in_flight
+    }
+}
+
+/// 交付速率采样
+///
+/// 在每个数据包发送时记录连接的交付状态, 数据包被确认时
+/// 以两次记录之间交付的字节数除以经过的时长得到交付速率.
+pub(crate) struct DeliveryRateSampler {
+    /// 连接已交付的字节数
+    delivered: usize,
+
+    /// 最近一次交付的时刻
+    delivered_time: Option<Instant>,
+
+    /// 当前发送区间的开始时刻
+    first_sent_time: Option<Instant>,
+
+    /// 受应用限制阶段结束时的交付字节数; 为 0 时表示不受应用限制
+    app_limited: usize,
+
+    /// 本次 ACK 中用于采样的数据包的交付状态
+    sample: Option<(DeliveryState, Instant)>,
+}
+
+impl DeliveryRateSampler {
+    /// 构造一个交付速率采样
+    ///
+    /// # Returns
+    /// 返回一个交付速率采样
+    pub(crate) fn new() -> Self {
+        Self {
+            delivered: 0,
+            delivered_time: None,
+            first_sent_time: None,
+            app_limited: 0,
+            sample: None,
+        }
+    }
+
+    /// 记录数据包发送时的交付状态
+    ///
+    /// # Arguments
+    /// `packet` - 已发送的数据包
+    /// `bytes_in_flight` - 发送该数据包之前的在途字节数
+    pub(crate) fn on_packet_sent(&mut self, packet: &mut SentPacket, bytes_in_flight: usize) {
+        let now = packet.get_time_sent();
+
+        // 没有在途数据时, 从该数据包开始新的发送区间.
+        if bytes_in_flight == 0 {
+            self.first_sent_time = Some(now);
+            self.delivered_time = Some(now);
+        }
+
+        packet.set_delivery_state(DeliveryState {
+            delivered: self.delivered,
+            delivered_time: self.delivered_time.unwrap_or(now),
+            first_sent_time: self.first_sent_time.unwrap_or(now),
+            is_app_limited: self.app_limited != 0,
+        });
+    }
+
+    /// 处理被确认的数据包
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `packet` - 被确认的数据包
+    pub(crate) fn on_packet_acked(&mut self, now: Instant, packet: &SentPacket) {
+        let state = match packet.get_delivery_state() {
+            Some(state) => state,
+            None => return,
+        };
+
+        self.delivered += packet.get_size();
+        self.delivered_time = Some(now);
+
+        // 使用本次 ACK 中最近发送的数据包计算样本.
+        if self
+            .sample
+            .is_none_or(|(sample, _)| state.delivered >= sample.delivered)
+        {
+            self.sample = Some((state, packet.get_time_sent()));
+            self.first_sent_time = Some(packet.get_time_sent());
+        }
+
+        if self.app_limited != 0 && self.delivered > self.app_limited {
+            self.app_limited = 0;
+        }
+    }
+
+    /// 标记连接受应用限制, 即没有更多可发送的数据
+    ///
+    /// # Arguments
+    /// `bytes_in_flight` - 当前在途字节数
+    pub(crate) fn on_app_limited(&mut self, bytes_in_flight: usize) {
+        self.app_limited = (self.delivered + bytes_in_flight).max(1);
+    }
+
+    /// 生成本次 ACK 的交付速率样本
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `min_rtt` - 最小 RTT; 采样区间短于 min_rtt 的样本不可信, 会被丢弃
+    /// `bytes_in_flight` - 处理本次 ACK 之后的在途字节数
+    /// # Returns
+    /// 返回交付速率样本; 若本次 ACK 无法生成有效样本, 则返回 None
+    pub(crate) fn take_sample(
+        &mut self,
+        now: Instant,
+        min_rtt: Duration,
+        bytes_in_flight: usize,
+    ) -> Option<RateSample> {
+        let (state, time_sent) = self.sample.take()?;
+
+        let send_elapsed = time_sent.saturating_duration_since(state.first_sent_time);
+        let ack_elapsed = now.saturating_duration_since(state.delivered_time);
+        let interval = send_elapsed.max(ack_elapsed);
+        if interval.is_zero() || interval < min_rtt {
+            return None;
+        }
+
+        let delivered = self.delivered - state.delivered;
+        // 以纳秒计算, 不足 1 微秒的区间也不会除以 0
+        let delivery_rate = (delivered as u128 * 1_000_000_000 / interval.as_nanos()) as u64;
+
+        Some(RateSample {
+            delivery_rate,
+            delivered,
+            prior_delivered: state.delivered,
+            total_delivered: self.delivered,
+            interval,
+            is_app_limited: state.is_app_limited,
+            bytes_in_flight,
+        })
+    }
+}
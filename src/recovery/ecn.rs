@@ -0,0 +1,244 @@
+use crate::{
+    attr::{EcnCodepoint, PacketNumberSpace},
+    frame::ECNCounts,
+};
+
+/// 验证阶段发送的 ECT(0) 数据包数量
+const TESTING_PACKETS: u64 = 10;
+
+/// 接收端的 ECN 码点计数
+///
+/// 按数据包编号空间统计收到的数据包所携带的 ECN 码点,
+/// 用于在 ACK 帧中填写 ECN Counts.
+pub(crate) struct ReceivedEcnCounts {
+    /// 各编号空间中的计数
+    counts: [ECNCounts; 3],
+
+    /// 各编号空间中是否收到过携带 ECN 码点的数据包
+    marked: [bool; 3],
+}
+
+impl ReceivedEcnCounts {
+    /// 构造接收端的 ECN 码点计数
+    ///
+    /// # Returns
+    /// 返回接收端的 ECN 码点计数
+    pub(crate) fn new() -> Self {
+        Self {
+            counts: [ECNCounts::default(); 3],
+            marked: [false; 3],
+        }
+    }
+
+    /// 记录一个成功处理的数据包所携带的 ECN 码点
+    ///
+    /// # Arguments
+    /// `space` - 数据包所在的编号空间
+    /// `ecn` - 数据包携带的 ECN 码点
+    pub(crate) fn on_packet_received(&mut self, space: PacketNumberSpace, ecn: EcnCodepoint) {
+        let counts = &mut self.counts[space.index()];
+        *counts = match ecn {
+            EcnCodepoint::NotEct => return,
+            EcnCodepoint::Ect0 => ECNCounts::new(
+                counts.get_ect0() + 1,
+                counts.get_ect1(),
+                counts.get_ecn_ce(),
+            ),
+            EcnCodepoint::Ect1 => ECNCounts::new(
+                counts.get_ect0(),
+                counts.get_ect1() + 1,
+                counts.get_ecn_ce(),
+            ),
+            EcnCodepoint::Ce => ECNCounts::new(
+                counts.get_ect0(),
+                counts.get_ect1(),
+                counts.get_ecn_ce() + 1,
+            ),
+        };
+        self.marked[space.index()] = true;
+    }
+
+    /// 获取 ACK 帧中需要填写的 ECN Counts
+    ///
+    /// # Arguments
+    /// `space` - 编号空间
+    /// # Returns
+    /// 返回 ECN Counts; 若该空间未收到过携带 ECN 码点的数据包, 则返回 None
+    pub(crate) fn get_counts(&self, space: PacketNumberSpace) -> Option<ECNCounts> {
+        if self.marked[space.index()] {
+            Some(self.counts[space.index()])
+        } else {
+            None
+        }
+    }
+}
+
+/// 发送端 ECN 验证的状态
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum EcnState {
+    /// 正在发送 ECT(0) 数据包以验证路径
+    Testing,
+
+    /// 验证阶段已发送足够的数据包, 等待 ACK 确认验证结果
+    Unknown,
+
+    /// 路径支持 ECN
+    Capable,
+
+    /// 路径或对方不支持 ECN, 不再发送 ECT 数据包
+    Failed,
+}
+
+/// 发送端 ECN 验证
+///
+/// 连接开始时发送若干 ECT(0) 数据包, 并通过对方 ACK 帧中的 ECN Counts 验证路径.
+/// 若 ECN Counts 缺失、减少、与被确认的 ECT 数据包数量不符, 或全部 ECT 数据包都丢失
+/// (码点被路径清除或携带码点的数据包被丢弃), 则停止使用 ECN.
+pub(crate) struct EcnValidator {
+    /// 验证状态
+    state: EcnState,
+
+    /// 验证阶段已发送的 ECT 数据包数量
+    testing_sent: u64,
+
+    /// 各编号空间中已发送的 ECT 数据包数量
+    sent: [u64; 3],
+
+    /// 各编号空间中已验证的对方 ECN Counts
+    peer_counts: [ECNCounts; 3],
+
+    /// 被确认的 ECT 数据包数量
+    acked: u64,
+
+    /// 丢失的 ECT 数据包数量
+    lost: u64,
+}
+
+impl EcnValidator {
+    /// 构造发送端 ECN 验证
+    ///
+    /// # Returns
+    /// 返回发送端 ECN 验证
+    pub(crate) fn new() -> Self {
+        Self {
+            state: EcnState::Testing,
+            testing_sent: 0,
+            sent: [0; 3],
+            peer_counts: [ECNCounts::default(); 3],
+            acked: 0,
+            lost: 0,
+        }
+    }
+
+    /// 获取验证状态
+    ///
+    /// # Returns
+    /// 返回验证状态
+    #[inline(always)]
+    pub(crate) const fn get_state(&self) -> EcnState {
+        self.state
+    }
+
+    /// 获取下一个数据包应使用的 ECN 码点
+    ///
+    /// # Returns
+    /// 返回 ECN 码点
+    pub(crate) const fn get_codepoint(&self) -> EcnCodepoint {
+        match self.state {
+            EcnState::Testing | EcnState::Capable => EcnCodepoint::Ect0,
+            EcnState::Unknown | EcnState::Failed => EcnCodepoint::NotEct,
+        }
+    }
+
+    /// 记录一个已发送的数据包
+    ///
+    /// # Arguments
+    /// `space` - 数据包所在的编号空间
+    /// `ecn` - 数据包使用的 ECN 码点
+    pub(crate) fn on_packet_sent(&mut self, space: PacketNumberSpace, ecn: EcnCodepoint) {
+        if ecn == EcnCodepoint::NotEct {
+            return;
+        }
+        self.sent[space.index()] += 1;
+
+        if self.state == EcnState::Testing {
+            self.testing_sent += 1;
+            if self.testing_sent >= TESTING_PACKETS {
+                self.state = EcnState::Unknown;
+            }
+        }
+    }
+
+    /// 处理 ACK 帧中的 ECN Counts
+    ///
+    /// # Arguments
+    /// `space` - ACK 帧所在的编号空间
+    /// `newly_acked` - 本次新确认的 ECT 数据包数量
+    /// `counts` - ACK 帧中的 ECN Counts
+    /// # Returns
+    /// 返回 ECN-CE 计数是否增加, 即是否需要作为拥塞事件处理
+    pub(crate) fn on_ack_received(
+        &mut self,
+        space: PacketNumberSpace,
+        newly_acked: u64,
+        counts: Option<ECNCounts>,
+    ) -> bool {
+        if self.state == EcnState::Failed {
+            return false;
+        }
+        self.acked += newly_acked;
+
+        let counts = match counts {
+            Some(counts) => counts,
+            None => {
+                // 确认了 ECT 数据包却没有携带 ECN Counts.
+                if newly_acked != 0 {
+                    self.state = EcnState::Failed;
+                }
+                return false;
+            }
+        };
+
+        let prev = self.peer_counts[space.index()];
+        if counts.get_ect0() < prev.get_ect0()
+            || counts.get_ect1() < prev.get_ect1()
+            || counts.get_ecn_ce() < prev.get_ecn_ce()
+        {
+            self.state = EcnState::Failed;
+            return false;
+        }
+
+        let ect0_increase = counts.get_ect0() - prev.get_ect0();
+        let ect1_increase = counts.get_ect1() - prev.get_ect1();
+        let ce_increase = counts.get_ecn_ce() - prev.get_ecn_ce();
+
+        // 本端只发送 ECT(0), 计数之和也不能超过已发送的 ECT 数据包数量.
+        if ect1_increase != 0
+            || ect0_increase + ce_increase < newly_acked
+            || counts.get_ect0() + counts.get_ecn_ce() > self.sent[space.index()]
+        {
+            self.state = EcnState::Failed;
+            return false;
+        }
+
+        self.peer_counts[space.index()] = counts;
+        if newly_acked != 0 && self.state != EcnState::Capable {
+            self.state = EcnState::Capable;
+        }
+
+        ce_increase != 0
+    }
+
+    /// 记录丢失的 ECT 数据包
+    ///
+    /// # Arguments
+    /// `lost` - 丢失的 ECT 数据包数量
+    pub(crate) fn on_packets_lost(&mut self, lost: u64) {
+        self.lost += lost;
+
+        // 验证阶段发送的 ECT 数据包全部丢失, 可能是路径丢弃了携带 ECN 码点的数据包.
+        if self.state == EcnState::Unknown && self.acked == 0 && self.lost >= self.testing_sent {
+            self.state = EcnState::Failed;
+        }
+    }
+}
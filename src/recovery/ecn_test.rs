@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    attr::{EcnCodepoint, PacketNumberSpace},
+    congestion::NewReno,
+    frame::{ACKFrame, ECNCounts},
+};
+
+use super::{EcnState, EcnValidator, LossDetector, ReceivedEcnCounts, SentFrame, SentPacket};
+
+#[test]
+fn test_received_ecn_counts() {
+    let mut counts = ReceivedEcnCounts::new();
+    let space = PacketNumberSpace::ApplicationData;
+
+    counts.on_packet_received(space, EcnCodepoint::NotEct);
+    assert_eq!(counts.get_counts(space), None);
+
+    counts.on_packet_received(space, EcnCodepoint::Ect0);
+    counts.on_packet_received(space, EcnCodepoint::Ect0);
+    counts.on_packet_received(space, EcnCodepoint::Ce);
+    assert_eq!(counts.get_counts(space), Some(ECNCounts::new(2, 0, 1)));
+    assert_eq!(counts.get_counts(PacketNumberSpace::Initial), None);
+}
+
+#[test]
+fn test_ecn_validation() {
+    let space = PacketNumberSpace::ApplicationData;
+
+    let mut ecn = EcnValidator::new();
+    for _ in 0..2 {
+        ecn.on_packet_sent(space, ecn.get_codepoint());
+    }
+    assert!(!ecn.on_ack_received(space, 2, Some(ECNCounts::new(2, 0, 0))));
+    assert_eq!(ecn.get_state(), EcnState::Capable);
+
+    // ECN-CE 增加.
+    ecn.on_packet_sent(space, ecn.get_codepoint());
+    assert!(ecn.on_ack_received(space, 1, Some(ECNCounts::new(2, 0, 1))));
+
+    // 码点被路径清除, ACK 中不再携带 ECN Counts.
+    let mut ecn = EcnValidator::new();
+    ecn.on_packet_sent(space, ecn.get_codepoint());
+    ecn.on_ack_received(space, 1, None);
+    assert_eq!(ecn.get_state(), EcnState::Failed);
+    assert_eq!(ecn.get_codepoint(), EcnCodepoint::NotEct);
+
+    // 计数少于被确认的 ECT 数据包.
+    let mut ecn = EcnValidator::new();
+    ecn.on_packet_sent(space, ecn.get_codepoint());
+    ecn.on_packet_sent(space, ecn.get_codepoint());
+    ecn.on_ack_received(space, 2, Some(ECNCounts::new(1, 0, 0)));
+    assert_eq!(ecn.get_state(), EcnState::Failed);
+
+    // 验证阶段的 ECT 数据包全部丢失.
+    let mut ecn = EcnValidator::new();
+    for _ in 0..10 {
+        ecn.on_packet_sent(space, ecn.get_codepoint());
+    }
+    assert_eq!(ecn.get_state(), EcnState::Unknown);
+    ecn.on_packets_lost(10);
+    assert_eq!(ecn.get_state(), EcnState::Failed);
+}
+
+#[test]
+fn test_ecn_ce_congestion_event() {
+    let start = Instant::now();
+    let space = PacketNumberSpace::ApplicationData;
+    let mut detector = LossDetector::new(
+        true,
+        Duration::from_millis(25),
+        Box::new(NewReno::new(1200)),
+    );
+
+    let mut packet = SentPacket::new(0, start, 1200, vec![SentFrame::Ping]);
+    packet.set_ecn(detector.get_ecn_codepoint());
+    detector.on_packet_sent(space, packet);
+
+    let mut ack = ACKFrame::new(true);
+    ack.set_ecn(ECNCounts::new(0, 0, 1));
+    detector
+        .on_ack_received(start + Duration::from_millis(10), space, &ack)
+        .unwrap();
+
+    assert_eq!(detector.get_congestion_window(), 6600);
+}
@@ -0,0 +1,806 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    attr::{EcnCodepoint, PacketNumber, PacketNumberSpace, DEFAULT_ACK_DELAY_EXPONENT},
+    congestion::CongestionController,
+    error::{TransportError, TransportErrorCode},
+    frame::{ACKFrame, FrameType},
+};
+
+use super::{
+    delivery_rate::DeliveryRateSampler,
+    ecn::EcnValidator,
+    rtt::{RttEstimator, GRANULARITY},
+    sent_packet::{SentFrame, SentPacket},
+};
+
+/// 丢包判定的数据包阈值
+///
+/// 当一个数据包之后已有 3 个编号更大的数据包被确认时, 判定该数据包丢失.
+pub(crate) const PACKET_THRESHOLD: u64 = 3;
+
+/// 丢包判定的时间阈值 (9/8 RTT) 的分子
+const TIME_THRESHOLD_NUMERATOR: u32 = 9;
+
+/// 丢包判定的时间阈值 (9/8 RTT) 的分母
+const TIME_THRESHOLD_DENOMINATOR: u32 = 8;
+
+/// PTO 超时后在对应编号空间中发送的探测包数量
+const PTO_PROBES: usize = 2;
+
+/// 持续拥塞判定阈值
+///
+/// 丢失的 ack-eliciting 数据包跨越的时长超过 3 倍 PTO 时, 判定为持续拥塞.
+const PERSISTENT_CONGESTION_THRESHOLD: u32 = 3;
+
+/// 单个数据包编号空间中的丢包检测状态
+struct SpaceState {
+    /// 尚未被确认或判定丢失的已发送数据包
+    sent_packets: BTreeMap<PacketNumber, SentPacket>,
+
+    /// 该空间中已发送的最大数据包编号
+    largest_sent: Option<PacketNumber>,
+
+    /// 该空间中被确认的最大数据包编号
+    largest_acked: Option<PacketNumber>,
+
+    /// 下一个数据包将因时间阈值被判定丢失的时刻
+    loss_time: Option<Instant>,
+
+    /// 最近一次发送 ack-eliciting 数据包的时刻
+    time_of_last_ack_eliciting_packet: Option<Instant>,
+
+    /// 仍在传输中的 ack-eliciting 数据包数量
+    ack_eliciting_in_flight: usize,
+
+    /// 待发送的探测包数量
+    probes: usize,
+}
+
+impl SpaceState {
+    fn new() -> Self {
+        Self {
+            sent_packets: BTreeMap::new(),
+            largest_sent: None,
+            largest_acked: None,
+            loss_time: None,
+            time_of_last_ack_eliciting_packet: None,
+            ack_eliciting_in_flight: 0,
+            probes: 0,
+        }
+    }
+
+    /// 从已发送数据包中移除一个数据包
+    ///
+    /// # Arguments
+    /// `packet_number` - 数据包编号
+    /// # Returns
+    /// 返回被移除的数据包
+    fn remove(&mut self, packet_number: PacketNumber) -> Option<SentPacket> {
+        let packet = self.sent_packets.remove(&packet_number)?;
+        if packet.is_in_flight() && packet.is_ack_eliciting() {
+            self.ack_eliciting_in_flight -= 1;
+        }
+
+        Some(packet)
+    }
+}
+
+/// 丢包检测
+///
+/// 按数据包编号空间记录已发送的数据包, 处理收到的 ACK 帧,
+/// 依据数据包阈值与时间阈值判定丢包, 并维护 PTO 计时器.
+pub(crate) struct LossDetector {
+    /// 各数据包编号空间的状态
+    spaces: [SpaceState; 3],
+
+    /// RTT 估算
+    rtt: RttEstimator,
+
+    /// 获得首个 RTT 样本的时刻
+    first_rtt_sample: Option<Instant>,
+
+    /// 拥塞控制
+    congestion: Box<dyn CongestionController>,
+
+    /// 交付速率采样
+    delivery_rate: DeliveryRateSampler,
+
+    /// 发送端 ECN 验证
+    ecn: EcnValidator,
+
+    /// 连续 PTO 超时的次数, 用于指数退避
+    pto_count: u32,
+
+    /// 丢包检测计时器
+    loss_detection_timer: Option<Instant>,
+
+    /// 对方延迟发送 ACK 的最大时长
+    max_ack_delay: Duration,
+
+    /// 对方用于编码 ACK Delay 的指数
+    ack_delay_exponent: u8,
+
+    /// 是否已获得 Handshake 密钥
+    has_handshake_keys: bool,
+
+    /// 握手是否已确认
+    handshake_confirmed: bool,
+
+    /// 对方是否已完成对本端地址的验证
+    ///
+    /// 服务端总是认为客户端已完成地址验证.
+    peer_completed_address_validation: bool,
+
+    /// 计入拥塞控制的在途字节数
+    bytes_in_flight: usize,
+}
+
+impl LossDetector {
+    /// 构造一个丢包检测
+    ///
+    /// # Arguments
+    /// `is_server` - 本端是否是服务端
+    /// `max_ack_delay` - 对方延迟发送 ACK 的最大时长
+    /// `congestion` - 拥塞控制
+    /// # Returns
+    /// 返回一个丢包检测
+    pub(crate) fn new(
+        is_server: bool,
+        max_ack_delay: Duration,
+        congestion: Box<dyn CongestionController>,
+    ) -> Self {
+        Self {
+            spaces: [SpaceState::new(), SpaceState::new(), SpaceState::new()],
+            rtt: RttEstimator::new(),
+            first_rtt_sample: None,
+            congestion,
+            delivery_rate: DeliveryRateSampler::new(),
+            ecn: EcnValidator::new(),
+            pto_count: 0,
+            loss_detection_timer: None,
+            max_ack_delay,
+            ack_delay_exponent: DEFAULT_ACK_DELAY_EXPONENT,
+            has_handshake_keys: false,
+            handshake_confirmed: false,
+            peer_completed_address_validation: is_server,
+            bytes_in_flight: 0,
+        }
+    }
+
+    /// 获取 RTT 估算
+    ///
+    /// # Returns
+    /// 返回 RTT 估算
+    #[inline(always)]
+    pub(crate) const fn get_rtt(&self) -> &RttEstimator {
+        &self.rtt
+    }
+
+    /// 获取拥塞控制
+    ///
+    /// # Returns
+    /// 返回拥塞控制
+    #[inline(always)]
+    pub(crate) fn get_congestion(&self) -> &dyn CongestionController {
+        self.congestion.as_ref()
+    }
+
+    /// 获取拥塞窗口
+    ///
+    /// # Returns
+    /// 返回拥塞窗口
+    #[inline(always)]
+    pub(crate) fn get_congestion_window(&self) -> usize {
+        self.congestion.get_window()
+    }
+
+    /// 获取发送端 ECN 验证
+    ///
+    /// # Returns
+    /// 返回发送端 ECN 验证
+    #[inline(always)]
+    pub(crate) const fn get_ecn(&self) -> &EcnValidator {
+        &self.ecn
+    }
+
+    /// 获取下一个数据包应使用的 ECN 码点
+    ///
+    /// # Returns
+    /// 返回 ECN 码点
+    #[inline(always)]
+    pub(crate) const fn get_ecn_codepoint(&self) -> EcnCodepoint {
+        self.ecn.get_codepoint()
+    }
+
+    /// 获取在途字节数
+    ///
+    /// # Returns
+    /// 返回在途字节数
+    #[inline(always)]
+    pub(crate) const fn get_bytes_in_flight(&self) -> usize {
+        self.bytes_in_flight
+    }
+
+    /// 获取连续 PTO 超时的次数
+    ///
+    /// # Returns
+    /// 返回连续 PTO 超时的次数
+    #[inline(always)]
+    pub(crate) const fn get_pto_count(&self) -> u32 {
+        self.pto_count
+    }
+
+    /// 获取丢包检测计时器的到期时刻
+    ///
+    /// # Returns
+    /// 返回计时器到期时刻; 若计时器未启动, 则返回 None
+    #[inline(always)]
+    pub(crate) const fn get_loss_detection_timer(&self) -> Option<Instant> {
+        self.loss_detection_timer
+    }
+
+    /// 获取编号空间中被确认的最大数据包编号, 用于选择 Packet Number 的编码长度
+    ///
+    /// # Arguments
+    /// `space` - 编号空间
+    /// # Returns
+    /// 返回被确认的最大数据包编号; 尚无数据包被确认时返回 None
+    #[inline(always)]
+    pub(crate) const fn get_largest_acked(&self, space: PacketNumberSpace) -> Option<PacketNumber> {
+        self.spaces[space.index()].largest_acked
+    }
+
+    /// 获取因时间阈值而待判定丢包的编号空间,
+    /// 计时器到期时 `on_loss_detection_timeout` 返回的数据包即属于该空间
+    ///
+    /// # Returns
+    /// 返回编号空间; 计时器为 PTO 计时器时返回 None
+    pub(crate) fn get_loss_space(&self) -> Option<PacketNumberSpace> {
+        self.get_loss_time_and_space().map(|(_, space)| space)
+    }
+
+    /// 设置对方用于编码 ACK Delay 的指数
+    ///
+    /// # Arguments
+    /// `ack_delay_exponent` - 对方通告的 ack_delay_exponent 传输参数
+    #[inline(always)]
+    pub(crate) fn set_ack_delay_exponent(&mut self, ack_delay_exponent: u8) {
+        self.ack_delay_exponent = ack_delay_exponent
+    }
+
+    /// 设置对方延迟发送 ACK 的最大时长
+    ///
+    /// # Arguments
+    /// `max_ack_delay` - 对方通告的 max_ack_delay 传输参数
+    #[inline(always)]
+    pub(crate) fn set_max_ack_delay(&mut self, max_ack_delay: Duration) {
+        self.max_ack_delay = max_ack_delay
+    }
+
+    /// 标记连接受应用限制, 即拥塞窗口未用满但已没有可发送的数据
+    #[inline(always)]
+    pub(crate) fn on_app_limited(&mut self) {
+        self.delivery_rate.on_app_limited(self.bytes_in_flight)
+    }
+
+    /// 连接迁移到新的网络路径, 新路径的 RTT 与可用带宽未知,
+    /// 将 RTT 估算与拥塞控制恢复到初始状态 (RFC 9000 §9.4)
+    pub(crate) fn on_path_changed(&mut self) {
+        self.rtt = RttEstimator::new();
+        self.first_rtt_sample = None;
+        self.congestion.reset();
+    }
+
+    /// 标记已获得 Handshake 密钥
+    #[inline(always)]
+    pub(crate) fn set_handshake_keys_available(&mut self) {
+        self.has_handshake_keys = true
+    }
+
+    /// 标记握手已确认
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    pub(crate) fn set_handshake_confirmed(&mut self, now: Instant) {
+        self.handshake_confirmed = true;
+        self.set_loss_detection_timer(now);
+    }
+
+    /// 标记对方已完成对本端地址的验证
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    pub(crate) fn set_peer_completed_address_validation(&mut self, now: Instant) {
+        self.peer_completed_address_validation = true;
+        self.set_loss_detection_timer(now);
+    }
+
+    /// 记录一个已发送的数据包
+    ///
+    /// # Arguments
+    /// `space` - 数据包所在的编号空间
+    /// `packet` - 已发送的数据包
+    pub(crate) fn on_packet_sent(&mut self, space: PacketNumberSpace, mut packet: SentPacket) {
+        let now = packet.get_time_sent();
+        let in_flight = packet.is_in_flight();
+        packet.set_space(space);
+
+        self.ecn.on_packet_sent(space, packet.get_ecn());
+
+        let state = &mut self.spaces[space.index()];
+        state.largest_sent = Some(packet.get_packet_number());
+        if in_flight {
+            if packet.is_ack_eliciting() {
+                state.time_of_last_ack_eliciting_packet = Some(now);
+                state.ack_eliciting_in_flight += 1;
+            }
+            self.delivery_rate
+                .on_packet_sent(&mut packet, self.bytes_in_flight);
+            self.bytes_in_flight += packet.get_size();
+            self.congestion
+                .on_packet_sent(&packet, self.bytes_in_flight);
+        }
+        state
+            .sent_packets
+            .insert(packet.get_packet_number(), packet);
+
+        if in_flight {
+            self.set_loss_detection_timer(now);
+        }
+    }
+
+    /// 处理收到的 ACK 帧
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `space` - ACK 帧所在的编号空间
+    /// `ack` - ACK 帧
+    /// # Returns
+    /// `0` - 新确认的数据包, 按数据包编号递增排列
+    /// `1` - 因本次确认而被判定丢失的数据包
+    /// 若 ACK 帧中的范围不合法, 则返回 FRAME_ENCODING_ERROR;
+    /// 若确认了从未发送的数据包, 则返回 PROTOCOL_VIOLATION (RFC 9000 §13.1).
+    pub(crate) fn on_ack_received(
+        &mut self,
+        now: Instant,
+        space: PacketNumberSpace,
+        ack: &ACKFrame,
+    ) -> Result<(Vec<SentPacket>, Vec<SentPacket>), TransportError> {
+        let frame_type = u8::from(FrameType::Ack {
+            with_ecm: ack.get_ecn().is_some(),
+        }) as u64;
+        let ranges = ack.get_acked_ranges().map_err(|_| {
+            TransportError::new(
+                TransportErrorCode::FrameEncodingError,
+                frame_type,
+                "invalid ack ranges",
+            )
+        })?;
+
+        let state = &mut self.spaces[space.index()];
+        if state
+            .largest_sent
+            .is_none_or(|largest| ack.get_largest() > largest)
+        {
+            return Err(TransportError::new(
+                TransportErrorCode::ProtocolViolation,
+                frame_type,
+                "ack of unsent packet",
+            ));
+        }
+        state.largest_acked = Some(
+            state
+                .largest_acked
+                .map_or(ack.get_largest(), |largest| largest.max(ack.get_largest())),
+        );
+
+        let mut acked = Vec::new();
+        for range in ranges {
+            let packet_numbers: Vec<PacketNumber> = state
+                .sent_packets
+                .range(range)
+                .map(|(packet_number, _)| *packet_number)
+                .collect();
+            for packet_number in packet_numbers {
+                if let Some(packet) = state.remove(packet_number) {
+                    acked.push(packet);
+                }
+            }
+        }
+
+        if acked.is_empty() {
+            return Ok((acked, Vec::new()));
+        }
+        acked.sort_by_key(SentPacket::get_packet_number);
+
+        for packet in acked.iter().filter(|packet| packet.is_in_flight()) {
+            self.bytes_in_flight -= packet.get_size();
+        }
+
+        // 仅当最大被确认的数据包是新确认的, 且本次至少确认了一个
+        // ack-eliciting 数据包时, 才产生 RTT 样本.
+        let mut rtt_sample = None;
+        if let Some(largest) = acked.last() {
+            if largest.get_packet_number() == ack.get_largest()
+                && acked.iter().any(SentPacket::is_ack_eliciting)
+            {
+                let latest_rtt = now.saturating_duration_since(largest.get_time_sent());
+                if self.first_rtt_sample.is_none() {
+                    self.first_rtt_sample = Some(now);
+                }
+                self.rtt.update(
+                    latest_rtt,
+                    self.get_ack_delay(space, ack),
+                    self.get_max_ack_delay(),
+                );
+                rtt_sample = Some(latest_rtt);
+            }
+        }
+
+        for packet in acked.iter().filter(|packet| packet.is_in_flight()) {
+            self.delivery_rate.on_packet_acked(now, packet);
+            self.congestion.on_ack(now, packet, &self.rtt);
+        }
+        self.process_ecn(now, space, ack, &acked);
+
+        if let Some(sample) =
+            self.delivery_rate
+                .take_sample(now, self.rtt.get_min_rtt(), self.bytes_in_flight)
+        {
+            self.congestion
+                .on_rate_sample(now, &sample, rtt_sample, &self.rtt);
+        }
+
+        let lost = self.detect_and_remove_lost_packets(space, now);
+        self.on_packets_lost(now, &lost);
+
+        if self.peer_completed_address_validation {
+            self.pto_count = 0;
+        }
+        self.set_loss_detection_timer(now);
+
+        Ok((acked, lost))
+    }
+
+    /// 处理丢包检测计时器超时
+    ///
+    /// 若存在因时间阈值而待判定丢失的数据包, 则判定丢包;
+    /// 否则视为 PTO 超时, 安排在对应编号空间中发送探测包.
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// # Returns
+    /// 返回被判定丢失的数据包
+    pub(crate) fn on_loss_detection_timeout(&mut self, now: Instant) -> Vec<SentPacket> {
+        match self.loss_detection_timer {
+            Some(timer) if timer <= now => {}
+            _ => return Vec::new(),
+        }
+
+        if let Some((_, space)) = self.get_loss_time_and_space() {
+            let lost = self.detect_and_remove_lost_packets(space, now);
+            self.on_packets_lost(now, &lost);
+            self.set_loss_detection_timer(now);
+            return lost;
+        }
+
+        if self.no_ack_eliciting_in_flight() {
+            // 客户端防死锁: Handshake 数据包可以证明地址所有权,
+            // Initial 数据包则可以为服务端赚取更多的反放大额度.
+            let space = if self.has_handshake_keys {
+                PacketNumberSpace::Handshake
+            } else {
+                PacketNumberSpace::Initial
+            };
+            self.spaces[space.index()].probes = 1;
+        } else if let Some((_, space)) = self.get_pto_time_and_space(now) {
+            self.spaces[space.index()].probes = PTO_PROBES;
+        }
+
+        self.pto_count += 1;
+        self.set_loss_detection_timer(now);
+
+        Vec::new()
+    }
+
+    /// 判断指定编号空间中是否有待发送的探测包
+    ///
+    /// # Arguments
+    /// `space` - 编号空间
+    /// # Returns
+    /// 返回是否有待发送的探测包
+    #[inline(always)]
+    pub(crate) const fn has_probe(&self, space: PacketNumberSpace) -> bool {
+        self.spaces[space.index()].probes != 0
+    }
+
+    /// 获取在指定编号空间中待发送的探测包内容
+    ///
+    /// 探测包优先重传该空间中最早的 ack-eliciting 数据包所承载的帧;
+    /// 若没有可重传的帧, 则使用 PING 帧.
+    ///
+    /// # Arguments
+    /// `space` - 编号空间
+    /// # Returns
+    /// 返回探测包需要承载的帧; 若该空间无需发送探测包, 则返回 None
+    pub(crate) fn poll_probe(&mut self, space: PacketNumberSpace) -> Option<Vec<SentFrame>> {
+        let state = &mut self.spaces[space.index()];
+        if state.probes == 0 {
+            return None;
+        }
+        state.probes -= 1;
+
+        let frames: Vec<SentFrame> = state
+            .sent_packets
+            .values()
+            .find(|packet| packet.is_ack_eliciting())
+            .map(|packet| {
+                packet
+                    .get_frames()
+                    .iter()
+                    .filter(|frame| frame.is_ack_eliciting() && **frame != SentFrame::Ping)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if frames.is_empty() {
+            Some(vec![SentFrame::Ping])
+        } else {
+            Some(frames)
+        }
+    }
+
+    /// 丢弃一个数据包编号空间
+    ///
+    /// 在丢弃 Initial 或 Handshake 密钥时调用, 该空间中的数据包不再计入在途字节数.
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `space` - 编号空间
+    pub(crate) fn discard_space(&mut self, now: Instant, space: PacketNumberSpace) {
+        let state = std::mem::replace(&mut self.spaces[space.index()], SpaceState::new());
+        for packet in state.sent_packets.values() {
+            if packet.is_in_flight() {
+                self.bytes_in_flight -= packet.get_size();
+            }
+        }
+
+        self.pto_count = 0;
+        self.set_loss_detection_timer(now);
+    }
+
+    /// 验证 ACK 帧中的 ECN Counts, ECN-CE 计数增加时视为拥塞事件
+    fn process_ecn(
+        &mut self,
+        now: Instant,
+        space: PacketNumberSpace,
+        ack: &ACKFrame,
+        acked: &[SentPacket],
+    ) {
+        let newly_acked = acked
+            .iter()
+            .filter(|packet| packet.get_ecn() != EcnCodepoint::NotEct)
+            .count() as u64;
+
+        if self.ecn.on_ack_received(space, newly_acked, ack.get_ecn()) {
+            if let Some(largest) = acked.last() {
+                self.congestion
+                    .on_congestion_event(now, largest.get_time_sent());
+            }
+        }
+    }
+
+    /// 将丢包通知拥塞控制, 并判定是否发生持续拥塞
+    fn on_packets_lost(&mut self, now: Instant, lost: &[SentPacket]) {
+        let lost_ect = lost
+            .iter()
+            .filter(|packet| packet.get_ecn() != EcnCodepoint::NotEct)
+            .count() as u64;
+        if lost_ect != 0 {
+            self.ecn.on_packets_lost(lost_ect);
+        }
+
+        let mut largest_time_sent: Option<Instant> = None;
+        for packet in lost.iter().filter(|packet| packet.is_in_flight()) {
+            self.congestion.on_packet_lost(now, packet);
+            largest_time_sent = largest_time_sent.max(Some(packet.get_time_sent()));
+        }
+
+        if let Some(time_sent) = largest_time_sent {
+            self.congestion.on_congestion_event(now, time_sent);
+
+            if self.in_persistent_congestion(lost) {
+                self.congestion.on_persistent_congestion(now);
+            }
+        }
+    }
+
+    /// 判断丢失的数据包是否构成持续拥塞
+    ///
+    /// 只考虑获得首个 RTT 样本之后发送的数据包. 若其中连续丢失的数据包里,
+    /// 最早与最晚的 ack-eliciting 数据包的发送时间间隔超过持续拥塞时长,
+    /// 则判定为持续拥塞.
+    fn in_persistent_congestion(&self, lost: &[SentPacket]) -> bool {
+        let first_rtt_sample = match self.first_rtt_sample {
+            Some(first_rtt_sample) => first_rtt_sample,
+            None => return false,
+        };
+        let duration =
+            (self.rtt.get_pto_base() + self.max_ack_delay) * PERSISTENT_CONGESTION_THRESHOLD;
+
+        let mut start: Option<Instant> = None;
+        let mut prev: Option<PacketNumber> = None;
+        for packet in lost {
+            if packet.get_time_sent() <= first_rtt_sample {
+                continue;
+            }
+
+            // 两个丢失的数据包之间存在未丢失的数据包, 重新开始计算.
+            if prev.is_some_and(|prev| prev + 1 != packet.get_packet_number()) {
+                start = None;
+            }
+            prev = Some(packet.get_packet_number());
+
+            if !packet.is_ack_eliciting() {
+                continue;
+            }
+            match start {
+                Some(start) if packet.get_time_sent() - start > duration => return true,
+                Some(_) => {}
+                None => start = Some(packet.get_time_sent()),
+            }
+        }
+
+        false
+    }
+
+    /// 获取 ACK 帧中对方报告的 ACK Delay
+    ///
+    /// Initial 与 Handshake 数据包中的 ACK 不应被延迟发送, 因此忽略其 ACK Delay.
+    fn get_ack_delay(&self, space: PacketNumberSpace, ack: &ACKFrame) -> Duration {
+        if space != PacketNumberSpace::ApplicationData {
+            return Duration::ZERO;
+        }
+
+        let micros = ack
+            .get_delay()
+            .saturating_mul(1 << self.ack_delay_exponent.min(20));
+        Duration::from_micros(micros)
+    }
+
+    /// 获取用于限制 ACK Delay 的 max_ack_delay, 握手确认前不作限制
+    fn get_max_ack_delay(&self) -> Option<Duration> {
+        if self.handshake_confirmed {
+            Some(self.max_ack_delay)
+        } else {
+            None
+        }
+    }
+
+    /// 判定并移除指定编号空间中丢失的数据包
+    fn detect_and_remove_lost_packets(
+        &mut self,
+        space: PacketNumberSpace,
+        now: Instant,
+    ) -> Vec<SentPacket> {
+        let loss_delay = (self.rtt.get_latest_rtt().max(self.rtt.get_smoothed_rtt())
+            * TIME_THRESHOLD_NUMERATOR
+            / TIME_THRESHOLD_DENOMINATOR)
+            .max(GRANULARITY);
+        let lost_send_time = now.checked_sub(loss_delay);
+
+        let state = &mut self.spaces[space.index()];
+        state.loss_time = None;
+
+        let largest_acked = match state.largest_acked {
+            Some(largest_acked) => largest_acked,
+            None => return Vec::new(),
+        };
+
+        let mut lost_packet_numbers = Vec::new();
+        for (packet_number, packet) in state.sent_packets.range(..largest_acked) {
+            let time_lost = lost_send_time.is_some_and(|t| packet.get_time_sent() <= t);
+            let reorder_lost = largest_acked >= packet_number + PACKET_THRESHOLD;
+
+            if time_lost || reorder_lost {
+                lost_packet_numbers.push(*packet_number);
+            } else {
+                let loss_time = packet.get_time_sent() + loss_delay;
+                state.loss_time = Some(state.loss_time.map_or(loss_time, |t| t.min(loss_time)));
+            }
+        }
+
+        let mut lost = Vec::with_capacity(lost_packet_numbers.len());
+        for packet_number in lost_packet_numbers {
+            if let Some(packet) = state.remove(packet_number) {
+                if packet.is_in_flight() {
+                    self.bytes_in_flight -= packet.get_size();
+                }
+                lost.push(packet);
+            }
+        }
+
+        lost
+    }
+
+    /// 获取最早的时间阈值丢包时刻及其所在的编号空间
+    fn get_loss_time_and_space(&self) -> Option<(Instant, PacketNumberSpace)> {
+        let mut ret: Option<(Instant, PacketNumberSpace)> = None;
+        for space in PacketNumberSpace::ALL {
+            if let Some(loss_time) = self.spaces[space.index()].loss_time {
+                if ret.is_none_or(|(t, _)| loss_time < t) {
+                    ret = Some((loss_time, space));
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// 获取最早的 PTO 到期时刻及其所在的编号空间
+    fn get_pto_time_and_space(&self, now: Instant) -> Option<(Instant, PacketNumberSpace)> {
+        let backoff = 2u32.saturating_pow(self.pto_count);
+        let mut duration = self.rtt.get_pto_base().saturating_mul(backoff);
+
+        if self.no_ack_eliciting_in_flight() {
+            let space = if self.has_handshake_keys {
+                PacketNumberSpace::Handshake
+            } else {
+                PacketNumberSpace::Initial
+            };
+            return Some((now + duration, space));
+        }
+
+        let mut ret: Option<(Instant, PacketNumberSpace)> = None;
+        for space in PacketNumberSpace::ALL {
+            let state = &self.spaces[space.index()];
+            if state.ack_eliciting_in_flight == 0 {
+                continue;
+            }
+            if space == PacketNumberSpace::ApplicationData {
+                // 握手确认前不为 Application Data 空间设置 PTO.
+                if !self.handshake_confirmed {
+                    return ret;
+                }
+                duration += self.max_ack_delay.saturating_mul(backoff);
+            }
+
+            if let Some(time) = state.time_of_last_ack_eliciting_packet {
+                let pto = time + duration;
+                if ret.is_none_or(|(t, _)| pto < t) {
+                    ret = Some((pto, space));
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// 判断所有编号空间中是否都没有在途的 ack-eliciting 数据包
+    fn no_ack_eliciting_in_flight(&self) -> bool {
+        self.spaces
+            .iter()
+            .all(|state| state.ack_eliciting_in_flight == 0)
+    }
+
+    /// 重新设置丢包检测计时器
+    fn set_loss_detection_timer(&mut self, now: Instant) {
+        if let Some((loss_time, _)) = self.get_loss_time_and_space() {
+            self.loss_detection_timer = Some(loss_time);
+            return;
+        }
+
+        if self.no_ack_eliciting_in_flight() && self.peer_completed_address_validation {
+            self.loss_detection_timer = None;
+            return;
+        }
+
+        self.loss_detection_timer = self.get_pto_time_and_space(now).map(|(t, _)| t);
+    }
+}
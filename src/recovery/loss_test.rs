@@ -0,0 +1,179 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    attr::PacketNumberSpace,
+    congestion::NewReno,
+    error::TransportErrorCode,
+    frame::{ACKFrame, ACKRange},
+};
+
+use super::{LossDetector, SentFrame, SentPacket};
+
+fn ping_packet(packet_number: u64, time_sent: Instant) -> SentPacket {
+    SentPacket::new(packet_number, time_sent, 1200, vec![SentFrame::Ping])
+}
+
+#[test]
+fn test_ack_removes_packets_and_samples_rtt() {
+    let start = Instant::now();
+    let mut detector = LossDetector::new(
+        true,
+        Duration::from_millis(25),
+        Box::new(NewReno::new(1200)),
+    );
+    let space = PacketNumberSpace::Initial;
+
+    for pn in 0..3 {
+        detector.on_packet_sent(space, ping_packet(pn, start));
+    }
+    assert_eq!(detector.get_bytes_in_flight(), 3600);
+    assert!(detector.get_loss_detection_timer().is_some());
+
+    let mut ack = ACKFrame::new(false);
+    ack.set_largest(2);
+    ack.set_first_range(2);
+
+    let now = start + Duration::from_millis(50);
+    let (acked, lost) = detector.on_ack_received(now, space, &ack).unwrap();
+    assert_eq!(acked.len(), 3);
+    assert!(lost.is_empty());
+    assert_eq!(detector.get_bytes_in_flight(), 0);
+    assert_eq!(
+        detector.get_rtt().get_latest_rtt(),
+        Duration::from_millis(50)
+    );
+    assert_eq!(detector.get_loss_detection_timer(), None);
+}
+
+#[test]
+fn test_ack_of_unsent_packet() {
+    let start = Instant::now();
+    let mut detector = LossDetector::new(
+        true,
+        Duration::from_millis(25),
+        Box::new(NewReno::new(1200)),
+    );
+    let space = PacketNumberSpace::ApplicationData;
+
+    // 尚未发送任何数据包时的确认
+    let mut ack = ACKFrame::new(false);
+    ack.set_largest(0);
+    let err = detector.on_ack_received(start, space, &ack).unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::ProtocolViolation);
+    assert_eq!(err.get_frame_type(), 0x02);
+
+    for pn in 0..2 {
+        detector.on_packet_sent(space, ping_packet(pn, start));
+    }
+
+    // 确认了未发送的数据包 5, 不推进最大被确认编号, 在途的数据包不会被判定丢失
+    let mut ack = ACKFrame::new(false);
+    ack.set_largest(5);
+    ack.set_first_range(4);
+    let now = start + Duration::from_millis(10);
+    let err = detector.on_ack_received(now, space, &ack).unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::ProtocolViolation);
+    assert_eq!(detector.get_bytes_in_flight(), 2400);
+
+    let mut ack = ACKFrame::new(false);
+    ack.set_largest(1);
+    let (acked, lost) = detector.on_ack_received(now, space, &ack).unwrap();
+    assert_eq!(acked.len(), 1);
+    assert!(lost.is_empty());
+}
+
+#[test]
+fn test_packet_threshold_loss() {
+    let start = Instant::now();
+    let mut detector = LossDetector::new(
+        true,
+        Duration::from_millis(25),
+        Box::new(NewReno::new(1200)),
+    );
+    let space = PacketNumberSpace::Initial;
+
+    for pn in 0..5 {
+        detector.on_packet_sent(space, ping_packet(pn, start));
+    }
+
+    // 确认 4 与 2, 数据包 0 满足数据包阈值, 数据包 1 与 3 尚未满足.
+    let mut ack = ACKFrame::new(false);
+    ack.set_largest(4);
+    ack.set_first_range(0);
+    ack.set_ranges(&[ACKRange::new(0, 0)]);
+
+    let now = start + Duration::from_millis(10);
+    let (acked, lost) = detector.on_ack_received(now, space, &ack).unwrap();
+    let acked: Vec<u64> = acked.iter().map(SentPacket::get_packet_number).collect();
+    let lost: Vec<u64> = lost.iter().map(SentPacket::get_packet_number).collect();
+    assert_eq!(acked, vec![2, 4]);
+    assert_eq!(lost, vec![0, 1]);
+
+    // 数据包 3 等待时间阈值, 计时器到期后被判定丢失.
+    let timer = detector.get_loss_detection_timer().unwrap();
+    let lost = detector.on_loss_detection_timeout(timer);
+    let lost: Vec<u64> = lost.iter().map(SentPacket::get_packet_number).collect();
+    assert_eq!(lost, vec![3]);
+}
+
+#[test]
+fn test_pto_probe() {
+    let start = Instant::now();
+    let mut detector = LossDetector::new(
+        true,
+        Duration::from_millis(25),
+        Box::new(NewReno::new(1200)),
+    );
+    let space = PacketNumberSpace::Initial;
+
+    let crypto = SentFrame::Crypto {
+        offset: 0,
+        len: 100,
+    };
+    detector.on_packet_sent(space, SentPacket::new(0, start, 1200, vec![crypto]));
+
+    let timer = detector.get_loss_detection_timer().unwrap();
+    assert!(detector.on_loss_detection_timeout(timer).is_empty());
+    assert_eq!(detector.get_pto_count(), 1);
+
+    assert_eq!(detector.poll_probe(space), Some(vec![crypto]));
+    assert_eq!(detector.poll_probe(space), Some(vec![crypto]));
+    assert_eq!(detector.poll_probe(space), None);
+
+    // 指数退避后的计时器晚于上一次.
+    assert!(detector.get_loss_detection_timer().unwrap() > timer);
+}
+
+#[test]
+fn test_persistent_congestion() {
+    let start = Instant::now();
+    let mut detector = LossDetector::new(
+        true,
+        Duration::from_millis(25),
+        Box::new(NewReno::new(1200)),
+    );
+    let space = PacketNumberSpace::ApplicationData;
+
+    // 获得首个 RTT 样本.
+    detector.on_packet_sent(space, ping_packet(0, start));
+    let mut ack = ACKFrame::new(false);
+    ack.set_largest(0);
+    detector
+        .on_ack_received(start + Duration::from_millis(10), space, &ack)
+        .unwrap();
+
+    // 连续丢失的数据包跨越了远超 3 倍 PTO 的时长.
+    for pn in 1..=4 {
+        let time_sent = start + Duration::from_millis(20 + pn * 200);
+        detector.on_packet_sent(space, ping_packet(pn, time_sent));
+    }
+    detector.on_packet_sent(space, ping_packet(5, start + Duration::from_secs(2)));
+
+    let mut ack = ACKFrame::new(false);
+    ack.set_largest(5);
+    let (_, lost) = detector
+        .on_ack_received(start + Duration::from_millis(2010), space, &ack)
+        .unwrap();
+    assert_eq!(lost.len(), 4);
+    assert_eq!(detector.get_congestion_window(), 2400);
+}
@@ -0,0 +1,30 @@
+mod delivery_rate;
+mod ecn;
+mod loss;
+mod received;
+mod retransmit;
+mod rtt;
+mod sent_packet;
+
+pub(crate) use delivery_rate::*;
+pub(crate) use ecn::*;
+pub(crate) use loss::*;
+pub(crate) use received::*;
+pub(crate) use retransmit::*;
+pub(crate) use rtt::*;
+pub(crate) use sent_packet::*;
+
+pub use delivery_rate::RateSample;
+pub use rtt::RttEstimator;
+pub use sent_packet::SentPacket;
+
+#[cfg(test)]
+mod ecn_test;
+#[cfg(test)]
+mod loss_test;
+#[cfg(test)]
+mod received_test;
+#[cfg(test)]
+mod retransmit_test;
+#[cfg(test)]
+mod rtt_test;
@@ -0,0 +1,215 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    attr::{PacketNumber, PacketNumberSpace},
+    frame::{ACKFrame, ACKRange, ECNCounts},
+    util::RangeSet,
+};
+
+/// 收到多少个 ack-eliciting 数据包后立即发送 ACK
+const ACK_ELICITING_THRESHOLD: usize = 2;
+
+/// 单个 ACK 帧中最多携带的 ACK Range 数量
+const MAX_ACK_RANGES: usize = 32;
+
+/// 接收端的数据包记录
+///
+/// 记录单个编号空间中已收到的数据包编号, 用于丢弃重复的数据包与生成 ACK 帧.
+/// 按 RFC 9000 §13.2 决定 ACK 的发送时机: Initial 与 Handshake 数据包立即确认;
+/// 1-RTT 数据包每收到两个 ack-eliciting 数据包或乱序到达时立即确认,
+/// 否则最多延迟 max_ack_delay.
+pub(crate) struct ReceivedPackets {
+    /// 已收到的数据包编号
+    received: RangeSet,
+
+    /// 小于该编号的数据包不再跟踪, 视为重复的数据包
+    floor: PacketNumber,
+
+    /// 收到的最大数据包编号及其到达时刻
+    largest: Option<(PacketNumber, Instant)>,
+
+    /// 上次发送 ACK 后收到的 ack-eliciting 数据包数量
+    ack_eliciting_since_ack: usize,
+
+    /// 是否需要立即发送 ACK
+    immediate: bool,
+
+    /// 延迟发送 ACK 的最晚时刻
+    ack_deadline: Option<Instant>,
+
+    /// 上次发送 ACK 后是否收到了新的数据包
+    unacked: bool,
+}
+
+impl ReceivedPackets {
+    /// 构造接收端的数据包记录
+    ///
+    /// # Returns
+    /// 返回接收端的数据包记录
+    pub(crate) fn new() -> Self {
+        Self {
+            received: RangeSet::new(),
+            floor: 0,
+            largest: None,
+            ack_eliciting_since_ack: 0,
+            immediate: false,
+            ack_deadline: None,
+            unacked: false,
+        }
+    }
+
+    /// 获取收到的最大数据包编号, 用于还原截断的 Packet Number
+    ///
+    /// # Returns
+    /// 返回收到的最大数据包编号
+    #[inline(always)]
+    pub(crate) fn get_largest(&self) -> Option<PacketNumber> {
+        self.largest.map(|(largest, _)| largest)
+    }
+
+    /// 获取延迟发送 ACK 的最晚时刻
+    ///
+    /// # Returns
+    /// 返回最晚时刻; 没有等待确认的 ack-eliciting 数据包时返回 None
+    #[inline(always)]
+    pub(crate) const fn get_ack_deadline(&self) -> Option<Instant> {
+        self.ack_deadline
+    }
+
+    /// 判断数据包是否已经收到过
+    ///
+    /// # Arguments
+    /// `packet_number` - 数据包编号
+    /// # Returns
+    /// 返回是否为重复的数据包
+    pub(crate) fn is_duplicate(&self, packet_number: PacketNumber) -> bool {
+        packet_number < self.floor || self.received.contains(packet_number as usize)
+    }
+
+    /// 记录一个成功处理的数据包
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `space` - 数据包所在的编号空间
+    /// `packet_number` - 数据包编号
+    /// `ack_eliciting` - 是否为 ack-eliciting 数据包
+    /// `max_ack_delay` - 本端延迟发送 ACK 的最大时长
+    pub(crate) fn on_packet_received(
+        &mut self,
+        now: Instant,
+        space: PacketNumberSpace,
+        packet_number: PacketNumber,
+        ack_eliciting: bool,
+        max_ack_delay: Duration,
+    ) {
+        // 乱序到达或出现空缺时立即确认, 以便对方尽快检测丢包
+        let out_of_order = self
+            .largest
+            .is_some_and(|(largest, _)| packet_number < largest || packet_number > largest + 1);
+
+        self.received
+            .insert(packet_number as usize..packet_number as usize + 1);
+        if self
+            .get_largest()
+            .is_none_or(|largest| packet_number > largest)
+        {
+            self.largest = Some((packet_number, now));
+        }
+        self.unacked = true;
+
+        if !ack_eliciting {
+            return;
+        }
+
+        self.ack_eliciting_since_ack += 1;
+        if space != PacketNumberSpace::ApplicationData
+            || self.ack_eliciting_since_ack >= ACK_ELICITING_THRESHOLD
+            || out_of_order
+        {
+            self.immediate = true;
+        } else if self.ack_deadline.is_none() {
+            self.ack_deadline = Some(now + max_ack_delay);
+        }
+    }
+
+    /// 判断是否需要发送 ACK
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// # Returns
+    /// 需要立即确认或延迟确认的时限已到时返回 true
+    pub(crate) fn should_send_ack(&self, now: Instant) -> bool {
+        self.immediate || self.ack_deadline.is_some_and(|deadline| deadline <= now)
+    }
+
+    /// 判断上次发送 ACK 后是否收到了新的数据包, 发送其他帧时可以顺带确认
+    ///
+    /// # Returns
+    /// 返回是否有尚未确认的数据包
+    #[inline(always)]
+    pub(crate) const fn has_unacked(&self) -> bool {
+        self.unacked
+    }
+
+    /// 生成 ACK 帧, 最多携带 `MAX_ACK_RANGES` 个区间
+    ///
+    /// # Arguments
+    /// `now` - 当前时刻
+    /// `ack_delay_exponent` - 本端用于编码 ACK Delay 的指数
+    /// `ecn` - 收到的数据包的 ECN 码点计数
+    /// # Returns
+    /// 返回 ACK 帧; 尚未收到数据包时返回 None
+    pub(crate) fn build_ack(
+        &self,
+        now: Instant,
+        ack_delay_exponent: u8,
+        ecn: Option<ECNCounts>,
+    ) -> Option<ACKFrame> {
+        let (largest, time) = self.largest?;
+        let mut ranges = self.received.iter().rev().take(MAX_ACK_RANGES);
+        let first = ranges.next()?;
+
+        let mut frame = ACKFrame::new(ecn.is_some());
+        frame.set_largest(largest);
+        frame.set_delay(
+            now.saturating_duration_since(time).as_micros() as u64 >> ack_delay_exponent,
+        );
+        frame.set_first_range(first.end - 1 - first.start);
+
+        let mut smallest = first.start;
+        let ranges = ranges
+            .map(|range| {
+                let ack_range =
+                    ACKRange::new(smallest - range.end - 1, range.end - 1 - range.start);
+                smallest = range.start;
+                ack_range
+            })
+            .collect::<Vec<_>>();
+        frame.set_ranges(&ranges);
+
+        if let Some(ecn) = ecn {
+            frame.set_ecn(ecn);
+        }
+
+        Some(frame)
+    }
+
+    /// 记录已发送 ACK, 重置 ACK 的发送时机
+    pub(crate) fn on_ack_sent(&mut self) {
+        self.ack_eliciting_since_ack = 0;
+        self.immediate = false;
+        self.ack_deadline = None;
+        self.unacked = false;
+    }
+
+    /// 本端发送的 ACK 帧被确认, 之后不再确认小于其最大编号的数据包
+    ///
+    /// # Arguments
+    /// `largest` - 被确认的 ACK 帧中的最大数据包编号
+    pub(crate) fn on_ack_acked(&mut self, largest: PacketNumber) {
+        if largest > self.floor {
+            self.received.remove(self.floor as usize..largest as usize);
+            self.floor = largest;
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+use crate::attr::PacketNumberSpace;
+
+use super::ReceivedPackets;
+
+const MAX_ACK_DELAY: Duration = Duration::from_millis(25);
+
+#[test]
+fn test_received_ack_ranges() {
+    let now = Instant::now();
+    let mut received = ReceivedPackets::new();
+    let space = PacketNumberSpace::Handshake;
+
+    for pn in [0, 1, 2, 5, 6, 9] {
+        received.on_packet_received(now, space, pn, true, MAX_ACK_DELAY);
+    }
+    assert!(received.is_duplicate(5));
+    assert!(!received.is_duplicate(4));
+    assert!(received.should_send_ack(now));
+
+    let ack = received.build_ack(now, 3, None).unwrap();
+    assert_eq!(ack.get_largest(), 9);
+    assert_eq!(ack.get_acked_ranges().unwrap(), vec![9..=9, 5..=6, 0..=2]);
+
+    // 被确认的 ACK 之前的数据包不再出现在 ACK 帧中
+    received.on_ack_sent();
+    received.on_ack_acked(5);
+    assert!(received.is_duplicate(3));
+    let ack = received.build_ack(now, 3, None).unwrap();
+    assert_eq!(ack.get_acked_ranges().unwrap(), vec![9..=9, 5..=6]);
+}
+
+#[test]
+fn test_received_delayed_ack() {
+    let now = Instant::now();
+    let mut received = ReceivedPackets::new();
+    let space = PacketNumberSpace::ApplicationData;
+
+    // 第一个 ack-eliciting 数据包延迟确认
+    received.on_packet_received(now, space, 0, true, MAX_ACK_DELAY);
+    assert!(!received.should_send_ack(now));
+    assert_eq!(received.get_ack_deadline(), Some(now + MAX_ACK_DELAY));
+    assert!(received.should_send_ack(now + MAX_ACK_DELAY));
+
+    // 第二个 ack-eliciting 数据包立即确认
+    received.on_packet_received(now, space, 1, true, MAX_ACK_DELAY);
+    assert!(received.should_send_ack(now));
+    received.on_ack_sent();
+
+    // 非 ack-eliciting 数据包不会触发 ACK, 乱序到达的数据包立即确认
+    received.on_packet_received(now, space, 2, false, MAX_ACK_DELAY);
+    assert!(!received.should_send_ack(now + MAX_ACK_DELAY));
+    assert!(received.has_unacked());
+    received.on_packet_received(now, space, 5, true, MAX_ACK_DELAY);
+    assert!(received.should_send_ack(now));
+}
@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::{
+    attr::{StreamIDSetter, StreamId},
+    flow_control::{ConnectionFlowControl, StreamFlowControl},
+    frame::{
+        HandshakeDoneFrame, MaxDataFrame, MaxStreamDataFrame, MaxStreamsFrame,
+        NewConnectionIDFrame, NewTokenFrame, RetireConnectionIDFrame,
+    },
+    packet::PacketBuilder,
+    stream::{RecvState, StreamCountManager, StreamMap},
+};
+
+use super::SentFrame;
+
+/// 控制帧重传跟踪器
+///
+/// 数据包中承载的控制帧以 `SentFrame` 的形式记录在对应的已发送数据包中.
+/// 数据包被判定为丢失后, 按 RFC 9000 §13.3 以最新状态重新生成控制帧:
+/// 已被更新的值所取代的帧不再重传, 由流或流量控制自行维护的帧交由其重新生成.
+///
+/// 没有其他状态来源的帧 (NEW_CONNECTION_ID、RETIRE_CONNECTION_ID、NEW_TOKEN 与
+/// HANDSHAKE_DONE) 的内容由跟踪器保存, 直到被确认或被取代.
+pub(crate) struct ControlFrameTracker {
+    /// 待发送或待重传的控制帧
+    pending: VecDeque<SentFrame>,
+
+    /// 尚未被确认的 NEW_CONNECTION_ID 帧, 按序列号索引
+    new_connection_ids: BTreeMap<u64, NewConnectionIDFrame>,
+
+    /// 已通告的最大 Retire Prior To, 序列号小于该值的 NEW_CONNECTION_ID 不再重传
+    retire_prior_to: u64,
+
+    /// 尚未被确认的 NEW_TOKEN 帧, 按本端分配的标识索引
+    new_tokens: BTreeMap<u64, NewTokenFrame>,
+
+    /// 下一个 NEW_TOKEN 帧的标识
+    next_token_id: u64,
+
+    /// HANDSHAKE_DONE 帧是否已被确认
+    handshake_done_acked: bool,
+}
+
+impl ControlFrameTracker {
+    /// 构造一个控制帧重传跟踪器
+    ///
+    /// # Returns
+    /// 返回控制帧重传跟踪器
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            new_connection_ids: BTreeMap::new(),
+            retire_prior_to: 0,
+            new_tokens: BTreeMap::new(),
+            next_token_id: 0,
+            handshake_done_acked: false,
+        }
+    }
+
+    /// 判断是否有待发送的控制帧
+    ///
+    /// # Returns
+    /// 返回是否有待发送的控制帧
+    #[inline(always)]
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// 计划发送 HANDSHAKE_DONE 帧
+    pub(crate) fn push_handshake_done(&mut self) {
+        if !self.handshake_done_acked {
+            self.push(SentFrame::HandshakeDone);
+        }
+    }
+
+    /// 计划发送 NEW_CONNECTION_ID 帧, 序列号小于其 Retire Prior To 的帧不再发送
+    ///
+    /// # Arguments
+    /// `frame` - NEW_CONNECTION_ID 帧
+    pub(crate) fn push_new_connection_id(&mut self, frame: NewConnectionIDFrame) {
+        let seq = frame.get_seq();
+        if frame.get_retire_prior_to() > self.retire_prior_to {
+            self.retire_prior_to = frame.get_retire_prior_to();
+
+            let retire_prior_to = self.retire_prior_to;
+            self.new_connection_ids
+                .retain(|&seq, _| seq >= retire_prior_to);
+            self.pending.retain(|frame| match frame {
+                SentFrame::NewConnectionId { seq } => *seq >= retire_prior_to,
+                _ => true,
+            });
+        }
+
+        if seq >= self.retire_prior_to {
+            self.new_connection_ids.insert(seq, frame);
+            self.push(SentFrame::NewConnectionId { seq });
+        }
+    }
+
+    /// 计划发送 RETIRE_CONNECTION_ID 帧
+    ///
+    /// # Arguments
+    /// `seq` - 废弃的 Connection ID 的序列号
+    pub(crate) fn push_retire_connection_id(&mut self, seq: u64) {
+        self.push(SentFrame::RetireConnectionId { seq });
+    }
+
+    /// 计划发送 NEW_TOKEN 帧
+    ///
+    /// # Arguments
+    /// `token` - 供客户端在未来连接中使用的 Token
+    pub(crate) fn push_new_token(&mut self, token: &[u8]) {
+        let id = self.next_token_id;
+        self.next_token_id += 1;
+
+        let mut frame = NewTokenFrame::new();
+        frame.set_token(token);
+        self.new_tokens.insert(id, frame);
+        self.push(SentFrame::NewToken { id });
+    }
+
+    /// 计划发送以最新状态重新生成的控制帧
+    ///
+    /// # Arguments
+    /// `frame` - 控制帧的记录
+    pub(crate) fn push(&mut self, frame: SentFrame) {
+        if !self.pending.contains(&frame) {
+            self.pending.push_back(frame);
+        }
+    }
+
+    /// 对方废弃了本端的 Connection ID, 对应的 NEW_CONNECTION_ID 帧不再重传
+    ///
+    /// # Arguments
+    /// `seq` - 被废弃的 Connection ID 的序列号
+    pub(crate) fn on_connection_id_retired(&mut self, seq: u64) {
+        self.new_connection_ids.remove(&seq);
+        self.pending
+            .retain(|frame| *frame != SentFrame::NewConnectionId { seq });
+    }
+
+    /// 将待发送的控制帧依次写入数据包, 直到剩余空间不足
+    ///
+    /// # Arguments
+    /// `builder` - 数据包组装器
+    pub(crate) fn write_frames(&mut self, builder: &mut PacketBuilder) {
+        while let Some(&sent) = self.pending.front() {
+            let written = match sent {
+                SentFrame::MaxData { max } => {
+                    let mut frame = MaxDataFrame::new();
+                    frame.set_maximum_data(max);
+                    builder.push_frame(&frame, sent)
+                }
+                SentFrame::MaxStreamData { stream_id, max } => {
+                    let mut frame = MaxStreamDataFrame::new();
+                    frame.set_stream_id(stream_id);
+                    frame.set_maximum_data(max);
+                    builder.push_frame(&frame, sent)
+                }
+                SentFrame::MaxStreams { bidi, max } => {
+                    let mut frame = MaxStreamsFrame::new(bidi);
+                    frame.set_maximum_streams(max);
+                    builder.push_frame(&frame, sent)
+                }
+                SentFrame::NewConnectionId { seq } => match self.new_connection_ids.get(&seq) {
+                    Some(frame) => builder.push_frame(frame, sent),
+                    None => true,
+                },
+                SentFrame::RetireConnectionId { seq } => {
+                    let mut frame = RetireConnectionIDFrame::new();
+                    frame.set_seq(seq);
+                    builder.push_frame(&frame, sent)
+                }
+                SentFrame::NewToken { id } => match self.new_tokens.get(&id) {
+                    Some(frame) => builder.push_frame(frame, sent),
+                    None => true,
+                },
+                SentFrame::HandshakeDone => builder.push_frame(&HandshakeDoneFrame::new(), sent),
+                _ => true,
+            };
+            if !written {
+                break;
+            }
+            self.pending.pop_front();
+        }
+    }
+
+    /// 控制帧被确认, 释放跟踪器保存的帧内容
+    ///
+    /// # Arguments
+    /// `frame` - 被确认的帧的记录
+    pub(crate) fn on_frame_acked(&mut self, frame: &SentFrame) {
+        match *frame {
+            SentFrame::NewConnectionId { seq } => {
+                self.new_connection_ids.remove(&seq);
+            }
+            SentFrame::NewToken { id } => {
+                self.new_tokens.remove(&id);
+            }
+            SentFrame::HandshakeDone => self.handshake_done_acked = true,
+            _ => {}
+        }
+    }
+
+    /// 控制帧丢失, 按最新状态决定是否重传
+    ///
+    /// # Arguments
+    /// `frame` - 丢失的帧的记录
+    /// `flow_control` - 连接级流量控制
+    /// `stream_flow_control` - 各个流的流量控制
+    /// `streams` - 流集合
+    /// `stream_counts` - 流数量管理器
+    pub(crate) fn on_frame_lost(
+        &mut self,
+        frame: &SentFrame,
+        flow_control: &mut ConnectionFlowControl,
+        stream_flow_control: &mut BTreeMap<StreamId, StreamFlowControl>,
+        streams: &mut StreamMap,
+        stream_counts: &mut StreamCountManager,
+    ) {
+        let retransmit = match *frame {
+            SentFrame::MaxData { max } => flow_control.on_max_data_lost(max).is_some(),
+            SentFrame::MaxStreamData { stream_id, max } => {
+                // 已知最终大小后不再需要扩大流的限制
+                let receiving = streams
+                    .get_recv(stream_id)
+                    .is_some_and(|stream| stream.get_state() == RecvState::Recv);
+                receiving
+                    && stream_flow_control
+                        .get(&stream_id)
+                        .and_then(|fc| fc.on_max_stream_data_lost(max))
+                        .is_some()
+            }
+            SentFrame::MaxStreams { bidi, max } => {
+                stream_counts.on_max_streams_lost(bidi, max).is_some()
+            }
+            SentFrame::DataBlocked { max } => {
+                flow_control.on_data_blocked_lost(max);
+                false
+            }
+            SentFrame::StreamDataBlocked { stream_id, max } => {
+                if let Some(fc) = stream_flow_control.get_mut(&stream_id) {
+                    fc.on_stream_data_blocked_lost(max);
+                }
+                false
+            }
+            SentFrame::StreamsBlocked { bidi, max } => {
+                stream_counts.on_streams_blocked_lost(bidi, max);
+                false
+            }
+            SentFrame::ResetStream { stream_id } => {
+                if let Some(stream) = streams.get_send_mut(stream_id) {
+                    stream.on_reset_lost();
+                }
+                false
+            }
+            SentFrame::StopSending { stream_id } => {
+                if let Some(stream) = streams.get_recv_mut(stream_id) {
+                    stream.on_stop_sending_lost();
+                }
+                false
+            }
+            SentFrame::NewConnectionId { seq } => self.new_connection_ids.contains_key(&seq),
+            SentFrame::NewToken { id } => self.new_tokens.contains_key(&id),
+            SentFrame::RetireConnectionId { .. } => true,
+            SentFrame::HandshakeDone => !self.handshake_done_acked,
+            _ => false,
+        };
+
+        if retransmit {
+            self.push(*frame);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    attr::{ConnectionID, Side, TransportParameters},
+    flow_control::ConnectionFlowControl,
+    frame::{FrameType, NewConnectionIDFrame},
+    packet::{PacketBuilder, PacketHeader, ShortHeader},
+    stream::{StreamCountManager, StreamMap},
+};
+
+use super::{ControlFrameTracker, SentFrame};
+
+const STREAM: FrameType = FrameType::Stream {
+    off_flag: true,
+    len_flag: true,
+    fin_flag: false,
+};
+
+fn builder() -> PacketBuilder {
+    let mut conn_id = ConnectionID::new();
+    conn_id.set_id(&[1; 8]);
+    let mut header = ShortHeader::new(0, 1);
+    header.set_dst(conn_id);
+    PacketBuilder::new(PacketHeader::Short(header), 1200, 16).unwrap()
+}
+
+fn new_connection_id(seq: u64, retire_prior_to: u64) -> NewConnectionIDFrame {
+    let mut conn_id = ConnectionID::new();
+    conn_id.set_id(&[seq as u8; 8]);
+    let mut frame = NewConnectionIDFrame::new();
+    frame.set_seq(seq);
+    frame.set_retire_prior_to(retire_prior_to);
+    frame.set_connection_id(conn_id);
+    frame
+}
+
+#[test]
+fn test_control_frame_max_data_superseded() {
+    let now = Instant::now();
+    let rtt = Duration::from_millis(100);
+    let params = TransportParameters::default();
+    let mut fc = ConnectionFlowControl::new(1000, 4000);
+    let mut stream_fc = BTreeMap::new();
+    let mut streams = StreamMap::new(Side::Server);
+    let mut counts = StreamCountManager::new(Side::Server, &params, &params);
+    let mut tracker = ControlFrameTracker::new();
+
+    fc.on_data_received(600, STREAM).unwrap();
+    fc.on_data_consumed(600);
+    let max = fc.poll_max_data_frame(now, rtt).unwrap().get_maximum_data();
+
+    // 丢失的限制仍是最新值, 重传
+    let lost = SentFrame::MaxData { max };
+    tracker.on_frame_lost(&lost, &mut fc, &mut stream_fc, &mut streams, &mut counts);
+    let mut packet = builder();
+    tracker.write_frames(&mut packet);
+    assert!(!tracker.has_pending());
+    assert_eq!(packet.finish().unwrap().get_frames(), &[lost]);
+
+    // 限制已被更新的值取代, 不再重传
+    fc.on_data_received(600, STREAM).unwrap();
+    fc.on_data_consumed(600);
+    assert!(fc.poll_max_data_frame(now + rtt * 4, rtt).is_some());
+    tracker.on_frame_lost(&lost, &mut fc, &mut stream_fc, &mut streams, &mut counts);
+    assert!(!tracker.has_pending());
+}
+
+#[test]
+fn test_control_frame_connection_id_and_handshake_done() {
+    let params = TransportParameters::default();
+    let mut fc = ConnectionFlowControl::new(1000, 4000);
+    let mut stream_fc = BTreeMap::new();
+    let mut streams = StreamMap::new(Side::Server);
+    let mut counts = StreamCountManager::new(Side::Server, &params, &params);
+    let mut tracker = ControlFrameTracker::new();
+
+    tracker.push_new_connection_id(new_connection_id(1, 0));
+    tracker.push_new_connection_id(new_connection_id(2, 0));
+    tracker.push_handshake_done();
+    tracker.push_new_token(&[9; 32]);
+
+    let mut packet = builder();
+    tracker.write_frames(&mut packet);
+    let packet = packet.finish().unwrap();
+    assert_eq!(packet.get_frames().len(), 4);
+
+    let mut lost = |tracker: &mut ControlFrameTracker, frame: &SentFrame| {
+        tracker.on_frame_lost(frame, &mut fc, &mut stream_fc, &mut streams, &mut counts)
+    };
+
+    // 新的 Retire Prior To 取代了丢失的 NEW_CONNECTION_ID
+    lost(&mut tracker, &SentFrame::NewConnectionId { seq: 1 });
+    tracker.push_new_connection_id(new_connection_id(3, 2));
+    lost(&mut tracker, &SentFrame::NewConnectionId { seq: 2 });
+
+    // 已被确认的帧不再重传
+    tracker.on_frame_acked(&SentFrame::HandshakeDone);
+    lost(&mut tracker, &SentFrame::HandshakeDone);
+    tracker.on_frame_acked(&SentFrame::NewToken { id: 0 });
+    lost(&mut tracker, &SentFrame::NewToken { id: 0 });
+    lost(&mut tracker, &SentFrame::RetireConnectionId { seq: 4 });
+
+    let mut packet = builder();
+    tracker.write_frames(&mut packet);
+    assert_eq!(
+        packet.finish().unwrap().get_frames(),
+        &[
+            SentFrame::NewConnectionId { seq: 3 },
+            SentFrame::NewConnectionId { seq: 2 },
+            SentFrame::RetireConnectionId { seq: 4 },
+        ]
+    );
+}
@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+/// 计时器粒度
+pub(crate) const GRANULARITY: Duration = Duration::from_millis(1);
+
+/// 尚未获得 RTT 样本时使用的初始 RTT
+pub(crate) const INITIAL_RTT: Duration = Duration::from_millis(333);
+
+/// RTT 估算
+///
+/// 在收到最大新确认数据包的 ACK 时获取 RTT 样本,
+/// 并由此计算 min_rtt、smoothed_rtt 以及 rttvar.
+///
+/// 对方在 ACK 帧中报告的 ACK Delay 会从 RTT 样本中扣除;
+/// 握手确认后, 扣除的 ACK Delay 不超过对方的 max_ack_delay.
+pub struct RttEstimator {
+    /// 最近一次的 RTT 样本
+    latest_rtt: Duration,
+
+    /// 最小 RTT, 不扣除 ACK Delay
+    min_rtt: Duration,
+
+    /// 平滑 RTT
+    smoothed_rtt: Duration,
+
+    /// RTT 平均偏差
+    rttvar: Duration,
+
+    /// 是否已经获得过 RTT 样本
+    has_sample: bool,
+}
+
+impl RttEstimator {
+    /// 构造一个 RTT 估算
+    ///
+    /// # Returns
+    /// 返回一个以 INITIAL_RTT 初始化的 RTT 估算
+    pub(crate) fn new() -> Self {
+        Self {
+            latest_rtt: Duration::ZERO,
+            min_rtt: Duration::ZERO,
+            smoothed_rtt: INITIAL_RTT,
+            rttvar: INITIAL_RTT / 2,
+            has_sample: false,
+        }
+    }
+
+    /// 使用新的 RTT 样本更新估算
+    ///
+    /// # Arguments
+    /// `latest_rtt` - RTT 样本
+    /// `ack_delay` - 对方报告的 ACK Delay
+    /// `max_ack_delay` - 对方延迟发送 ACK 的最大时长; 握手确认前为 None
+    pub(crate) fn update(
+        &mut self,
+        latest_rtt: Duration,
+        ack_delay: Duration,
+        max_ack_delay: Option<Duration>,
+    ) {
+        self.latest_rtt = latest_rtt;
+
+        if !self.has_sample {
+            self.has_sample = true;
+            self.min_rtt = latest_rtt;
+            self.smoothed_rtt = latest_rtt;
+            self.rttvar = latest_rtt / 2;
+            return;
+        }
+
+        self.min_rtt = self.min_rtt.min(latest_rtt);
+
+        let ack_delay = match max_ack_delay {
+            Some(max_ack_delay) => ack_delay.min(max_ack_delay),
+            None => ack_delay,
+        };
+
+        // 扣除 ACK Delay 后的样本不能小于 min_rtt.
+        let adjusted_rtt = if latest_rtt >= self.min_rtt + ack_delay {
+            latest_rtt - ack_delay
+        } else {
+            latest_rtt
+        };
+
+        let rttvar_sample = self.smoothed_rtt.abs_diff(adjusted_rtt);
+        self.rttvar = (self.rttvar * 3 + rttvar_sample) / 4;
+        self.smoothed_rtt = (self.smoothed_rtt * 7 + adjusted_rtt) / 8;
+    }
+
+    /// 判断是否已经获得过 RTT 样本
+    ///
+    /// # Returns
+    /// 返回是否已经获得过 RTT 样本
+    #[inline(always)]
+    pub const fn has_sample(&self) -> bool {
+        self.has_sample
+    }
+
+    /// 获取最近一次的 RTT 样本
+    ///
+    /// # Returns
+    /// 返回最近一次的 RTT 样本
+    #[inline(always)]
+    pub const fn get_latest_rtt(&self) -> Duration {
+        self.latest_rtt
+    }
+
+    /// 获取最小 RTT
+    ///
+    /// # Returns
+    /// 返回最小 RTT
+    #[inline(always)]
+    pub const fn get_min_rtt(&self) -> Duration {
+        self.min_rtt
+    }
+
+    /// 获取平滑 RTT
+    ///
+    /// # Returns
+    /// 返回平滑 RTT
+    #[inline(always)]
+    pub const fn get_smoothed_rtt(&self) -> Duration {
+        self.smoothed_rtt
+    }
+
+    /// 获取 RTT 平均偏差
+    ///
+    /// # Returns
+    /// 返回 RTT 平均偏差
+    #[inline(always)]
+    pub const fn get_rttvar(&self) -> Duration {
+        self.rttvar
+    }
+
+    /// 获取 PTO 基础时长, 即 smoothed_rtt + max(4 * rttvar, kGranularity)
+    ///
+    /// # Returns
+    /// 返回不含 max_ack_delay 与指数退避的 PTO 时长
+    pub(crate) fn get_pto_base(&self) -> Duration {
+        self.smoothed_rtt + (self.rttvar * 4).max(GRANULARITY)
+    }
+}
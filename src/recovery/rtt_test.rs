@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use super::RttEstimator;
+
+#[test]
+fn test_rtt_first_sample() {
+    let mut rtt = RttEstimator::new();
+    assert!(!rtt.has_sample());
+
+    // 首个样本不扣除 ACK Delay.
+    rtt.update(Duration::from_millis(100), Duration::from_millis(20), None);
+    assert_eq!(rtt.get_latest_rtt(), Duration::from_millis(100));
+    assert_eq!(rtt.get_min_rtt(), Duration::from_millis(100));
+    assert_eq!(rtt.get_smoothed_rtt(), Duration::from_millis(100));
+    assert_eq!(rtt.get_rttvar(), Duration::from_millis(50));
+}
+
+#[test]
+fn test_rtt_ack_delay_adjustment() {
+    let mut rtt = RttEstimator::new();
+    rtt.update(Duration::from_millis(100), Duration::ZERO, None);
+
+    // ACK Delay 被 max_ack_delay 限制为 25ms, 调整后样本为 100ms.
+    rtt.update(
+        Duration::from_millis(125),
+        Duration::from_millis(40),
+        Some(Duration::from_millis(25)),
+    );
+    assert_eq!(rtt.get_smoothed_rtt(), Duration::from_millis(100));
+    assert_eq!(rtt.get_rttvar(), Duration::from_micros(37500));
+
+    // 扣除 ACK Delay 后会小于 min_rtt, 因此不扣除.
+    rtt.update(
+        Duration::from_millis(108),
+        Duration::from_millis(10),
+        Some(Duration::from_millis(25)),
+    );
+    assert_eq!(rtt.get_smoothed_rtt(), Duration::from_millis(101));
+    assert_eq!(rtt.get_min_rtt(), Duration::from_millis(100));
+}
@@ -0,0 +1,321 @@
+use std::time::Instant;
+
+use crate::attr::{EcnCodepoint, PacketNumber, PacketNumberSpace, StreamId};
+
+use super::delivery_rate::DeliveryState;
+
+/// 已发送数据包中承载的帧
+///
+/// 仅记录丢包后重传所需的信息, 而不保存帧本身.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum SentFrame {
+    /// PADDING 帧
+    Padding,
+
+    /// PING 帧
+    Ping,
+
+    /// ACK 帧
+    Ack {
+        /// ACK 帧确认的最大数据包编号
+        largest: PacketNumber,
+    },
+
+    /// CRYPTO 帧
+    Crypto {
+        /// CRYPTO 数据的偏移量
+        offset: usize,
+        /// CRYPTO 数据的长度
+        len: usize,
+    },
+
+    /// STREAM 帧
+    Stream {
+        /// Stream 标识
+        stream_id: StreamId,
+        /// 数据的偏移量
+        offset: usize,
+        /// 数据的长度
+        len: usize,
+        /// 是否携带 FIN 标识
+        fin: bool,
+    },
+
+    /// MAX_DATA 帧
+    MaxData {
+        /// 通告的连接级限制
+        max: usize,
+    },
+
+    /// MAX_STREAM_DATA 帧
+    MaxStreamData {
+        /// Stream 标识
+        stream_id: StreamId,
+        /// 通告的流级限制
+        max: usize,
+    },
+
+    /// MAX_STREAMS 帧
+    MaxStreams {
+        /// 是否是双向流
+        bidi: bool,
+        /// 通告的流数量限制
+        max: usize,
+    },
+
+    /// DATA_BLOCKED 帧
+    DataBlocked {
+        /// 受阻时的连接级限制
+        max: usize,
+    },
+
+    /// STREAM_DATA_BLOCKED 帧
+    StreamDataBlocked {
+        /// Stream 标识
+        stream_id: StreamId,
+        /// 受阻时的流级限制
+        max: usize,
+    },
+
+    /// STREAMS_BLOCKED 帧
+    StreamsBlocked {
+        /// 是否是双向流
+        bidi: bool,
+        /// 受阻时的流数量限制
+        max: usize,
+    },
+
+    /// RESET_STREAM 或 RESET_STREAM_AT 帧
+    ResetStream {
+        /// Stream 标识
+        stream_id: StreamId,
+    },
+
+    /// STOP_SENDING 帧
+    StopSending {
+        /// Stream 标识
+        stream_id: StreamId,
+    },
+
+    /// NEW_CONNECTION_ID 帧
+    NewConnectionId {
+        /// Connection ID 的序列号
+        seq: u64,
+    },
+
+    /// RETIRE_CONNECTION_ID 帧
+    RetireConnectionId {
+        /// 废弃的 Connection ID 的序列号
+        seq: u64,
+    },
+
+    /// NEW_TOKEN 帧
+    NewToken {
+        /// 本端为 Token 分配的标识
+        id: u64,
+    },
+
+    /// HANDSHAKE_DONE 帧
+    HandshakeDone,
+
+    /// PATH_CHALLENGE 帧, 丢失后不重传, 由路径验证发送新的 PATH_CHALLENGE 帧
+    PathChallenge,
+
+    /// PATH_RESPONSE 帧, 丢失后不重传
+    PathResponse,
+
+    /// CONNECTION_CLOSE 帧
+    ConnectionClose,
+}
+
+impl SentFrame {
+    /// 判断该帧是否会引发对方回复 ACK
+    ///
+    /// 除 ACK、PADDING 与 CONNECTION_CLOSE 之外的帧都是 ack-eliciting 的.
+    ///
+    /// # Returns
+    /// 返回是否为 ack-eliciting 帧
+    pub const fn is_ack_eliciting(&self) -> bool {
+        !matches!(
+            self,
+            SentFrame::Padding | SentFrame::Ack { .. } | SentFrame::ConnectionClose
+        )
+    }
+}
+
+/// 已发送的数据包
+///
+/// 在数据包被确认、被判定为丢失或所在的编号空间被丢弃之前,
+/// 丢包检测会一直保存该记录.
+#[derive(Clone, Debug)]
+pub struct SentPacket {
+    /// 数据包编号
+    packet_number: PacketNumber,
+
+    /// 发送时间
+    time_sent: Instant,
+
+    /// 数据包所占字节数, 包括包头与 AEAD 认证标签
+    size: usize,
+
+    /// 是否为 ack-eliciting 数据包
+    ack_eliciting: bool,
+
+    /// 是否计入 bytes_in_flight
+    ///
+    /// ack-eliciting 数据包或携带 PADDING 帧的数据包均计入 bytes_in_flight.
+    in_flight: bool,
+
+    /// 数据包中承载的帧
+    frames: Vec<SentFrame>,
+
+    /// 发送时记录的连接交付状态, 用于交付速率采样
+    delivery_state: Option<DeliveryState>,
+
+    /// 发送时使用的 ECN 码点
+    ecn: EcnCodepoint,
+
+    /// 数据包所在的编号空间
+    space: PacketNumberSpace,
+}
+
+impl SentPacket {
+    /// 构造一个已发送数据包的记录
+    ///
+    /// # Arguments
+    /// `packet_number` - 数据包编号
+    /// `time_sent` - 发送时间
+    /// `size` - 数据包所占字节数
+    /// `frames` - 数据包中承载的帧
+    /// # Returns
+    /// 返回已发送数据包的记录
+    pub(crate) fn new(
+        packet_number: PacketNumber,
+        time_sent: Instant,
+        size: usize,
+        frames: Vec<SentFrame>,
+    ) -> Self {
+        let ack_eliciting = frames.iter().any(SentFrame::is_ack_eliciting);
+        let in_flight = ack_eliciting || frames.contains(&SentFrame::Padding);
+
+        Self {
+            packet_number,
+            time_sent,
+            size,
+            ack_eliciting,
+            in_flight,
+            frames,
+            delivery_state: None,
+            ecn: EcnCodepoint::NotEct,
+            space: PacketNumberSpace::ApplicationData,
+        }
+    }
+
+    /// 获取数据包编号
+    ///
+    /// # Returns
+    /// 返回数据包编号
+    #[inline(always)]
+    pub const fn get_packet_number(&self) -> PacketNumber {
+        self.packet_number
+    }
+
+    /// 获取发送时间
+    ///
+    /// # Returns
+    /// 返回发送时间
+    #[inline(always)]
+    pub const fn get_time_sent(&self) -> Instant {
+        self.time_sent
+    }
+
+    /// 获取数据包所占字节数
+    ///
+    /// # Returns
+    /// 返回数据包所占字节数
+    #[inline(always)]
+    pub const fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// 获取是否为 ack-eliciting 数据包
+    ///
+    /// # Returns
+    /// 返回是否为 ack-eliciting 数据包
+    #[inline(always)]
+    pub(crate) const fn is_ack_eliciting(&self) -> bool {
+        self.ack_eliciting
+    }
+
+    /// 获取是否计入 bytes_in_flight
+    ///
+    /// # Returns
+    /// 返回是否计入 bytes_in_flight
+    #[inline(always)]
+    pub const fn is_in_flight(&self) -> bool {
+        self.in_flight
+    }
+
+    /// 获取数据包中承载的帧
+    ///
+    /// # Returns
+    /// 返回数据包中承载的帧
+    #[inline(always)]
+    pub(crate) fn get_frames(&self) -> &[SentFrame] {
+        &self.frames
+    }
+
+    /// 获取发送时记录的连接交付状态
+    ///
+    /// # Returns
+    /// 返回连接交付状态
+    #[inline(always)]
+    pub(crate) const fn get_delivery_state(&self) -> Option<DeliveryState> {
+        self.delivery_state
+    }
+
+    /// 设置发送时记录的连接交付状态
+    ///
+    /// # Arguments
+    /// `delivery_state` - 连接交付状态
+    #[inline(always)]
+    pub(crate) fn set_delivery_state(&mut self, delivery_state: DeliveryState) {
+        self.delivery_state = Some(delivery_state)
+    }
+
+    /// 获取发送时使用的 ECN 码点
+    ///
+    /// # Returns
+    /// 返回 ECN 码点
+    #[inline(always)]
+    pub(crate) const fn get_ecn(&self) -> EcnCodepoint {
+        self.ecn
+    }
+
+    /// 设置发送时使用的 ECN 码点
+    ///
+    /// # Arguments
+    /// `ecn` - ECN 码点
+    #[inline(always)]
+    pub(crate) fn set_ecn(&mut self, ecn: EcnCodepoint) {
+        self.ecn = ecn
+    }
+
+    /// 获取数据包所在的编号空间
+    ///
+    /// # Returns
+    /// 返回编号空间, 不同编号空间的数据包编号不可比较
+    #[inline(always)]
+    pub(crate) const fn get_space(&self) -> PacketNumberSpace {
+        self.space
+    }
+
+    /// 设置数据包所在的编号空间
+    ///
+    /// # Arguments
+    /// `space` - 编号空间
+    #[inline(always)]
+    pub(crate) fn set_space(&mut self, space: PacketNumberSpace) {
+        self.space = space
+    }
+}
@@ -0,0 +1,350 @@
+use crate::{
+    attr::{Side, StreamDirection, StreamId, TransportParameters},
+    error::{TransportError, TransportErrorCode},
+    frame::{FrameType, MaxStreamsFrame, StreamsBlockedFrame},
+};
+
+/// 流数量限制的上限, 超过该值的流标识无法编码为变长整数
+const MAX_STREAMS_LIMIT: usize = 1 << 60;
+
+/// 单一方向 (双向或单向) 上的流数量状态
+struct StreamCount {
+    /// 本端已打开的流数量
+    local_opened: usize,
+
+    /// 对端允许本端打开的流数量
+    local_max: usize,
+
+    /// 最近一次发送 STREAMS_BLOCKED 帧时的限制
+    blocked_at: Option<usize>,
+
+    /// 本端是否因为流数量限制而无法打开流
+    blocked: bool,
+
+    /// 对端已打开的流数量
+    remote_opened: usize,
+
+    /// 对端已关闭的流数量
+    remote_closed: usize,
+
+    /// 本端允许对端打开的流数量
+    remote_max: usize,
+
+    /// 本端通告的初始流数量限制, 即对端可同时打开的流数量
+    remote_window: usize,
+}
+
+impl StreamCount {
+    fn new(local_max: usize, remote_max: usize) -> Self {
+        Self {
+            local_opened: 0,
+            local_max,
+            blocked_at: None,
+            blocked: false,
+            remote_opened: 0,
+            remote_closed: 0,
+            remote_max,
+            remote_window: remote_max,
+        }
+    }
+}
+
+/// 流数量管理
+///
+/// 按 initial_max_streams_bidi/uni 与 MAX_STREAMS 限制双方打开的流数量,
+/// 本端受限时发送 STREAMS_BLOCKED, 对端的流关闭后发送 MAX_STREAMS.
+pub(crate) struct StreamCountManager {
+    /// 本端角色
+    local: Side,
+
+    /// 双向流数量状态
+    bidi: StreamCount,
+
+    /// 单向流数量状态
+    uni: StreamCount,
+}
+
+impl StreamCountManager {
+    /// 构造一个流数量管理
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    /// `local_params` - 本端通告的传输参数
+    /// `peer_params` - 对端通告的传输参数
+    ///
+    /// # Returns
+    /// 返回流数量管理
+    pub(crate) fn new(
+        local: Side,
+        local_params: &TransportParameters,
+        peer_params: &TransportParameters,
+    ) -> Self {
+        Self {
+            local,
+            bidi: StreamCount::new(
+                peer_params.get_initial_max_streams_bidi(),
+                local_params.get_initial_max_streams_bidi(),
+            ),
+            uni: StreamCount::new(
+                peer_params.get_initial_max_streams_uni(),
+                local_params.get_initial_max_streams_uni(),
+            ),
+        }
+    }
+
+    /// 获取对端允许本端打开的流数量
+    ///
+    /// # Arguments
+    /// `direction` - 流的方向
+    ///
+    /// # Returns
+    /// 返回对端允许本端打开的流数量
+    pub(crate) fn get_local_max(&self, direction: StreamDirection) -> usize {
+        self.count(direction).local_max
+    }
+
+    /// 获取本端允许对端打开的流数量
+    ///
+    /// # Arguments
+    /// `direction` - 流的方向
+    ///
+    /// # Returns
+    /// 返回本端允许对端打开的流数量
+    pub(crate) fn get_remote_max(&self, direction: StreamDirection) -> usize {
+        self.count(direction).remote_max
+    }
+
+    /// 打开一个本端发起的流
+    ///
+    /// # Arguments
+    /// `direction` - 流的方向
+    ///
+    /// # Returns
+    /// 返回新流的标识, 受流数量限制时返回 None
+    pub(crate) fn open_local(&mut self, direction: StreamDirection) -> Option<StreamId> {
+        let local = self.local;
+        let count = self.count_mut(direction);
+        if count.local_opened >= count.local_max {
+            count.blocked = true;
+            return None;
+        }
+
+        let id = StreamId::nth(local, direction, count.local_opened as u64);
+        count.local_opened += 1;
+        Some(id)
+    }
+
+    /// 判断本端发起的流是否已经打开
+    ///
+    /// # Arguments
+    /// `stream_id` - 本端发起的流标识
+    ///
+    /// # Returns
+    /// 返回流是否已经由本端打开
+    pub(crate) fn is_local_opened(&self, stream_id: StreamId) -> bool {
+        (stream_id.get_index() as usize) < self.count(stream_id.get_direction()).local_opened
+    }
+
+    /// 处理对端的 MAX_STREAMS 帧
+    ///
+    /// # Arguments
+    /// `frame` - MAX_STREAMS 帧
+    ///
+    /// # Returns
+    /// 限制超过 2^60 时返回 FRAME_ENCODING_ERROR
+    pub(crate) fn on_max_streams_frame(
+        &mut self,
+        frame: &MaxStreamsFrame,
+    ) -> Result<(), TransportError> {
+        let max = frame.get_maximum_streams();
+        if max > MAX_STREAMS_LIMIT {
+            return Err(TransportError::new(
+                TransportErrorCode::FrameEncodingError,
+                u8::from(FrameType::MaxStreams {
+                    bidi_flag: frame.is_bidi(),
+                }) as u64,
+                "maximum streams exceeds 2^60",
+            ));
+        }
+
+        let count = self.count_mut(Self::direction(frame.is_bidi()));
+        if max > count.local_max {
+            count.local_max = max;
+            count.blocked = false;
+        }
+
+        Ok(())
+    }
+
+    /// 处理对端的 STREAMS_BLOCKED 帧
+    ///
+    /// # Arguments
+    /// `frame` - STREAMS_BLOCKED 帧
+    ///
+    /// # Returns
+    /// 限制超过 2^60 时返回 FRAME_ENCODING_ERROR
+    pub(crate) fn on_streams_blocked_frame(
+        &mut self,
+        frame: &StreamsBlockedFrame,
+    ) -> Result<(), TransportError> {
+        if frame.get_maximum_streams() > MAX_STREAMS_LIMIT {
+            return Err(TransportError::new(
+                TransportErrorCode::FrameEncodingError,
+                u8::from(FrameType::StreamsBlocked {
+                    bidi_flag: frame.is_bidi(),
+                }) as u64,
+                "maximum streams exceeds 2^60",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 生成 STREAMS_BLOCKED 帧, 每个限制值仅发送一次
+    ///
+    /// # Returns
+    /// 本端因流数量限制无法打开流时返回 STREAMS_BLOCKED 帧
+    pub(crate) fn poll_streams_blocked_frame(&mut self) -> Option<StreamsBlockedFrame> {
+        for direction in [StreamDirection::Bidi, StreamDirection::Uni] {
+            let count = self.count_mut(direction);
+            if count.blocked && count.blocked_at != Some(count.local_max) {
+                count.blocked_at = Some(count.local_max);
+
+                let mut frame = StreamsBlockedFrame::new(direction == StreamDirection::Bidi);
+                frame.set_maximum_streams(count.local_max);
+                return Some(frame);
+            }
+        }
+
+        None
+    }
+
+    /// 处理对端发起的流上收到的帧, 隐式打开所有序号更小的流
+    ///
+    /// # Arguments
+    /// `stream_id` - 对端发起的流标识
+    /// `frame_type` - 收到的帧类型
+    ///
+    /// # Returns
+    /// 返回本次新打开的流, 超过流数量限制时返回 STREAM_LIMIT_ERROR
+    pub(crate) fn on_remote_stream(
+        &mut self,
+        stream_id: StreamId,
+        frame_type: FrameType,
+    ) -> Result<Vec<StreamId>, TransportError> {
+        let remote = self.local.peer();
+        let direction = stream_id.get_direction();
+        let count = self.count_mut(direction);
+
+        let index = stream_id.get_index() as usize;
+        if index >= count.remote_max {
+            return Err(TransportError::new(
+                TransportErrorCode::StreamLimitError,
+                u8::from(frame_type) as u64,
+                "stream limit exceeded",
+            ));
+        }
+
+        let opened = (count.remote_opened..=index)
+            .map(|n| StreamId::nth(remote, direction, n as u64))
+            .collect();
+        count.remote_opened = count.remote_opened.max(index + 1);
+
+        Ok(opened)
+    }
+
+    /// 对端发起的流关闭
+    ///
+    /// # Arguments
+    /// `direction` - 流的方向
+    pub(crate) fn on_remote_stream_closed(&mut self, direction: StreamDirection) {
+        self.count_mut(direction).remote_closed += 1;
+    }
+
+    /// 判断是否需要发送 MAX_STREAMS 帧
+    ///
+    /// # Returns
+    /// 返回是否需要扩大对端可打开的流数量
+    pub(crate) fn should_update(&self) -> bool {
+        [StreamDirection::Bidi, StreamDirection::Uni]
+            .into_iter()
+            .any(|direction| Self::next_remote_max(self.count(direction)).is_some())
+    }
+
+    /// 生成 MAX_STREAMS 帧, 对端可打开的流数量不足初始限制的一半时扩大限制
+    ///
+    /// # Returns
+    /// 需要扩大限制时返回 MAX_STREAMS 帧
+    pub(crate) fn poll_max_streams_frame(&mut self) -> Option<MaxStreamsFrame> {
+        for direction in [StreamDirection::Bidi, StreamDirection::Uni] {
+            let count = self.count_mut(direction);
+            if let Some(max) = Self::next_remote_max(count) {
+                count.remote_max = max;
+                return Some(Self::max_streams_frame(direction, max));
+            }
+        }
+
+        None
+    }
+
+    /// MAX_STREAMS 帧丢失
+    ///
+    /// # Arguments
+    /// `bidi` - 是否是双向流
+    /// `max` - 丢失帧中的限制
+    ///
+    /// # Returns
+    /// 丢失的限制仍是最新值时返回需要重传的 MAX_STREAMS 帧
+    pub(crate) fn on_max_streams_lost(&self, bidi: bool, max: usize) -> Option<MaxStreamsFrame> {
+        let direction = Self::direction(bidi);
+        (self.count(direction).remote_max == max).then(|| Self::max_streams_frame(direction, max))
+    }
+
+    /// STREAMS_BLOCKED 帧丢失后允许再次发送
+    ///
+    /// # Arguments
+    /// `bidi` - 是否是双向流
+    /// `max` - 丢失帧中的限制
+    pub(crate) fn on_streams_blocked_lost(&mut self, bidi: bool, max: usize) {
+        let count = self.count_mut(Self::direction(bidi));
+        if count.blocked_at == Some(max) {
+            count.blocked_at = None;
+        }
+    }
+
+    fn direction(bidi: bool) -> StreamDirection {
+        if bidi {
+            StreamDirection::Bidi
+        } else {
+            StreamDirection::Uni
+        }
+    }
+
+    fn max_streams_frame(direction: StreamDirection, max: usize) -> MaxStreamsFrame {
+        let mut frame = MaxStreamsFrame::new(direction == StreamDirection::Bidi);
+        frame.set_maximum_streams(max);
+        frame
+    }
+
+    fn next_remote_max(count: &StreamCount) -> Option<usize> {
+        let max = count.remote_closed + count.remote_window;
+        (max > count.remote_max
+            && count.remote_max - count.remote_opened.min(count.remote_max)
+                < count.remote_window.div_ceil(2))
+        .then_some(max)
+    }
+
+    fn count(&self, direction: StreamDirection) -> &StreamCount {
+        match direction {
+            StreamDirection::Bidi => &self.bidi,
+            StreamDirection::Uni => &self.uni,
+        }
+    }
+
+    fn count_mut(&mut self, direction: StreamDirection) -> &mut StreamCount {
+        match direction {
+            StreamDirection::Bidi => &mut self.bidi,
+            StreamDirection::Uni => &mut self.uni,
+        }
+    }
+}
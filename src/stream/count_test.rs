@@ -0,0 +1,97 @@
+use crate::{
+    attr::{Serializer, Side, StreamDirection, StreamId, TransportParameters},
+    error::TransportErrorCode,
+    frame::{FrameType, MaxStreamsFrame},
+};
+
+use super::StreamCountManager;
+
+const STREAM: FrameType = FrameType::Stream {
+    off_flag: false,
+    len_flag: true,
+    fin_flag: false,
+};
+
+fn params(bidi: usize, uni: usize) -> TransportParameters {
+    let mut params = TransportParameters::new();
+    params.set_initial_max_streams_bidi(bidi);
+    params.set_initial_max_streams_uni(uni);
+    params
+}
+
+#[test]
+fn test_max_streams_frame() {
+    let mut frame = MaxStreamsFrame::new(false);
+    frame.set_maximum_streams(100);
+
+    let mut buf = Vec::new();
+    assert_eq!(frame.write(&mut buf).unwrap(), 3);
+    assert_eq!(buf, [0x13, 0x40, 0x64]);
+}
+
+#[test]
+fn test_local_stream_limit() {
+    let mut manager = StreamCountManager::new(Side::Client, &params(0, 0), &params(0, 1));
+
+    assert_eq!(
+        manager.open_local(StreamDirection::Uni),
+        Some(StreamId::new(2))
+    );
+    assert!(manager.open_local(StreamDirection::Uni).is_none());
+
+    let blocked = manager.poll_streams_blocked_frame().unwrap();
+    assert!(!blocked.is_bidi());
+    assert_eq!(blocked.get_maximum_streams(), 1);
+    assert!(manager.poll_streams_blocked_frame().is_none());
+
+    let mut frame = MaxStreamsFrame::new(false);
+    frame.set_maximum_streams(3);
+    manager.on_max_streams_frame(&frame).unwrap();
+    assert_eq!(
+        manager.open_local(StreamDirection::Uni),
+        Some(StreamId::new(6))
+    );
+
+    assert!(manager.is_local_opened(StreamId::new(6)));
+    assert!(!manager.is_local_opened(StreamId::new(10)));
+    assert!(!manager.is_local_opened(StreamId::new(0)));
+
+    frame.set_maximum_streams((1 << 60) + 1);
+    let err = manager.on_max_streams_frame(&frame).unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::FrameEncodingError);
+}
+
+#[test]
+fn test_remote_stream_limit() {
+    let mut manager = StreamCountManager::new(Side::Server, &params(4, 0), &params(0, 0));
+
+    // 隐式打开序号更小的流
+    let opened = manager.on_remote_stream(StreamId::new(8), STREAM).unwrap();
+    assert_eq!(
+        opened,
+        vec![StreamId::new(0), StreamId::new(4), StreamId::new(8)]
+    );
+    assert!(manager
+        .on_remote_stream(StreamId::new(4), STREAM)
+        .unwrap()
+        .is_empty());
+
+    let err = manager
+        .on_remote_stream(StreamId::new(16), STREAM)
+        .unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::StreamLimitError);
+
+    // 对端可打开的流数量不足初始限制的一半时扩大限制
+    assert!(!manager.should_update());
+    manager.on_remote_stream_closed(StreamDirection::Bidi);
+    assert!(manager.should_update());
+    let frame = manager.poll_max_streams_frame().unwrap();
+    assert!(frame.is_bidi());
+    assert_eq!(frame.get_maximum_streams(), 5);
+    assert!(!manager.should_update());
+    manager.on_remote_stream_closed(StreamDirection::Bidi);
+    assert!(manager.poll_max_streams_frame().is_none());
+
+    assert!(manager.on_max_streams_lost(true, 5).is_some());
+    assert!(manager.on_max_streams_lost(true, 4).is_none());
+}
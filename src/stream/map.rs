@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    attr::{Side, StreamIDGetter, StreamId},
+    error::{StreamError, TransportError, TransportErrorCode},
+    frame::{ResetStreamAtFrame, ResetStreamFrame, StopSendingFrame},
+};
+
+use super::{recv::RecvStream, send::SendStream};
+
+/// 流集合
+///
+/// 按流标识保存各个流的发送部分与接收部分, 并提供应用层终止流的操作.
+pub(crate) struct StreamMap {
+    /// 本端角色
+    local: Side,
+
+    /// 发送部分
+    send: BTreeMap<StreamId, SendStream>,
+
+    /// 接收部分
+    recv: BTreeMap<StreamId, RecvStream>,
+
+    /// 双方是否都支持 RESET_STREAM_AT 帧
+    reset_stream_at: bool,
+}
+
+impl StreamMap {
+    /// 构造一个流集合
+    ///
+    /// # Arguments
+    /// `local` - 本端角色
+    ///
+    /// # Returns
+    /// 返回流集合
+    pub(crate) fn new(local: Side) -> Self {
+        Self {
+            local,
+            send: BTreeMap::new(),
+            recv: BTreeMap::new(),
+            reset_stream_at: false,
+        }
+    }
+
+    /// 设置双方是否都支持 RESET_STREAM_AT 帧
+    ///
+    /// # Arguments
+    /// `reset_stream_at` - 双方是否都通告了 reset_stream_at 传输参数
+    pub(crate) fn set_reset_stream_at(&mut self, reset_stream_at: bool) {
+        self.reset_stream_at = reset_stream_at
+    }
+
+    /// 获取双方是否都支持 RESET_STREAM_AT 帧
+    ///
+    /// # Returns
+    /// 返回双方是否都通告了 reset_stream_at 传输参数
+    #[inline(always)]
+    pub(crate) const fn get_reset_stream_at(&self) -> bool {
+        self.reset_stream_at
+    }
+
+    /// 创建一个流, 按流的方向与发起方创建发送部分与接收部分
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    pub(crate) fn insert(&mut self, stream_id: StreamId) {
+        if stream_id.is_sendable(self.local) {
+            self.send
+                .entry(stream_id)
+                .or_insert_with(|| SendStream::new(stream_id, self.local));
+        }
+        if stream_id.is_receivable(self.local) {
+            self.recv
+                .entry(stream_id)
+                .or_insert_with(|| RecvStream::new(stream_id, self.local));
+        }
+    }
+
+    /// 获取流的发送部分
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    ///
+    /// # Returns
+    /// 返回流的发送部分
+    pub(crate) fn get_send(&self, stream_id: StreamId) -> Option<&SendStream> {
+        self.send.get(&stream_id)
+    }
+
+    /// 获取流的发送部分
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    ///
+    /// # Returns
+    /// 返回流的发送部分
+    pub(crate) fn get_send_mut(&mut self, stream_id: StreamId) -> Option<&mut SendStream> {
+        self.send.get_mut(&stream_id)
+    }
+
+    /// 获取流的接收部分
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    ///
+    /// # Returns
+    /// 返回流的接收部分
+    pub(crate) fn get_recv(&self, stream_id: StreamId) -> Option<&RecvStream> {
+        self.recv.get(&stream_id)
+    }
+
+    /// 获取流的接收部分
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    ///
+    /// # Returns
+    /// 返回流的接收部分
+    pub(crate) fn get_recv_mut(&mut self, stream_id: StreamId) -> Option<&mut RecvStream> {
+        self.recv.get_mut(&stream_id)
+    }
+
+    /// 遍历所有流的发送部分
+    ///
+    /// # Returns
+    /// 返回按流标识升序排列的发送部分
+    pub(crate) fn iter_send_mut(&mut self) -> impl Iterator<Item = &mut SendStream> {
+        self.send.values_mut()
+    }
+
+    /// 遍历所有流的接收部分
+    ///
+    /// # Returns
+    /// 返回按流标识升序排列的接收部分
+    pub(crate) fn iter_recv_mut(&mut self) -> impl Iterator<Item = &mut RecvStream> {
+        self.recv.values_mut()
+    }
+
+    /// 重置流的发送部分, 之后将发送 RESET_STREAM 帧
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `error_code` - 应用错误码
+    ///
+    /// # Returns
+    /// 流不存在或没有发送部分时返回错误
+    pub(crate) fn reset(
+        &mut self,
+        stream_id: StreamId,
+        error_code: u64,
+    ) -> Result<(), StreamError> {
+        let stream = self
+            .send
+            .get_mut(&stream_id)
+            .ok_or(StreamError::UnknownStream)?;
+        stream.reset(error_code);
+        Ok(())
+    }
+
+    /// 以 RESET_STREAM_AT 重置流的发送部分, 可靠大小之前的数据仍会被交付
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `error_code` - 应用错误码
+    /// `reliable_size` - 可靠大小
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 流不存在、没有发送部分或对端不支持 RESET_STREAM_AT 时返回错误
+    pub(crate) fn reset_at(
+        &mut self,
+        stream_id: StreamId,
+        error_code: u64,
+        reliable_size: usize,
+        max_offset: usize,
+    ) -> Result<(), StreamError> {
+        if !self.reset_stream_at {
+            return Err(StreamError::Unsupported);
+        }
+
+        let stream = self
+            .send
+            .get_mut(&stream_id)
+            .ok_or(StreamError::UnknownStream)?;
+        stream.reset_at(error_code, reliable_size, max_offset);
+        Ok(())
+    }
+
+    /// 要求对端停止在流上发送, 之后将发送 STOP_SENDING 帧
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `error_code` - 应用错误码
+    ///
+    /// # Returns
+    /// 流不存在或没有接收部分时返回错误
+    pub(crate) fn stop_sending(
+        &mut self,
+        stream_id: StreamId,
+        error_code: u64,
+    ) -> Result<(), StreamError> {
+        let stream = self
+            .recv
+            .get_mut(&stream_id)
+            .ok_or(StreamError::UnknownStream)?;
+        stream.stop_sending(error_code);
+        Ok(())
+    }
+
+    /// 处理对端的 STOP_SENDING 帧, 对应的流将以 RESET_STREAM 应答
+    ///
+    /// # Arguments
+    /// `frame` - STOP_SENDING 帧
+    ///
+    /// # Returns
+    /// 本端仅接收的流上收到 STOP_SENDING 时返回 STREAM_STATE_ERROR
+    pub(crate) fn on_stop_sending_frame(
+        &mut self,
+        frame: &StopSendingFrame,
+    ) -> Result<(), TransportError> {
+        frame.check_direction(self.local)?;
+
+        match self.send.get_mut(&frame.get_stream_id()) {
+            Some(stream) => stream.on_stop_sending_frame(frame),
+            None => Ok(()),
+        }
+    }
+
+    /// 判断是否有待发送的 RESET_STREAM、RESET_STREAM_AT 或 STOP_SENDING 帧
+    ///
+    /// # Returns
+    /// 返回是否有待发送的帧
+    pub(crate) fn has_pending_frames(&self) -> bool {
+        self.send.values().any(SendStream::has_pending_reset)
+            || self.recv.values().any(RecvStream::has_pending_stop_sending)
+    }
+
+    /// 生成待发送的 RESET_STREAM 帧
+    ///
+    /// # Returns
+    /// 返回 RESET_STREAM 帧
+    pub(crate) fn poll_reset_stream_frame(&mut self) -> Option<ResetStreamFrame> {
+        self.send
+            .values_mut()
+            .find_map(|stream| stream.poll_reset_stream_frame())
+    }
+
+    /// 生成待发送的 RESET_STREAM_AT 帧
+    ///
+    /// # Returns
+    /// 返回 RESET_STREAM_AT 帧
+    pub(crate) fn poll_reset_stream_at_frame(&mut self) -> Option<ResetStreamAtFrame> {
+        self.send
+            .values_mut()
+            .find_map(|stream| stream.poll_reset_stream_at_frame())
+    }
+
+    /// 生成待发送的 STOP_SENDING 帧
+    ///
+    /// # Returns
+    /// 返回 STOP_SENDING 帧
+    pub(crate) fn poll_stop_sending_frame(&mut self) -> Option<StopSendingFrame> {
+        self.recv
+            .values_mut()
+            .find_map(|stream| stream.poll_stop_sending_frame())
+    }
+
+    /// 移除发送部分与接收部分均已处于终止状态的流
+    ///
+    /// # Returns
+    /// 返回被移除的流标识
+    pub(crate) fn collect_closed(&mut self) -> Vec<StreamId> {
+        let mut ids = self
+            .send
+            .keys()
+            .chain(self.recv.keys())
+            .copied()
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+
+        ids.retain(|id| {
+            self.send.get(id).is_none_or(|stream| stream.is_terminal())
+                && self.recv.get(id).is_none_or(|stream| stream.is_terminal())
+        });
+        for id in &ids {
+            self.send.remove(id);
+            self.recv.remove(id);
+        }
+
+        ids
+    }
+}
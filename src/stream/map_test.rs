@@ -0,0 +1,101 @@
+use crate::{
+    attr::{Side, StreamDataGetter, StreamDirection, StreamIDGetter, StreamIDSetter, StreamId},
+    error::StreamError,
+    frame::StopSendingFrame,
+};
+
+use super::{RecvState, SendState, StreamMap};
+
+#[test]
+fn test_reset_and_stop_sending() {
+    let mut streams = StreamMap::new(Side::Client);
+    let bidi = StreamId::nth(Side::Client, StreamDirection::Bidi, 0);
+    let uni = StreamId::nth(Side::Server, StreamDirection::Uni, 0);
+    streams.insert(bidi);
+    streams.insert(uni);
+
+    let send = streams.get_send_mut(bidi).unwrap();
+    send.write(b"hello").unwrap();
+    send.poll_stream_frame(1200, usize::MAX).unwrap();
+
+    streams.reset(bidi, 5).unwrap();
+    let frame = streams.poll_reset_stream_frame().unwrap();
+    assert_eq!(frame.get_stream_id(), bidi);
+    assert_eq!(frame.get_error_code(), 5);
+    assert_eq!(frame.get_final_size(), 5);
+    assert!(streams.poll_reset_stream_frame().is_none());
+
+    // 对端发起的单向流没有发送部分
+    assert_eq!(streams.reset(uni, 5), Err(StreamError::UnknownStream));
+
+    streams.stop_sending(uni, 6).unwrap();
+    let frame = streams.poll_stop_sending_frame().unwrap();
+    assert_eq!(frame.get_stream_id(), uni);
+    assert_eq!(frame.get_error_code(), 6);
+    streams.get_recv_mut(uni).unwrap().on_stop_sending_lost();
+    assert!(streams.poll_stop_sending_frame().is_some());
+    assert_eq!(streams.get_recv(uni).unwrap().get_state(), RecvState::Recv);
+}
+
+#[test]
+fn test_answer_stop_sending() {
+    let mut streams = StreamMap::new(Side::Server);
+    let id = StreamId::nth(Side::Client, StreamDirection::Bidi, 0);
+    streams.insert(id);
+
+    let send = streams.get_send_mut(id).unwrap();
+    send.write(b"hello world").unwrap();
+    send.poll_stream_frame(8, usize::MAX).unwrap();
+
+    let mut frame = StopSendingFrame::new();
+    frame.set_stream_id(id);
+    frame.set_error_code(42);
+    streams.on_stop_sending_frame(&frame).unwrap();
+
+    let send = streams.get_send_mut(id).unwrap();
+    assert_eq!(send.get_state(), SendState::ResetSent);
+    assert_eq!(send.write(b"!"), Err(StreamError::Stopped(42)));
+
+    let reset = streams.poll_reset_stream_frame().unwrap();
+    assert_eq!(reset.get_error_code(), 42);
+    assert_eq!(reset.get_final_size(), 6);
+}
+
+#[test]
+fn test_reset_stream_at() {
+    let mut streams = StreamMap::new(Side::Client);
+    let id = StreamId::nth(Side::Client, StreamDirection::Uni, 0);
+    streams.insert(id);
+    streams
+        .get_send_mut(id)
+        .unwrap()
+        .write(b"headerbody")
+        .unwrap();
+
+    assert_eq!(
+        streams.reset_at(id, 1, 6, usize::MAX),
+        Err(StreamError::Unsupported)
+    );
+    streams.set_reset_stream_at(true);
+
+    // 重置时尚未发送的可靠数据仍会被发送
+    let send = streams.get_send_mut(id).unwrap();
+    send.poll_stream_frame(5, usize::MAX).unwrap();
+    streams.reset_at(id, 1, 6, usize::MAX).unwrap();
+    assert!(streams.poll_reset_stream_frame().is_none());
+    let frame = streams.poll_reset_stream_at_frame().unwrap();
+    assert_eq!(frame.get_reliable_size(), 6);
+    assert_eq!(frame.get_final_size(), 6);
+
+    let send = streams.get_send_mut(id).unwrap();
+    let stream = send.poll_stream_frame(1200, usize::MAX).unwrap();
+    assert_eq!(stream.get_data(), (3, &b"der"[..]));
+    assert!(send.poll_stream_frame(1200, usize::MAX).is_none());
+
+    send.on_reset_acked();
+    assert_eq!(send.get_state(), SendState::ResetSent);
+    send.on_stream_frame_lost(0, 3, false);
+    send.on_stream_frame_acked(3, 3, false);
+    send.on_stream_frame_acked(0, 3, false);
+    assert_eq!(send.get_state(), SendState::ResetRecvd);
+}
@@ -0,0 +1,34 @@
+mod count;
+mod map;
+mod recv;
+mod recv_buf;
+mod scheduler;
+mod send;
+mod send_buf;
+
+pub(crate) use count::*;
+pub(crate) use map::*;
+pub(crate) use recv::*;
+pub(crate) use recv_buf::*;
+pub(crate) use scheduler::*;
+pub(crate) use send::*;
+pub(crate) use send_buf::*;
+
+pub use recv::RecvState;
+pub use scheduler::Priority;
+pub use send::SendState;
+
+#[cfg(test)]
+mod count_test;
+#[cfg(test)]
+mod map_test;
+#[cfg(test)]
+mod recv_buf_test;
+#[cfg(test)]
+mod recv_test;
+#[cfg(test)]
+mod scheduler_test;
+#[cfg(test)]
+mod send_buf_test;
+#[cfg(test)]
+mod send_test;
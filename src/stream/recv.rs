@@ -0,0 +1,349 @@
+use crate::{
+    attr::{Side, StreamIDSetter, StreamId},
+    error::{StreamError, TransportError, TransportErrorCode},
+    frame::{FrameType, ResetStreamAtFrame, ResetStreamFrame, StopSendingFrame, StreamFrame},
+};
+
+use super::recv_buf::RecvBuffer;
+
+/// 接收流状态 (RFC 9000 §3.2)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RecvState {
+    /// 正在接收数据
+    Recv,
+
+    /// 已收到 FIN, 流的最终大小已知
+    SizeKnown,
+
+    /// 已收到全部数据
+    DataRecvd,
+
+    /// 已收到 RESET_STREAM 帧
+    ResetRecvd,
+
+    /// 应用层已读取全部数据, 终止状态
+    DataRead,
+
+    /// 应用层已得知流被重置, 终止状态
+    ResetRead,
+}
+
+/// 接收流
+///
+/// 由接收缓冲区与接收流状态机组成. 对端的 STREAM 与 RESET_STREAM 帧、
+/// 以及应用层读取驱动状态转换.
+pub(crate) struct RecvStream {
+    /// 流标识
+    stream_id: StreamId,
+
+    /// 本端角色
+    local: Side,
+
+    /// 接收流状态
+    state: RecvState,
+
+    /// 接收缓冲区
+    buffer: RecvBuffer,
+
+    /// 对端 RESET_STREAM 或 RESET_STREAM_AT 帧中的应用错误码
+    reset_error_code: Option<u64>,
+
+    /// 对端 RESET_STREAM_AT 帧中的可靠大小, RESET_STREAM 视为可靠大小为 0
+    reliable_size: usize,
+
+    /// 本端要求对端停止发送时的应用错误码
+    stop_error_code: Option<u64>,
+
+    /// 是否有待发送的 STOP_SENDING 帧
+    stop_pending: bool,
+}
+
+impl RecvStream {
+    /// 构造一个接收流
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `local` - 本端角色
+    ///
+    /// # Returns
+    /// 返回接收流
+    pub(crate) fn new(stream_id: StreamId, local: Side) -> Self {
+        Self {
+            stream_id,
+            local,
+            state: RecvState::Recv,
+            buffer: RecvBuffer::new(),
+            reset_error_code: None,
+            reliable_size: 0,
+            stop_error_code: None,
+            stop_pending: false,
+        }
+    }
+
+    /// 获取流标识
+    ///
+    /// # Returns
+    /// 返回流标识
+    #[inline(always)]
+    pub(crate) const fn get_stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// 获取接收流状态
+    ///
+    /// # Returns
+    /// 返回接收流状态
+    #[inline(always)]
+    pub(crate) const fn get_state(&self) -> RecvState {
+        self.state
+    }
+
+    /// 获取接收缓冲区
+    ///
+    /// # Returns
+    /// 返回接收缓冲区
+    #[inline(always)]
+    pub(crate) const fn get_buffer(&self) -> &RecvBuffer {
+        &self.buffer
+    }
+
+    /// 获取不再占用流量控制信用的字节数, 即应用层已读取的数据加上因流被重置而
+    /// 不会再交付的数据. 该值只增不减.
+    ///
+    /// # Returns
+    /// 返回已读取或已丢弃的字节数
+    pub(crate) fn get_consumed(&self) -> usize {
+        let read_offset = self.buffer.get_read_offset();
+        match (self.state, self.buffer.get_final_size()) {
+            (RecvState::ResetRead, Some(final_size)) => final_size,
+            // 可靠大小之前尚未读取的数据仍会交付
+            (_, Some(final_size)) if self.reset_error_code.is_some() => {
+                final_size - self.reliable_size.saturating_sub(read_offset)
+            }
+            _ => read_offset,
+        }
+    }
+
+    /// 判断接收流是否处于终止状态
+    ///
+    /// # Returns
+    /// 返回是否处于终止状态
+    pub(crate) fn is_terminal(&self) -> bool {
+        matches!(self.state, RecvState::DataRead | RecvState::ResetRead)
+    }
+
+    /// 判断应用层是否有可读取的数据或事件
+    ///
+    /// # Returns
+    /// 返回是否可读
+    pub(crate) fn is_readable(&self) -> bool {
+        match self.state {
+            RecvState::Recv | RecvState::SizeKnown => self.buffer.get_readable() != 0,
+            RecvState::DataRecvd | RecvState::ResetRecvd => true,
+            RecvState::DataRead | RecvState::ResetRead => false,
+        }
+    }
+
+    /// 处理对端的 STREAM 帧
+    ///
+    /// 收到 FIN 后进入 Size Known 状态, 收到全部数据后进入 Data Recvd 状态.
+    ///
+    /// # Arguments
+    /// `frame` - STREAM 帧
+    ///
+    /// # Returns
+    /// 本端仅发送的流上收到 STREAM 时返回 STREAM_STATE_ERROR;
+    /// 最终大小不一致时返回 FINAL_SIZE_ERROR
+    pub(crate) fn on_stream_frame(&mut self, frame: &StreamFrame) -> Result<(), TransportError> {
+        frame.check_direction(self.local)?;
+
+        match self.state {
+            RecvState::Recv | RecvState::SizeKnown => {
+                self.buffer.on_stream_frame(frame)?;
+                self.update_recv_state();
+            }
+            // 已收到全部数据或已被重置, 仅校验最终大小
+            RecvState::DataRecvd | RecvState::ResetRecvd => {
+                self.buffer.on_stream_frame(frame)?;
+            }
+            RecvState::DataRead | RecvState::ResetRead => {}
+        }
+
+        Ok(())
+    }
+
+    /// 处理对端的 RESET_STREAM 帧, 丢弃尚未读取的数据并进入 Reset Recvd 状态
+    ///
+    /// # Arguments
+    /// `frame` - RESET_STREAM 帧
+    ///
+    /// # Returns
+    /// 本端仅发送的流上收到 RESET_STREAM 时返回 STREAM_STATE_ERROR;
+    /// 最终大小不一致时返回 FINAL_SIZE_ERROR
+    pub(crate) fn on_reset_stream_frame(
+        &mut self,
+        frame: &ResetStreamFrame,
+    ) -> Result<(), TransportError> {
+        frame.check_direction(self.local)?;
+
+        if !self.is_terminal() {
+            self.buffer.on_reset_stream_frame(frame)?;
+        }
+        self.on_reset(frame.get_error_code(), 0);
+
+        Ok(())
+    }
+
+    /// 处理对端的 RESET_STREAM_AT 帧
+    ///
+    /// 收到可靠大小之前的全部数据后进入 Reset Recvd 状态, 应用层读取完这部分数据后
+    /// 才得知流被重置. 多次收到时可靠大小只能减小.
+    ///
+    /// # Arguments
+    /// `frame` - RESET_STREAM_AT 帧
+    ///
+    /// # Returns
+    /// 本端仅发送的流上收到时返回 STREAM_STATE_ERROR; 可靠大小超过最终大小时返回
+    /// FRAME_ENCODING_ERROR; 最终大小不一致时返回 FINAL_SIZE_ERROR
+    pub(crate) fn on_reset_stream_at_frame(
+        &mut self,
+        frame: &ResetStreamAtFrame,
+    ) -> Result<(), TransportError> {
+        frame.check_direction(self.local)?;
+        if frame.get_reliable_size() > frame.get_final_size() {
+            return Err(TransportError::new(
+                TransportErrorCode::FrameEncodingError,
+                u8::from(FrameType::ResetStreamAt) as u64,
+                "reliable size exceeds final size",
+            ));
+        }
+
+        if !self.is_terminal() {
+            self.buffer.on_reset_stream_at_frame(frame)?;
+        }
+        self.on_reset(frame.get_error_code(), frame.get_reliable_size());
+
+        Ok(())
+    }
+
+    /// 按序读取数据
+    ///
+    /// 读取全部数据后进入 Data Read 状态; 流被重置时返回错误并进入 Reset Read 状态.
+    ///
+    /// # Arguments
+    /// `buf` - 读取缓冲区
+    ///
+    /// # Returns
+    /// 返回读取的字节数, 流已读取完毕时返回 0; 流被重置时返回错误
+    pub(crate) fn read(&mut self, buf: &mut [u8]) -> Result<usize, StreamError> {
+        match self.state {
+            RecvState::ResetRecvd | RecvState::ResetRead => {
+                // 先交付可靠大小之前的数据
+                let limit = self
+                    .reliable_size
+                    .saturating_sub(self.buffer.get_read_offset())
+                    .min(buf.len());
+                if self.state == RecvState::ResetRecvd && limit != 0 {
+                    return Ok(self.buffer.read(&mut buf[..limit]));
+                }
+
+                self.state = RecvState::ResetRead;
+                Err(StreamError::Reset(
+                    self.reset_error_code.unwrap_or_default(),
+                ))
+            }
+            _ => {
+                let size = self.buffer.read(buf);
+                if self.state == RecvState::DataRecvd && self.buffer.is_finished() {
+                    self.state = RecvState::DataRead;
+                }
+                Ok(size)
+            }
+        }
+    }
+
+    /// 要求对端停止发送, 仅在尚未收到全部数据或 RESET_STREAM 时发送 STOP_SENDING
+    ///
+    /// # Arguments
+    /// `error_code` - 应用错误码
+    ///
+    /// # Returns
+    /// 返回是否需要发送 STOP_SENDING 帧
+    pub(crate) fn stop_sending(&mut self, error_code: u64) -> bool {
+        if !matches!(self.state, RecvState::Recv | RecvState::SizeKnown)
+            || self.stop_error_code.is_some()
+        {
+            return false;
+        }
+
+        self.stop_error_code = Some(error_code);
+        self.stop_pending = true;
+        true
+    }
+
+    /// 判断是否有待发送的 STOP_SENDING 帧
+    ///
+    /// # Returns
+    /// 返回是否有待发送的 STOP_SENDING 帧
+    #[inline(always)]
+    pub(crate) const fn has_pending_stop_sending(&self) -> bool {
+        self.stop_pending
+    }
+
+    /// 生成 STOP_SENDING 帧
+    ///
+    /// # Returns
+    /// 有待发送的 STOP_SENDING 帧时返回该帧
+    pub(crate) fn poll_stop_sending_frame(&mut self) -> Option<StopSendingFrame> {
+        if !self.stop_pending {
+            return None;
+        }
+        self.stop_pending = false;
+
+        let mut frame = StopSendingFrame::new();
+        frame.set_stream_id(self.stream_id);
+        frame.set_error_code(self.stop_error_code.unwrap_or_default());
+        Some(frame)
+    }
+
+    /// STOP_SENDING 帧丢失, 对端尚未重置流时重传
+    pub(crate) fn on_stop_sending_lost(&mut self) {
+        if matches!(self.state, RecvState::Recv | RecvState::SizeKnown) {
+            self.stop_pending = true;
+        }
+    }
+
+    fn on_reset(&mut self, error_code: u64, reliable_size: usize) {
+        if !matches!(self.state, RecvState::Recv | RecvState::SizeKnown)
+            || self
+                .reset_error_code
+                .is_some_and(|_| reliable_size >= self.reliable_size)
+        {
+            return;
+        }
+
+        self.reset_error_code.get_or_insert(error_code);
+        self.reliable_size = reliable_size;
+        if reliable_size == 0 {
+            self.buffer.clear();
+        }
+        self.update_recv_state();
+    }
+
+    fn update_recv_state(&mut self) {
+        // 以 RESET_STREAM_AT 重置后, 收到可靠大小之前的全部数据即进入 Reset Recvd 状态
+        if self.reset_error_code.is_some()
+            && self.buffer.get_read_offset() + self.buffer.get_readable() >= self.reliable_size
+        {
+            self.state = RecvState::ResetRecvd;
+            return;
+        }
+
+        if self.buffer.get_final_size().is_some() && self.state == RecvState::Recv {
+            self.state = RecvState::SizeKnown;
+        }
+        if self.state == RecvState::SizeKnown && self.buffer.is_all_received() {
+            self.state = RecvState::DataRecvd;
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    attr::StreamDataGetter,
+    error::{TransportError, TransportErrorCode},
+    frame::{FrameType, ResetStreamAtFrame, ResetStreamFrame, StreamFrame},
+};
+
+/// 流接收缓冲区
+///
+/// 按偏移量缓存乱序到达的流数据, 去除重叠与重复部分,
+/// 并按序向应用层交付连续的字节.
+pub(crate) struct RecvBuffer {
+    /// 已接收但尚未被读取的数据片段, 以偏移量为键, 片段之间互不重叠
+    segments: BTreeMap<usize, Vec<u8>>,
+
+    /// 应用层已读取的偏移量
+    read_offset: usize,
+
+    /// 已接收数据的最大偏移量
+    max_offset: usize,
+
+    /// 由 FIN 或 RESET_STREAM 确定的最终大小
+    final_size: Option<usize>,
+}
+
+impl RecvBuffer {
+    /// 构造一个流接收缓冲区
+    ///
+    /// # Returns
+    /// 返回流接收缓冲区
+    pub(crate) fn new() -> Self {
+        Self {
+            segments: BTreeMap::new(),
+            read_offset: 0,
+            max_offset: 0,
+            final_size: None,
+        }
+    }
+
+    /// 获取应用层已读取的偏移量
+    ///
+    /// # Returns
+    /// 返回已读取的偏移量
+    #[inline(always)]
+    pub(crate) const fn get_read_offset(&self) -> usize {
+        self.read_offset
+    }
+
+    /// 获取已接收数据的最大偏移量
+    ///
+    /// # Returns
+    /// 返回已接收数据的最大偏移量
+    #[inline(always)]
+    pub(crate) const fn get_max_offset(&self) -> usize {
+        self.max_offset
+    }
+
+    /// 获取流的最终大小
+    ///
+    /// # Returns
+    /// 返回流的最终大小, 未知时返回 None
+    #[inline(always)]
+    pub(crate) const fn get_final_size(&self) -> Option<usize> {
+        self.final_size
+    }
+
+    /// 判断是否已经接收到全部数据
+    ///
+    /// # Returns
+    /// 返回是否已经接收到全部数据
+    pub(crate) fn is_all_received(&self) -> bool {
+        self.final_size
+            .is_some_and(|final_size| self.readable_end() == final_size)
+    }
+
+    /// 判断应用层是否已经读取全部数据
+    ///
+    /// # Returns
+    /// 返回应用层是否已读取全部数据
+    pub(crate) fn is_finished(&self) -> bool {
+        self.final_size == Some(self.read_offset)
+    }
+
+    /// 获取可以按序读取的字节数
+    ///
+    /// # Returns
+    /// 返回可读字节数
+    pub(crate) fn get_readable(&self) -> usize {
+        self.readable_end() - self.read_offset
+    }
+
+    /// 接收一个 STREAM 帧
+    ///
+    /// # Arguments
+    /// `frame` - STREAM 帧
+    pub(crate) fn on_stream_frame(&mut self, frame: &StreamFrame) -> Result<(), TransportError> {
+        let (offset, data) = frame.get_data();
+
+        self.insert(offset, data, frame.get_fin_flag())
+            .map_err(|reason| {
+                TransportError::new(
+                    TransportErrorCode::FinalSizeError,
+                    u8::from(FrameType::Stream {
+                        off_flag: frame.get_off_flag(),
+                        len_flag: frame.get_len_flag(),
+                        fin_flag: frame.get_fin_flag(),
+                    }) as u64,
+                    reason,
+                )
+            })
+    }
+
+    /// 接收一个 RESET_STREAM 帧, 仅记录并校验最终大小
+    ///
+    /// # Arguments
+    /// `frame` - RESET_STREAM 帧
+    pub(crate) fn on_reset_stream_frame(
+        &mut self,
+        frame: &ResetStreamFrame,
+    ) -> Result<(), TransportError> {
+        self.set_final_size(frame.get_final_size())
+            .map_err(|reason| {
+                TransportError::new(
+                    TransportErrorCode::FinalSizeError,
+                    u8::from(FrameType::ResetStream) as u64,
+                    reason,
+                )
+            })
+    }
+
+    /// 接收一个 RESET_STREAM_AT 帧, 仅记录并校验最终大小
+    ///
+    /// # Arguments
+    /// `frame` - RESET_STREAM_AT 帧
+    pub(crate) fn on_reset_stream_at_frame(
+        &mut self,
+        frame: &ResetStreamAtFrame,
+    ) -> Result<(), TransportError> {
+        self.set_final_size(frame.get_final_size())
+            .map_err(|reason| {
+                TransportError::new(
+                    TransportErrorCode::FinalSizeError,
+                    u8::from(FrameType::ResetStreamAt) as u64,
+                    reason,
+                )
+            })
+    }
+
+    /// 写入一段流数据
+    ///
+    /// # Arguments
+    /// `offset` - 数据在流中的偏移量
+    /// `data` - 数据
+    /// `fin` - 该段数据是否是流的结尾
+    ///
+    /// # Returns
+    /// 最终大小不一致时返回错误原因
+    pub(crate) fn insert(
+        &mut self,
+        offset: usize,
+        data: &[u8],
+        fin: bool,
+    ) -> Result<(), &'static str> {
+        let end = offset + data.len();
+
+        if fin {
+            self.set_final_size(end)?;
+        } else if self.final_size.is_some_and(|final_size| end > final_size) {
+            return Err("data received beyond final size");
+        }
+        self.max_offset = self.max_offset.max(end);
+
+        // 仅保存尚未读取且尚未缓存的部分
+        let mut start = offset.max(self.read_offset);
+        while start < end {
+            if let Some((&seg_offset, seg)) = self.segments.range(..=start).next_back() {
+                let seg_end = seg_offset + seg.len();
+                if seg_end > start {
+                    start = seg_end;
+                    continue;
+                }
+            }
+
+            let piece_end = self
+                .segments
+                .range(start..)
+                .next()
+                .map_or(end, |(&next, _)| next.min(end));
+            self.segments
+                .insert(start, data[start - offset..piece_end - offset].to_vec());
+            start = piece_end;
+        }
+
+        Ok(())
+    }
+
+    /// 按序读取数据
+    ///
+    /// # Arguments
+    /// `buf` - 读取缓冲区
+    ///
+    /// # Returns
+    /// 返回读取的字节数
+    pub(crate) fn read(&mut self, buf: &mut [u8]) -> usize {
+        let mut size = 0;
+
+        while size < buf.len() {
+            let Some(mut entry) = self.segments.first_entry() else {
+                break;
+            };
+            if *entry.key() != self.read_offset {
+                break;
+            }
+
+            let seg = entry.get_mut();
+            let n = seg.len().min(buf.len() - size);
+            buf[size..size + n].copy_from_slice(&seg[..n]);
+            size += n;
+            self.read_offset += n;
+
+            if n == seg.len() {
+                entry.remove();
+            } else {
+                let rest = seg.split_off(n);
+                entry.remove();
+                self.segments.insert(self.read_offset, rest);
+            }
+        }
+
+        size
+    }
+
+    /// 丢弃所有缓存的数据, 用于流被重置后
+    pub(crate) fn clear(&mut self) {
+        self.segments.clear();
+    }
+
+    /// 设置流的最终大小
+    ///
+    /// # Arguments
+    /// `final_size` - 流的最终大小
+    ///
+    /// # Returns
+    /// 最终大小不一致时返回错误原因
+    fn set_final_size(&mut self, final_size: usize) -> Result<(), &'static str> {
+        match self.final_size {
+            Some(known) if known != final_size => Err("final size changed"),
+            _ if final_size < self.max_offset => Err("final size below received data"),
+            _ => {
+                self.final_size = Some(final_size);
+                Ok(())
+            }
+        }
+    }
+
+    /// 获取从已读取偏移量开始连续可读的数据结尾
+    fn readable_end(&self) -> usize {
+        let mut end = self.read_offset;
+        for (&offset, seg) in self.segments.range(self.read_offset..) {
+            if offset != end {
+                break;
+            }
+            end += seg.len();
+        }
+
+        end
+    }
+}
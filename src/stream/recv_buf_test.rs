@@ -0,0 +1,75 @@
+use crate::{
+    attr::StreamDataSetter,
+    error::TransportErrorCode,
+    frame::{ResetStreamFrame, StreamFrame},
+};
+
+use super::RecvBuffer;
+
+fn stream_frame(offset: usize, data: &[u8], fin: bool) -> StreamFrame {
+    let mut frame = StreamFrame::new(true, true, fin);
+    frame.set_data(offset, data);
+    frame
+}
+
+#[test]
+fn test_recv_buffer_out_of_order() {
+    let mut buffer = RecvBuffer::new();
+    let mut buf = [0u8; 16];
+
+    buffer
+        .on_stream_frame(&stream_frame(6, b"world", true))
+        .unwrap();
+    assert_eq!(buffer.get_readable(), 0);
+    assert_eq!(buffer.read(&mut buf), 0);
+
+    // 与已缓存数据重叠
+    buffer
+        .on_stream_frame(&stream_frame(3, b"lo wor", false))
+        .unwrap();
+    buffer
+        .on_stream_frame(&stream_frame(0, b"hel", false))
+        .unwrap();
+    assert!(buffer.is_all_received());
+    assert_eq!(buffer.get_readable(), 11);
+
+    assert_eq!(buffer.read(&mut buf[..4]), 4);
+    assert_eq!(buffer.read(&mut buf[4..]), 7);
+    assert_eq!(&buf[..11], b"hello world");
+    assert!(buffer.is_finished());
+
+    // 重复数据被忽略
+    buffer
+        .on_stream_frame(&stream_frame(0, b"hello", false))
+        .unwrap();
+    assert_eq!(buffer.get_readable(), 0);
+}
+
+#[test]
+fn test_recv_buffer_final_size() {
+    let mut buffer = RecvBuffer::new();
+    buffer
+        .on_stream_frame(&stream_frame(0, b"hello", true))
+        .unwrap();
+
+    let err = buffer
+        .on_stream_frame(&stream_frame(5, b"!", false))
+        .unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::FinalSizeError);
+    assert!(buffer
+        .on_stream_frame(&stream_frame(0, b"hel", true))
+        .is_err());
+
+    let mut reset = ResetStreamFrame::new();
+    reset.set_final_size(4);
+    assert!(buffer.on_reset_stream_frame(&reset).is_err());
+    reset.set_final_size(5);
+    assert!(buffer.on_reset_stream_frame(&reset).is_ok());
+
+    let mut buffer = RecvBuffer::new();
+    buffer
+        .on_stream_frame(&stream_frame(0, b"hello", false))
+        .unwrap();
+    reset.set_final_size(3);
+    assert!(buffer.on_reset_stream_frame(&reset).is_err());
+}
@@ -0,0 +1,106 @@
+use crate::{
+    attr::{Side, StreamDataSetter, StreamDirection, StreamIDSetter, StreamId},
+    error::{StreamError, TransportErrorCode},
+    frame::{ResetStreamAtFrame, ResetStreamFrame, StreamFrame},
+};
+
+use super::{RecvState, RecvStream};
+
+fn stream_frame(id: StreamId, offset: usize, data: &[u8], fin: bool) -> StreamFrame {
+    let mut frame = StreamFrame::new(true, true, fin);
+    frame.set_stream_id(id);
+    frame.set_data(offset, data);
+    frame
+}
+
+#[test]
+fn test_recv_stream_states() {
+    let id = StreamId::nth(Side::Client, StreamDirection::Bidi, 0);
+    let mut stream = RecvStream::new(id, Side::Server);
+    let mut buf = [0u8; 16];
+
+    stream
+        .on_stream_frame(&stream_frame(id, 5, b"world", true))
+        .unwrap();
+    assert_eq!(stream.get_state(), RecvState::SizeKnown);
+
+    stream
+        .on_stream_frame(&stream_frame(id, 0, b"hello", false))
+        .unwrap();
+    assert_eq!(stream.get_state(), RecvState::DataRecvd);
+
+    assert_eq!(stream.read(&mut buf), Ok(10));
+    assert_eq!(stream.get_state(), RecvState::DataRead);
+    assert!(stream.is_terminal());
+
+    // 终止状态下忽略后续的帧
+    let mut reset = ResetStreamFrame::new();
+    reset.set_stream_id(id);
+    reset.set_final_size(10);
+    stream.on_reset_stream_frame(&reset).unwrap();
+    assert_eq!(stream.get_state(), RecvState::DataRead);
+}
+
+#[test]
+fn test_recv_stream_reset() {
+    let id = StreamId::nth(Side::Server, StreamDirection::Uni, 0);
+    let mut stream = RecvStream::new(id, Side::Client);
+    let mut buf = [0u8; 16];
+
+    stream
+        .on_stream_frame(&stream_frame(id, 0, b"hello", false))
+        .unwrap();
+    assert_eq!(stream.get_state(), RecvState::Recv);
+
+    let mut reset = ResetStreamFrame::new();
+    reset.set_stream_id(id);
+    reset.set_error_code(3);
+    reset.set_final_size(5);
+    stream.on_reset_stream_frame(&reset).unwrap();
+    assert_eq!(stream.get_state(), RecvState::ResetRecvd);
+
+    assert_eq!(stream.read(&mut buf), Err(StreamError::Reset(3)));
+    assert_eq!(stream.get_state(), RecvState::ResetRead);
+
+    // 本端发起的单向流上本端只能发送
+    let id = StreamId::nth(Side::Client, StreamDirection::Uni, 0);
+    let mut stream = RecvStream::new(id, Side::Client);
+    let err = stream
+        .on_stream_frame(&stream_frame(id, 0, b"hello", false))
+        .unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::StreamStateError);
+}
+
+#[test]
+fn test_recv_stream_reset_at() {
+    let id = StreamId::nth(Side::Server, StreamDirection::Uni, 0);
+    let mut stream = RecvStream::new(id, Side::Client);
+    let mut buf = [0u8; 16];
+
+    stream
+        .on_stream_frame(&stream_frame(id, 6, b"body", false))
+        .unwrap();
+
+    let mut reset = ResetStreamAtFrame::new();
+    reset.set_stream_id(id);
+    reset.set_error_code(4);
+    reset.set_final_size(10);
+    reset.set_reliable_size(11);
+    let err = stream.on_reset_stream_at_frame(&reset).unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::FrameEncodingError);
+
+    // 可靠大小之前的数据未全部到达
+    reset.set_reliable_size(6);
+    stream.on_reset_stream_at_frame(&reset).unwrap();
+    assert_eq!(stream.get_state(), RecvState::SizeKnown);
+
+    stream
+        .on_stream_frame(&stream_frame(id, 0, b"header", false))
+        .unwrap();
+    assert_eq!(stream.get_state(), RecvState::ResetRecvd);
+
+    assert_eq!(stream.read(&mut buf), Ok(6));
+    assert_eq!(&buf[..6], b"header");
+    assert_eq!(stream.read(&mut buf), Err(StreamError::Reset(4)));
+    assert_eq!(stream.get_state(), RecvState::ResetRead);
+}
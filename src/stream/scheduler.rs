@@ -0,0 +1,219 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::attr::StreamId;
+
+/// 流优先级 (RFC 9218)
+///
+/// 紧急程度取值 0 ~ 7, 数值越小越优先; 增量流之间轮流发送,
+/// 非增量流按流标识顺序逐个发送完毕.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Priority {
+    /// 紧急程度
+    urgency: u8,
+
+    /// 是否增量发送
+    incremental: bool,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_URGENCY, false)
+    }
+}
+
+impl Priority {
+    /// 默认紧急程度
+    pub const DEFAULT_URGENCY: u8 = 3;
+
+    /// 最低紧急程度
+    pub const MAX_URGENCY: u8 = 7;
+
+    /// 构造一个流优先级, 紧急程度超过 7 时取 7
+    ///
+    /// # Arguments
+    /// `urgency` - 紧急程度
+    /// `incremental` - 是否增量发送
+    ///
+    /// # Returns
+    /// 返回流优先级
+    pub const fn new(urgency: u8, incremental: bool) -> Self {
+        Self {
+            urgency: if urgency > Self::MAX_URGENCY {
+                Self::MAX_URGENCY
+            } else {
+                urgency
+            },
+            incremental,
+        }
+    }
+
+    /// 获取紧急程度
+    ///
+    /// # Returns
+    /// 返回紧急程度
+    #[inline(always)]
+    pub const fn get_urgency(&self) -> u8 {
+        self.urgency
+    }
+
+    /// 获取是否增量发送
+    ///
+    /// # Returns
+    /// 返回是否增量发送
+    #[inline(always)]
+    pub const fn is_incremental(&self) -> bool {
+        self.incremental
+    }
+}
+
+/// 调度顺序: 紧急程度, 非增量流优先于增量流, 以及同类流中的次序
+///
+/// 非增量流的次序为流标识, 增量流的次序为加入轮转的序号.
+type ScheduleKey = (u8, bool, u64, StreamId);
+
+/// 流调度器
+///
+/// 决定有待发送数据的流写入下一个数据包的顺序.
+/// 紧急程度高的流优先; 同一紧急程度下, 非增量流按流标识顺序发送,
+/// 增量流之间轮流发送.
+pub(crate) struct StreamScheduler {
+    /// 各个流的优先级, 未设置的流使用默认优先级
+    priorities: HashMap<StreamId, Priority>,
+
+    /// 有待发送数据的流
+    active: BTreeSet<ScheduleKey>,
+
+    /// 有待发送数据的流在 `active` 中的调度顺序
+    keys: HashMap<StreamId, ScheduleKey>,
+
+    /// 增量流的轮转序号
+    round: u64,
+}
+
+impl StreamScheduler {
+    /// 构造一个流调度器
+    ///
+    /// # Returns
+    /// 返回流调度器
+    pub(crate) fn new() -> Self {
+        Self {
+            priorities: HashMap::new(),
+            active: BTreeSet::new(),
+            keys: HashMap::new(),
+            round: 0,
+        }
+    }
+
+    /// 获取流的优先级
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    ///
+    /// # Returns
+    /// 返回流的优先级
+    pub(crate) fn get_priority(&self, stream_id: StreamId) -> Priority {
+        self.priorities.get(&stream_id).copied().unwrap_or_default()
+    }
+
+    /// 设置流的优先级, 可在流有待发送数据时调整
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `priority` - 优先级
+    pub(crate) fn set_priority(&mut self, stream_id: StreamId, priority: Priority) {
+        if self.priorities.insert(stream_id, priority) == Some(priority) {
+            return;
+        }
+
+        if self.unschedule(stream_id) {
+            self.schedule(stream_id);
+        }
+    }
+
+    /// 标记流有待发送的数据
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    pub(crate) fn push(&mut self, stream_id: StreamId) {
+        if !self.keys.contains_key(&stream_id) {
+            self.schedule(stream_id);
+        }
+    }
+
+    /// 判断流是否有待发送的数据
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    ///
+    /// # Returns
+    /// 返回流是否有待发送的数据
+    pub(crate) fn contains(&self, stream_id: StreamId) -> bool {
+        self.keys.contains_key(&stream_id)
+    }
+
+    /// 获取下一个应当发送的流
+    ///
+    /// # Returns
+    /// 返回流标识
+    pub(crate) fn peek(&self) -> Option<StreamId> {
+        self.active.first().map(|key| key.3)
+    }
+
+    /// 按调度顺序遍历有待发送数据的流
+    ///
+    /// # Returns
+    /// 返回按调度顺序排列的流标识
+    pub(crate) fn iter(&self) -> impl Iterator<Item = StreamId> + '_ {
+        self.active.iter().map(|key| key.3)
+    }
+
+    /// 流的数据被写入数据包之后调用
+    ///
+    /// 数据已全部发送的流被移出调度; 增量流移到同类流的末尾, 非增量流保持在队首.
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `exhausted` - 流是否已没有待发送的数据
+    pub(crate) fn on_sent(&mut self, stream_id: StreamId, exhausted: bool) {
+        if exhausted {
+            self.unschedule(stream_id);
+        } else if self.get_priority(stream_id).is_incremental() && self.unschedule(stream_id) {
+            self.schedule(stream_id);
+        }
+    }
+
+    /// 移除流, 用于流关闭后
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    pub(crate) fn remove(&mut self, stream_id: StreamId) {
+        self.unschedule(stream_id);
+        self.priorities.remove(&stream_id);
+    }
+
+    fn schedule(&mut self, stream_id: StreamId) {
+        let priority = self.get_priority(stream_id);
+        let order = if priority.is_incremental() {
+            self.round += 1;
+            self.round
+        } else {
+            stream_id.get_value()
+        };
+
+        let key = (
+            priority.get_urgency(),
+            priority.is_incremental(),
+            order,
+            stream_id,
+        );
+        self.active.insert(key);
+        self.keys.insert(stream_id, key);
+    }
+
+    fn unschedule(&mut self, stream_id: StreamId) -> bool {
+        match self.keys.remove(&stream_id) {
+            Some(key) => self.active.remove(&key),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::attr::StreamId;
+
+use super::{Priority, StreamScheduler};
+
+#[test]
+fn test_urgency_and_fifo() {
+    let mut scheduler = StreamScheduler::new();
+    scheduler.set_priority(StreamId::new(8), Priority::new(1, false));
+    for id in [12, 8, 4, 0] {
+        scheduler.push(StreamId::new(id));
+    }
+
+    // 紧急程度优先, 同一紧急程度的非增量流按流标识顺序
+    assert_eq!(
+        scheduler
+            .iter()
+            .map(|id| id.get_value())
+            .collect::<Vec<_>>(),
+        vec![8, 0, 4, 12]
+    );
+
+    // 非增量流未发送完毕时保持在队首
+    scheduler.on_sent(StreamId::new(8), false);
+    assert_eq!(scheduler.peek(), Some(StreamId::new(8)));
+    scheduler.on_sent(StreamId::new(8), true);
+    assert_eq!(scheduler.peek(), Some(StreamId::new(0)));
+
+    // 运行时调整优先级
+    scheduler.set_priority(StreamId::new(12), Priority::new(0, false));
+    assert_eq!(scheduler.peek(), Some(StreamId::new(12)));
+    assert_eq!(Priority::new(9, true).get_urgency(), 7);
+}
+
+#[test]
+fn test_incremental_round_robin() {
+    let mut scheduler = StreamScheduler::new();
+    for id in [0, 4, 8] {
+        scheduler.set_priority(StreamId::new(id), Priority::new(3, true));
+        scheduler.push(StreamId::new(id));
+    }
+    scheduler.push(StreamId::new(12));
+
+    // 同一紧急程度下非增量流优先
+    assert_eq!(scheduler.peek(), Some(StreamId::new(12)));
+    scheduler.on_sent(StreamId::new(12), true);
+
+    let mut order = Vec::new();
+    for _ in 0..6 {
+        let id = scheduler.peek().unwrap();
+        order.push(id.get_value());
+        scheduler.on_sent(id, false);
+    }
+    assert_eq!(order, vec![0, 4, 8, 0, 4, 8]);
+
+    scheduler.remove(StreamId::new(4));
+    assert!(!scheduler.contains(StreamId::new(4)));
+    assert_eq!(
+        scheduler.get_priority(StreamId::new(4)),
+        Priority::default()
+    );
+    assert_eq!(scheduler.iter().count(), 2);
+}
@@ -0,0 +1,418 @@
+use crate::{
+    attr::{Side, StreamIDSetter, StreamId},
+    error::{StreamError, TransportError, TransportErrorCode},
+    frame::{ResetStreamAtFrame, ResetStreamFrame, StopSendingFrame, StreamFrame},
+};
+
+use super::send_buf::SendBuffer;
+
+/// 发送流状态 (RFC 9000 §3.1)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SendState {
+    /// 流已创建, 尚未发送任何数据
+    Ready,
+
+    /// 正在发送数据
+    Send,
+
+    /// 已发送携带 FIN 的 STREAM 帧, 等待全部数据被确认
+    DataSent,
+
+    /// 已发送 RESET_STREAM 帧
+    ResetSent,
+
+    /// 全部数据已被确认, 终止状态
+    DataRecvd,
+
+    /// RESET_STREAM 帧已被确认, 终止状态
+    ResetRecvd,
+}
+
+/// 发送流
+///
+/// 由发送缓冲区与发送流状态机组成. 应用层写入、STREAM 帧的发送与确认、
+/// 以及对端的 STOP_SENDING 驱动状态转换.
+pub(crate) struct SendStream {
+    /// 流标识
+    stream_id: StreamId,
+
+    /// 本端角色
+    local: Side,
+
+    /// 发送流状态
+    state: SendState,
+
+    /// 发送缓冲区
+    buffer: SendBuffer,
+
+    /// 本端重置流时的应用错误码
+    reset_error_code: Option<u64>,
+
+    /// 是否有待发送的 RESET_STREAM 或 RESET_STREAM_AT 帧
+    reset_pending: bool,
+
+    /// RESET_STREAM_AT 的可靠大小, 以 RESET_STREAM 重置时为 None
+    reliable_size: Option<usize>,
+
+    /// RESET_STREAM 或 RESET_STREAM_AT 帧是否已被确认
+    reset_acked: bool,
+
+    /// 对端 STOP_SENDING 帧中的应用错误码
+    stop_error_code: Option<u64>,
+}
+
+impl SendStream {
+    /// 构造一个发送流
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `local` - 本端角色
+    ///
+    /// # Returns
+    /// 返回发送流
+    pub(crate) fn new(stream_id: StreamId, local: Side) -> Self {
+        Self {
+            stream_id,
+            local,
+            state: SendState::Ready,
+            buffer: SendBuffer::new(),
+            reset_error_code: None,
+            reset_pending: false,
+            reliable_size: None,
+            reset_acked: false,
+            stop_error_code: None,
+        }
+    }
+
+    /// 获取流标识
+    ///
+    /// # Returns
+    /// 返回流标识
+    #[inline(always)]
+    pub(crate) const fn get_stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// 获取发送流状态
+    ///
+    /// # Returns
+    /// 返回发送流状态
+    #[inline(always)]
+    pub(crate) const fn get_state(&self) -> SendState {
+        self.state
+    }
+
+    /// 获取发送缓冲区
+    ///
+    /// # Returns
+    /// 返回发送缓冲区
+    #[inline(always)]
+    pub(crate) const fn get_buffer(&self) -> &SendBuffer {
+        &self.buffer
+    }
+
+    /// 获取对端 STOP_SENDING 帧中的应用错误码
+    ///
+    /// # Returns
+    /// 返回应用错误码, 未收到 STOP_SENDING 时返回 None
+    #[inline(always)]
+    pub(crate) const fn get_stop_error_code(&self) -> Option<u64> {
+        self.stop_error_code
+    }
+
+    /// 判断是否有待发送的 RESET_STREAM 或 RESET_STREAM_AT 帧
+    ///
+    /// # Returns
+    /// 返回是否有待发送的重置帧
+    #[inline(always)]
+    pub(crate) const fn has_pending_reset(&self) -> bool {
+        self.reset_pending
+    }
+
+    /// 判断发送流是否处于终止状态
+    ///
+    /// # Returns
+    /// 返回是否处于终止状态
+    pub(crate) fn is_terminal(&self) -> bool {
+        matches!(self.state, SendState::DataRecvd | SendState::ResetRecvd)
+    }
+
+    /// 写入应用数据
+    ///
+    /// # Arguments
+    /// `data` - 应用数据
+    ///
+    /// # Returns
+    /// 返回写入的字节数; 对端要求停止发送、流已结束或已被重置时返回错误
+    pub(crate) fn write(&mut self, data: &[u8]) -> Result<usize, StreamError> {
+        self.check_writable()?;
+        Ok(self.buffer.write(data))
+    }
+
+    /// 结束写入
+    ///
+    /// # Returns
+    /// 对端要求停止发送、流已结束或已被重置时返回错误
+    pub(crate) fn finish(&mut self) -> Result<(), StreamError> {
+        self.check_writable()?;
+        self.buffer.finish();
+        Ok(())
+    }
+
+    /// 判断是否有待发送的 STREAM 帧
+    ///
+    /// # Arguments
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 返回是否有待发送的 STREAM 帧
+    pub(crate) fn has_pending(&self, max_offset: usize) -> bool {
+        self.get_send_limit(max_offset)
+            .is_some_and(|limit| self.buffer.has_pending(limit))
+    }
+
+    /// 按数据包剩余空间生成一个 STREAM 帧
+    ///
+    /// 发送首个帧后进入 Send 状态, 发送 FIN 后进入 Data Sent 状态.
+    /// 以 RESET_STREAM_AT 重置后仍发送可靠大小之前的数据.
+    ///
+    /// # Arguments
+    /// `budget` - 数据包中可用于该帧的字节数
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 返回 STREAM 帧
+    pub(crate) fn poll_stream_frame(
+        &mut self,
+        budget: usize,
+        max_offset: usize,
+    ) -> Option<StreamFrame> {
+        let limit = self.get_send_limit(max_offset)?;
+
+        let frame = self.buffer.poll_frame(self.stream_id, budget, limit)?;
+        if self.state == SendState::Ready {
+            self.state = SendState::Send;
+        }
+        if frame.get_fin_flag() && self.state == SendState::Send {
+            self.state = SendState::DataSent;
+        }
+
+        Some(frame)
+    }
+
+    /// STREAM 帧被确认, 全部数据被确认后进入 Data Recvd 状态
+    ///
+    /// # Arguments
+    /// `offset` - 数据的偏移量
+    /// `len` - 数据的长度
+    /// `fin` - 是否携带 FIN
+    pub(crate) fn on_stream_frame_acked(&mut self, offset: usize, len: usize, fin: bool) {
+        match self.state {
+            SendState::Send | SendState::DataSent => {
+                self.buffer.on_ack(offset, len, fin);
+                if self.state == SendState::DataSent && self.buffer.is_all_acked() {
+                    self.state = SendState::DataRecvd;
+                }
+            }
+            SendState::ResetSent if self.reliable_size.is_some() => {
+                self.buffer.on_ack(offset, len, fin);
+                self.update_reset_state();
+            }
+            _ => {}
+        }
+    }
+
+    /// STREAM 帧丢失, 重置后仅重传可靠大小之前的数据
+    ///
+    /// # Arguments
+    /// `offset` - 数据的偏移量
+    /// `len` - 数据的长度
+    /// `fin` - 是否携带 FIN
+    pub(crate) fn on_stream_frame_lost(&mut self, offset: usize, len: usize, fin: bool) {
+        match self.state {
+            SendState::Send | SendState::DataSent => self.buffer.on_lost(offset, len, fin),
+            SendState::ResetSent => {
+                if let Some(reliable_size) = self.reliable_size {
+                    self.buffer.on_lost(offset, len, fin);
+                    self.buffer.discard_lost_from(reliable_size);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 处理对端的 STOP_SENDING 帧, 以相同的应用错误码重置流作为应答
+    ///
+    /// # Arguments
+    /// `frame` - STOP_SENDING 帧
+    ///
+    /// # Returns
+    /// 本端仅接收的流上收到 STOP_SENDING 时返回 STREAM_STATE_ERROR
+    pub(crate) fn on_stop_sending_frame(
+        &mut self,
+        frame: &StopSendingFrame,
+    ) -> Result<(), TransportError> {
+        frame.check_direction(self.local)?;
+
+        if self.stop_error_code.is_none() {
+            self.stop_error_code = Some(frame.get_error_code());
+            self.reset(frame.get_error_code());
+        }
+        Ok(())
+    }
+
+    /// 重置流, 进入 Reset Sent 状态
+    ///
+    /// 已经以 RESET_STREAM_AT 重置的流可以再次以 RESET_STREAM 重置, 放弃可靠交付.
+    ///
+    /// # Arguments
+    /// `error_code` - 应用错误码
+    ///
+    /// # Returns
+    /// 全部数据已被确认或流已被重置时返回 false
+    pub(crate) fn reset(&mut self, error_code: u64) -> bool {
+        match self.state {
+            SendState::Ready | SendState::Send | SendState::DataSent => {
+                self.reset_error_code = Some(error_code)
+            }
+            SendState::ResetSent if self.reliable_size.is_some() => {}
+            _ => return false,
+        }
+
+        self.state = SendState::ResetSent;
+        self.reliable_size = None;
+        self.reset_acked = false;
+        self.reset_pending = true;
+        self.buffer.clear();
+        true
+    }
+
+    /// 以 RESET_STREAM_AT 重置流, 进入 Reset Sent 状态, 但继续发送可靠大小之前的数据
+    ///
+    /// 可靠大小不超过已写入的数据量与流量控制允许发送的最大偏移量,
+    /// 以免最终大小超过对端的限制; 再次重置时可靠大小只能减小.
+    ///
+    /// # Arguments
+    /// `error_code` - 应用错误码
+    /// `reliable_size` - 可靠大小
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 全部数据已被确认或流已被重置时返回 false
+    pub(crate) fn reset_at(
+        &mut self,
+        error_code: u64,
+        reliable_size: usize,
+        max_offset: usize,
+    ) -> bool {
+        let reliable_size = reliable_size
+            .min(self.buffer.get_write_offset())
+            .min(max_offset);
+        match self.state {
+            SendState::Ready | SendState::Send | SendState::DataSent => {
+                self.reset_error_code = Some(error_code)
+            }
+            SendState::ResetSent if self.reliable_size.is_some_and(|r| reliable_size < r) => {}
+            _ => return false,
+        }
+
+        self.state = SendState::ResetSent;
+        self.reliable_size = Some(reliable_size);
+        self.reset_acked = false;
+        self.reset_pending = true;
+        self.buffer.discard_lost_from(reliable_size);
+        true
+    }
+
+    /// 生成 RESET_STREAM 帧, 最终大小为已发送数据的最大偏移量
+    ///
+    /// # Returns
+    /// 有待发送的 RESET_STREAM 帧时返回该帧
+    pub(crate) fn poll_reset_stream_frame(&mut self) -> Option<ResetStreamFrame> {
+        if !self.reset_pending || self.reliable_size.is_some() {
+            return None;
+        }
+        self.reset_pending = false;
+
+        let mut frame = ResetStreamFrame::new();
+        frame.set_stream_id(self.stream_id);
+        frame.set_error_code(self.reset_error_code.unwrap_or_default());
+        frame.set_final_size(self.get_final_size());
+        Some(frame)
+    }
+
+    /// 生成 RESET_STREAM_AT 帧
+    ///
+    /// # Returns
+    /// 有待发送的 RESET_STREAM_AT 帧时返回该帧
+    pub(crate) fn poll_reset_stream_at_frame(&mut self) -> Option<ResetStreamAtFrame> {
+        let reliable_size = self.reliable_size?;
+        if !self.reset_pending {
+            return None;
+        }
+        self.reset_pending = false;
+
+        let mut frame = ResetStreamAtFrame::new();
+        frame.set_stream_id(self.stream_id);
+        frame.set_error_code(self.reset_error_code.unwrap_or_default());
+        frame.set_final_size(self.get_final_size());
+        frame.set_reliable_size(reliable_size);
+        Some(frame)
+    }
+
+    /// RESET_STREAM 或 RESET_STREAM_AT 帧丢失, 在 Reset Sent 状态下重传
+    pub(crate) fn on_reset_lost(&mut self) {
+        if self.state == SendState::ResetSent {
+            self.reset_pending = true;
+        }
+    }
+
+    /// 获取流的最终大小, 即已发送数据与可靠大小中较大的偏移量
+    ///
+    /// # Returns
+    /// 返回流的最终大小
+    pub(crate) fn get_final_size(&self) -> usize {
+        self.buffer
+            .get_sent_offset()
+            .max(self.reliable_size.unwrap_or_default())
+    }
+
+    /// RESET_STREAM 或 RESET_STREAM_AT 帧被确认,
+    /// 可靠大小之前的数据也全部被确认后进入 Reset Recvd 状态
+    pub(crate) fn on_reset_acked(&mut self) {
+        if self.state == SendState::ResetSent {
+            self.reset_acked = true;
+            self.update_reset_state();
+        }
+    }
+
+    fn update_reset_state(&mut self) {
+        if self.reset_acked
+            && self
+                .reliable_size
+                .is_none_or(|r| self.buffer.get_acked_offset() >= r)
+        {
+            self.state = SendState::ResetRecvd;
+        }
+    }
+
+    /// 获取允许发送的最大偏移量, 不允许发送 STREAM 帧时返回 None
+    fn get_send_limit(&self, max_offset: usize) -> Option<usize> {
+        match self.state {
+            SendState::Ready | SendState::Send | SendState::DataSent => Some(max_offset),
+            SendState::ResetSent => self.reliable_size.map(|r| r.min(max_offset)),
+            _ => None,
+        }
+    }
+
+    fn check_writable(&self) -> Result<(), StreamError> {
+        if let Some(code) = self.stop_error_code {
+            return Err(StreamError::Stopped(code));
+        }
+        if !matches!(self.state, SendState::Ready | SendState::Send) || self.buffer.is_fin() {
+            return Err(StreamError::Closed);
+        }
+
+        Ok(())
+    }
+}
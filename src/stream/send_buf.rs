@@ -0,0 +1,358 @@
+use std::collections::VecDeque;
+
+use crate::{
+    attr::{StreamDataSetter, StreamIDSetter, StreamId},
+    frame::{CryptoFrame, StreamFrame},
+    util::{self, RangeSet},
+};
+
+/// 流发送缓冲区
+///
+/// 保存应用层写入但尚未被确认的数据, 按数据包预算切分为 STREAM 帧,
+/// 并跟踪每段数据处于在途、已确认或丢失的状态. 丢失的数据优先于新数据重传.
+pub(crate) struct SendBuffer {
+    /// 尚未被确认的数据, 首字节的偏移量为 `base`
+    buffer: VecDeque<u8>,
+
+    /// `buffer` 首字节在流中的偏移量, 之前的数据均已被确认
+    base: usize,
+
+    /// 已发送过的新数据的结尾偏移量
+    sent: usize,
+
+    /// 已被确认的数据区间 (仅包含 `base` 之后的部分)
+    acked: RangeSet,
+
+    /// 丢失待重传的数据区间
+    lost: RangeSet,
+
+    /// 应用层是否已结束写入
+    fin: bool,
+
+    /// FIN 是否在途或已被确认
+    fin_sent: bool,
+
+    /// FIN 是否已被确认
+    fin_acked: bool,
+}
+
+impl SendBuffer {
+    /// 构造一个流发送缓冲区
+    ///
+    /// # Returns
+    /// 返回流发送缓冲区
+    pub(crate) fn new() -> Self {
+        Self {
+            buffer: VecDeque::new(),
+            base: 0,
+            sent: 0,
+            acked: RangeSet::new(),
+            lost: RangeSet::new(),
+            fin: false,
+            fin_sent: false,
+            fin_acked: false,
+        }
+    }
+
+    /// 获取应用层已写入数据的结尾偏移量
+    ///
+    /// # Returns
+    /// 返回已写入数据的结尾偏移量
+    #[inline(always)]
+    pub(crate) fn get_write_offset(&self) -> usize {
+        self.base + self.buffer.len()
+    }
+
+    /// 获取已发送过的新数据的结尾偏移量
+    ///
+    /// # Returns
+    /// 返回已发送数据的结尾偏移量
+    #[inline(always)]
+    pub(crate) const fn get_sent_offset(&self) -> usize {
+        self.sent
+    }
+
+    /// 获取连续被确认的数据的结尾偏移量
+    ///
+    /// # Returns
+    /// 返回连续被确认的数据的结尾偏移量
+    #[inline(always)]
+    pub(crate) const fn get_acked_offset(&self) -> usize {
+        self.base
+    }
+
+    /// 获取尚未被确认的字节数
+    ///
+    /// # Returns
+    /// 返回尚未被确认的字节数
+    #[inline(always)]
+    pub(crate) fn get_unacked(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// 判断应用层是否已结束写入
+    ///
+    /// # Returns
+    /// 返回是否已结束写入
+    #[inline(always)]
+    pub(crate) const fn is_fin(&self) -> bool {
+        self.fin
+    }
+
+    /// 判断全部数据与 FIN 是否都已被确认
+    ///
+    /// # Returns
+    /// 返回是否全部被确认
+    pub(crate) fn is_all_acked(&self) -> bool {
+        self.fin_acked && self.buffer.is_empty()
+    }
+
+    /// 判断全部数据与 FIN 是否都已发送过且没有待重传的数据
+    ///
+    /// # Returns
+    /// 返回是否全部发送
+    pub(crate) fn is_all_sent(&self) -> bool {
+        self.fin_sent && self.lost.is_empty() && self.sent == self.get_write_offset()
+    }
+
+    /// 写入应用数据
+    ///
+    /// # Arguments
+    /// `data` - 应用数据
+    ///
+    /// # Returns
+    /// 返回写入的字节数, 结束写入后返回 0
+    pub(crate) fn write(&mut self, data: &[u8]) -> usize {
+        if self.fin {
+            return 0;
+        }
+
+        self.buffer.extend(data);
+        data.len()
+    }
+
+    /// 结束写入, 之后发送的最后一个 STREAM 帧将携带 FIN
+    pub(crate) fn finish(&mut self) {
+        self.fin = true;
+    }
+
+    /// 判断是否有待发送的数据 (包含丢失待重传的数据与 FIN)
+    ///
+    /// # Arguments
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 返回是否有待发送的数据
+    pub(crate) fn has_pending(&self, max_offset: usize) -> bool {
+        !self.lost.is_empty()
+            || self.sent < self.get_write_offset().min(max_offset)
+            || (self.fin && !self.fin_sent && self.sent == self.get_write_offset())
+    }
+
+    /// 判断新数据的发送是否受流量控制限制
+    ///
+    /// # Arguments
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 返回是否受流量控制限制
+    pub(crate) fn is_blocked(&self, max_offset: usize) -> bool {
+        self.sent >= max_offset && self.sent < self.get_write_offset()
+    }
+
+    /// 按数据包剩余空间生成一个 STREAM 帧
+    ///
+    /// 丢失的数据优先于新数据发送. 偏移量为 0 时省略 OFF 字段;
+    /// 数据填满剩余空间时省略 LEN 字段, 此时该帧必须是数据包中的最后一个帧.
+    ///
+    /// # Arguments
+    /// `stream_id` - 流标识
+    /// `budget` - 数据包中可用于该帧的字节数
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 返回 STREAM 帧, 没有可发送的数据或空间不足时返回 None
+    pub(crate) fn poll_frame(
+        &mut self,
+        stream_id: StreamId,
+        budget: usize,
+        max_offset: usize,
+    ) -> Option<StreamFrame> {
+        let write_offset = self.get_write_offset();
+        let (offset, available, retransmit) = self.next_range(max_offset);
+        let fin_pending = self.fin && !self.fin_sent;
+        if available == 0 && !(fin_pending && offset == write_offset) {
+            return None;
+        }
+
+        let off_flag = offset != 0;
+        let header = 1
+            + util::varint_len(stream_id.get_value())
+            + if off_flag {
+                util::varint_len(offset as u64)
+            } else {
+                0
+            };
+        if budget <= header {
+            return None;
+        }
+        let space = budget - header;
+
+        let (len, len_flag) = if available >= space {
+            (space, false)
+        } else {
+            let len_size = util::varint_len(available as u64);
+            (available.min(space.saturating_sub(len_size)), true)
+        };
+        if len == 0 && available != 0 {
+            return None;
+        }
+
+        let fin_flag = fin_pending && offset + len == write_offset;
+        if fin_flag {
+            self.fin_sent = true;
+        }
+        let data = self.take(offset, len, retransmit);
+
+        let mut frame = StreamFrame::new(off_flag, len_flag, fin_flag);
+        frame.set_stream_id(stream_id);
+        frame.set_data(offset, &data);
+
+        Some(frame)
+    }
+
+    /// 按数据包剩余空间生成一个 CRYPTO 帧, 用于握手数据的发送缓冲区
+    ///
+    /// CRYPTO 帧总是携带偏移量与长度字段, 且不受流量控制限制.
+    ///
+    /// # Arguments
+    /// `budget` - 数据包中可用于该帧的字节数
+    ///
+    /// # Returns
+    /// 返回 CRYPTO 帧, 没有可发送的数据或空间不足时返回 None
+    pub(crate) fn poll_crypto_frame(&mut self, budget: usize) -> Option<CryptoFrame> {
+        let (offset, available, retransmit) = self.next_range(usize::MAX);
+        if available == 0 {
+            return None;
+        }
+
+        let header = 1 + util::varint_len(offset as u64);
+        let space = budget.saturating_sub(header);
+        let len_size = util::varint_len(available.min(space) as u64);
+        let len = available.min(space.saturating_sub(len_size));
+        if len == 0 {
+            return None;
+        }
+
+        let data = self.take(offset, len, retransmit);
+
+        let mut frame = CryptoFrame::new();
+        frame.set_data(offset, &data);
+
+        Some(frame)
+    }
+
+    /// 获取下一段待发送的数据, 丢失的数据优先于新数据
+    ///
+    /// # Arguments
+    /// `max_offset` - 流量控制允许发送的最大偏移量
+    ///
+    /// # Returns
+    /// 返回数据的偏移量、可发送的长度以及是否为重传
+    fn next_range(&self, max_offset: usize) -> (usize, usize, bool) {
+        match self.lost.first() {
+            Some(range) => (range.start, range.len(), true),
+            None => {
+                let end = self.get_write_offset().min(max_offset);
+                (self.sent, end.saturating_sub(self.sent), false)
+            }
+        }
+    }
+
+    /// 取出一段待发送的数据, 并更新发送进度或重传队列
+    ///
+    /// # Arguments
+    /// `offset` - 数据的偏移量
+    /// `len` - 数据的长度
+    /// `retransmit` - 是否为重传
+    ///
+    /// # Returns
+    /// 返回数据
+    fn take(&mut self, offset: usize, len: usize, retransmit: bool) -> Vec<u8> {
+        let end = offset + len;
+        if retransmit {
+            self.lost.remove(offset..end);
+        } else {
+            self.sent = end;
+        }
+
+        let start = offset - self.base;
+        self.buffer.range(start..start + len).copied().collect()
+    }
+
+    /// 处理 STREAM 帧被确认
+    ///
+    /// # Arguments
+    /// `offset` - 数据的偏移量
+    /// `len` - 数据的长度
+    /// `fin` - 是否携带 FIN
+    pub(crate) fn on_ack(&mut self, offset: usize, len: usize, fin: bool) {
+        if fin {
+            self.fin_acked = true;
+        }
+
+        let start = offset.max(self.base);
+        let end = offset + len;
+        if start >= end {
+            return;
+        }
+        self.acked.insert(start..end);
+        self.lost.remove(start..end);
+
+        // 释放从 `base` 开始连续确认的数据
+        if let Some(range) = self.acked.first() {
+            if range.start == self.base {
+                self.buffer.drain(..range.len());
+                self.base = range.end;
+                self.acked.remove(range);
+            }
+        }
+    }
+
+    /// 处理 STREAM 帧丢失, 将尚未被确认的部分加入重传队列
+    ///
+    /// # Arguments
+    /// `offset` - 数据的偏移量
+    /// `len` - 数据的长度
+    /// `fin` - 是否携带 FIN
+    pub(crate) fn on_lost(&mut self, offset: usize, len: usize, fin: bool) {
+        if fin && !self.fin_acked {
+            self.fin_sent = false;
+        }
+
+        let start = offset.max(self.base);
+        let end = offset + len;
+        if start >= end {
+            return;
+        }
+        for range in self.acked.subtract(start..end) {
+            self.lost.insert(range);
+        }
+    }
+
+    /// 放弃重传指定偏移量之后的丢失数据, 用于以 RESET_STREAM_AT 重置流后
+    ///
+    /// # Arguments
+    /// `offset` - 偏移量
+    pub(crate) fn discard_lost_from(&mut self, offset: usize) {
+        self.lost.remove(offset..usize::MAX);
+    }
+
+    /// 释放全部数据, 用于以 RESET_STREAM 重置流后; 已发送数据的结尾偏移量保持不变
+    pub(crate) fn clear(&mut self) {
+        self.buffer.clear();
+        self.base = self.sent;
+        self.acked = RangeSet::new();
+        self.lost = RangeSet::new();
+    }
+}
@@ -0,0 +1,78 @@
+use crate::attr::{Side, StreamDataGetter, StreamDirection, StreamId};
+
+use super::SendBuffer;
+
+#[test]
+fn test_send_buffer_flags() {
+    let id = StreamId::nth(Side::Client, StreamDirection::Bidi, 0);
+    let mut buffer = SendBuffer::new();
+    buffer.write(&[1; 100]);
+    buffer.finish();
+
+    // 首帧偏移量为 0 省略 OFF, 数据填满空间省略 LEN
+    let frame = buffer.poll_frame(id, 42, usize::MAX).unwrap();
+    assert!(!frame.get_off_flag() && !frame.get_len_flag() && !frame.get_fin_flag());
+    assert_eq!(frame.get_data().1.len(), 40);
+
+    // 剩余数据不足以填满空间时携带 LEN 与 FIN
+    let frame = buffer.poll_frame(id, 1200, usize::MAX).unwrap();
+    assert!(frame.get_off_flag() && frame.get_len_flag() && frame.get_fin_flag());
+    assert_eq!(frame.get_data(), (40, &[1; 60][..]));
+
+    assert!(buffer.is_all_sent());
+    assert!(buffer.poll_frame(id, 1200, usize::MAX).is_none());
+}
+
+#[test]
+fn test_send_buffer_retransmit() {
+    let id = StreamId::nth(Side::Server, StreamDirection::Uni, 0);
+    let mut buffer = SendBuffer::new();
+    buffer.write(&(0..30).collect::<Vec<u8>>());
+
+    // 流量控制限制
+    let frame = buffer.poll_frame(id, 1200, 20).unwrap();
+    assert_eq!(frame.get_data().1.len(), 20);
+    assert!(buffer.is_blocked(20));
+    assert!(buffer.poll_frame(id, 1200, 20).is_none());
+
+    buffer.on_ack(0, 5, false);
+    buffer.on_ack(10, 5, false);
+    buffer.on_lost(0, 20, false);
+    assert_eq!(buffer.get_unacked(), 25);
+
+    // 丢失数据优先于新数据重传, 已确认的部分不重传
+    let frame = buffer.poll_frame(id, 1200, 30).unwrap();
+    assert_eq!(frame.get_data(), (5, &[5, 6, 7, 8, 9][..]));
+    let frame = buffer.poll_frame(id, 1200, 30).unwrap();
+    assert_eq!(frame.get_data().0, 15);
+    assert_eq!(frame.get_data().1.len(), 5);
+    let frame = buffer.poll_frame(id, 1200, 30).unwrap();
+    assert_eq!(frame.get_data().0, 20);
+    assert_eq!(frame.get_data().1.len(), 10);
+
+    // FIN 丢失后单独重传
+    buffer.finish();
+    let frame = buffer.poll_frame(id, 1200, 30).unwrap();
+    assert!(frame.get_fin_flag() && frame.get_data().1.is_empty());
+    buffer.on_lost(30, 0, true);
+    assert!(buffer.poll_frame(id, 1200, 30).unwrap().get_fin_flag());
+
+    buffer.on_ack(5, 25, true);
+    assert!(buffer.is_all_acked());
+}
+
+#[test]
+fn test_send_buffer_clear() {
+    let id = StreamId::nth(Side::Client, StreamDirection::Uni, 0);
+    let mut buffer = SendBuffer::new();
+    buffer.write(&[1; 100]);
+    buffer.poll_frame(id, 42, usize::MAX).unwrap();
+    buffer.on_lost(0, 40, false);
+
+    // 释放全部数据, 不再有待发送或待重传的数据
+    buffer.clear();
+    assert_eq!(buffer.get_unacked(), 0);
+    assert_eq!(buffer.get_sent_offset(), 40);
+    assert_eq!(buffer.get_write_offset(), 40);
+    assert!(!buffer.has_pending(usize::MAX));
+}
@@ -0,0 +1,91 @@
+use crate::{
+    attr::{Side, StreamDirection, StreamIDSetter, StreamId},
+    error::{StreamError, TransportErrorCode},
+    frame::StopSendingFrame,
+};
+
+use super::{SendState, SendStream};
+
+#[test]
+fn test_send_stream_states() {
+    let id = StreamId::nth(Side::Client, StreamDirection::Bidi, 0);
+    let mut stream = SendStream::new(id, Side::Client);
+    assert_eq!(stream.get_state(), SendState::Ready);
+
+    stream.write(b"hello").unwrap();
+    let frame = stream.poll_stream_frame(1200, usize::MAX).unwrap();
+    assert!(!frame.get_fin_flag());
+    assert_eq!(stream.get_state(), SendState::Send);
+
+    stream.finish().unwrap();
+    assert_eq!(stream.write(b"!"), Err(StreamError::Closed));
+    let frame = stream.poll_stream_frame(1200, usize::MAX).unwrap();
+    assert!(frame.get_fin_flag());
+    assert_eq!(stream.get_state(), SendState::DataSent);
+
+    stream.on_stream_frame_acked(5, 0, true);
+    assert_eq!(stream.get_state(), SendState::DataSent);
+    stream.on_stream_frame_acked(0, 5, false);
+    assert_eq!(stream.get_state(), SendState::DataRecvd);
+    assert!(stream.is_terminal());
+    assert!(!stream.reset(0));
+}
+
+#[test]
+fn test_send_stream_reset() {
+    let id = StreamId::nth(Side::Server, StreamDirection::Uni, 0);
+    let mut stream = SendStream::new(id, Side::Server);
+    stream.write(b"hello").unwrap();
+    stream.poll_stream_frame(1200, usize::MAX).unwrap();
+
+    stream.write(b" world").unwrap();
+
+    // 重置后释放缓冲的数据, 最终大小仍为已发送数据的偏移量
+    assert!(stream.reset(7));
+    assert_eq!(stream.get_state(), SendState::ResetSent);
+    assert_eq!(stream.get_buffer().get_unacked(), 0);
+    assert_eq!(stream.get_final_size(), 5);
+    assert_eq!(stream.write(b"!"), Err(StreamError::Closed));
+    assert_eq!(stream.finish(), Err(StreamError::Closed));
+    assert_eq!(stream.get_buffer().get_unacked(), 0);
+
+    stream.on_stream_frame_lost(0, 5, false);
+    assert!(stream.poll_stream_frame(1200, usize::MAX).is_none());
+    stream.on_reset_acked();
+    assert_eq!(stream.get_state(), SendState::ResetRecvd);
+    assert_eq!(stream.write(b"!"), Err(StreamError::Closed));
+}
+
+#[test]
+fn test_send_stream_reset_at_limit() {
+    let id = StreamId::nth(Side::Server, StreamDirection::Uni, 0);
+    let mut stream = SendStream::new(id, Side::Server);
+    stream.write(&[0; 100]).unwrap();
+    stream.poll_stream_frame(20, usize::MAX).unwrap();
+
+    // 可靠大小不超过流量控制允许发送的最大偏移量
+    assert!(stream.reset_at(7, 80, 50));
+    let frame = stream.poll_reset_stream_at_frame().unwrap();
+    assert_eq!(frame.get_reliable_size(), 50);
+    assert_eq!(frame.get_final_size(), 50);
+    assert_eq!(stream.get_final_size(), 50);
+}
+
+#[test]
+fn test_send_stream_stop_sending() {
+    let mut frame = StopSendingFrame::new();
+    frame.set_error_code(9);
+
+    // 对端发起的单向流上本端只能接收
+    let id = StreamId::nth(Side::Client, StreamDirection::Uni, 0);
+    frame.set_stream_id(id);
+    let mut stream = SendStream::new(id, Side::Server);
+    let err = stream.on_stop_sending_frame(&frame).unwrap_err();
+    assert_eq!(err.get_code(), TransportErrorCode::StreamStateError);
+
+    let id = StreamId::nth(Side::Client, StreamDirection::Bidi, 0);
+    frame.set_stream_id(id);
+    let mut stream = SendStream::new(id, Side::Server);
+    stream.on_stop_sending_frame(&frame).unwrap();
+    assert_eq!(stream.write(b"hello"), Err(StreamError::Stopped(9)));
+}
@@ -0,0 +1,22 @@
+use std::io::{self, Read};
+
+/// 读出指定长度的字节
+///
+/// 长度通常来自对端控制的长度字段, 因此只从已有的输入中逐步读取, 不会按长度预先分配缓冲区.
+///
+/// # Arguments
+/// `r` - 输入
+/// `len` - 需要读出的字节数
+/// # Returns
+/// 返回读出的字节; 剩余的输入不足 `len` 字节时返回错误
+pub(crate) fn read_bytes(r: &mut dyn Read, len: u64) -> Result<Vec<u8>, io::Error> {
+    let mut buf = Vec::new();
+    Read::take(r, len).read_to_end(&mut buf)?;
+    if buf.len() as u64 != len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "length exceeds remaining input",
+        ));
+    }
+    Ok(buf)
+}
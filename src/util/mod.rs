@@ -1,8 +1,14 @@
 mod byteorder;
+mod bytes;
+mod range_set;
 mod varint;
 
 pub(crate) use byteorder::{from_bigendian_bytes, to_bigendian_bytes};
-pub(crate) use varint::{read_varint, write_varint};
+pub(crate) use bytes::read_bytes;
+pub(crate) use range_set::RangeSet;
+pub(crate) use varint::{read_varint, varint_len, write_varint};
 
+#[cfg(test)]
+mod range_set_test;
 #[cfg(test)]
 mod varint_test;
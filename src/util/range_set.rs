@@ -0,0 +1,155 @@
+use std::{collections::BTreeMap, ops::Range};
+
+/// 区间集合
+///
+/// 以左闭右开区间保存一组互不重叠且互不相邻的整数区间, 插入时自动合并.
+#[derive(Clone, Default, Debug)]
+pub(crate) struct RangeSet {
+    /// 区间起点到区间终点的映射
+    ranges: BTreeMap<usize, usize>,
+}
+
+impl RangeSet {
+    /// 构造一个空的区间集合
+    ///
+    /// # Returns
+    /// 返回区间集合
+    pub(crate) fn new() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    /// 判断集合是否为空
+    ///
+    /// # Returns
+    /// 返回集合是否为空
+    #[inline(always)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// 获取集合中最小的区间
+    ///
+    /// # Returns
+    /// 返回最小的区间
+    pub(crate) fn first(&self) -> Option<Range<usize>> {
+        self.ranges
+            .first_key_value()
+            .map(|(&start, &end)| start..end)
+    }
+
+    /// 遍历集合中的区间
+    ///
+    /// # Returns
+    /// 返回按升序排列的区间
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = Range<usize>> + '_ {
+        self.ranges.iter().map(|(&start, &end)| start..end)
+    }
+
+    /// 判断集合是否包含某个值
+    ///
+    /// # Arguments
+    /// `value` - 值
+    ///
+    /// # Returns
+    /// 返回是否包含
+    pub(crate) fn contains(&self, value: usize) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, &end)| end > value)
+    }
+
+    /// 插入一个区间
+    ///
+    /// # Arguments
+    /// `range` - 区间
+    pub(crate) fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let mut start = range.start;
+        let mut end = range.end;
+
+        // 与前一个相交或相邻的区间合并
+        if let Some((&prev_start, &prev_end)) = self.ranges.range(..=start).next_back() {
+            if prev_end >= start {
+                start = prev_start;
+                end = end.max(prev_end);
+            }
+        }
+
+        // 合并被覆盖或相邻的后续区间
+        let covered = self
+            .ranges
+            .range(start..=end)
+            .map(|(&s, &e)| (s, e))
+            .collect::<Vec<_>>();
+        for (s, e) in covered {
+            self.ranges.remove(&s);
+            end = end.max(e);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    /// 移除一个区间
+    ///
+    /// # Arguments
+    /// `range` - 区间
+    pub(crate) fn remove(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let overlapped = self
+            .ranges
+            .range(..range.end)
+            .rev()
+            .take_while(|(_, &e)| e > range.start)
+            .map(|(&s, &e)| (s, e))
+            .collect::<Vec<_>>();
+
+        for (s, e) in overlapped {
+            self.ranges.remove(&s);
+            if s < range.start {
+                self.ranges.insert(s, range.start);
+            }
+            if e > range.end {
+                self.ranges.insert(range.end, e);
+            }
+        }
+    }
+
+    /// 求区间与集合的差集
+    ///
+    /// # Arguments
+    /// `range` - 区间
+    ///
+    /// # Returns
+    /// 返回区间中不被集合覆盖的部分
+    pub(crate) fn subtract(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut result = Vec::new();
+        let mut start = range.start;
+
+        for covered in self.iter() {
+            if covered.end <= start {
+                continue;
+            }
+            if covered.start >= range.end {
+                break;
+            }
+            if covered.start > start {
+                result.push(start..covered.start);
+            }
+            start = start.max(covered.end);
+        }
+        if start < range.end {
+            result.push(start..range.end);
+        }
+
+        result
+    }
+}
@@ -0,0 +1,23 @@
+use super::RangeSet;
+
+#[test]
+fn test_range_set() {
+    let mut set = RangeSet::new();
+    set.insert(10..20);
+    set.insert(30..40);
+    set.insert(20..25);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![10..25, 30..40]);
+
+    set.insert(5..35);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![5..40]);
+    assert!(set.contains(5));
+    assert!(!set.contains(40));
+
+    set.remove(12..15);
+    set.remove(38..50);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![5..12, 15..38]);
+    assert_eq!(set.subtract(0..20), vec![0..5, 12..15]);
+
+    set.remove(0..100);
+    assert!(set.is_empty());
+}
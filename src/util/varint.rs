@@ -41,10 +41,7 @@ pub(crate) fn read_varint(r: &mut dyn Read) -> Result<Varint, io::Error> {
                 size: 8,
             })
         }
-        _ => Err(io::Error::new(
-            io::ErrorKind::Other,
-            "unexcepted variable-length 2MSB",
-        )),
+        _ => Err(io::Error::other("unexcepted variable-length 2MSB")),
     }
 }
 
@@ -84,3 +81,19 @@ pub(crate) fn write_varint(n: u64, w: &mut dyn Write) -> Result<usize, io::Error
         )),
     }
 }
+
+/// 获取数值编码为变长整数后的字节数
+///
+/// # Arguments
+/// `n` - 数值
+///
+/// # Returns
+/// 返回编码后的字节数
+pub(crate) const fn varint_len(n: u64) -> usize {
+    match n {
+        0..=63 => 1,
+        64..=16383 => 2,
+        16384..=1073741823 => 4,
+        _ => 8,
+    }
+}